@@ -6,6 +6,9 @@ mod bench {
     extern crate image;
     extern crate test;
 
+    use bardecoder::encode::{render, QREncoder};
+    use bardecoder::ECLevel;
+
     use image::DynamicImage;
 
     use self::test::Bencher;
@@ -28,6 +31,29 @@ mod bench {
         bench_image(&img, b);
     }
 
+    #[bench]
+    pub fn version40_high_with_errors(b: &mut Bencher) {
+        let text: String = (0..1250)
+            .map(|i| (b'a' + (i * 7 % 26) as u8) as char)
+            .collect();
+        let data = QREncoder::new(ECLevel::HIGH).encode(&text).unwrap();
+        let mut img = render(&data, 3, 4);
+
+        // Flip a scattering of modules inside the symbol so most blocks need correcting
+        for y in 20..data.side - 20 {
+            for x in 20..data.side - 20 {
+                if (7 * x + 13 * y) % 61 == 0 {
+                    for (i, j) in (0..3).flat_map(|i| (0..3).map(move |j| (i, j))) {
+                        let pixel = img.get_pixel_mut(3 * (x + 4) + i, 3 * (y + 4) + j);
+                        pixel.0[0] = 255 - pixel.0[0];
+                    }
+                }
+            }
+        }
+
+        bench_image(&DynamicImage::ImageLuma8(img), b);
+    }
+
     pub fn bench_image(image: &DynamicImage, b: &mut Bencher) {
         let decoder = bardecoder::default_decoder();
 
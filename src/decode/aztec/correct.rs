@@ -0,0 +1,282 @@
+use crate::util::aztec::AztecError;
+
+/// Galois field used by Aztec Codes, the size depends on the codeword size
+pub struct Field {
+    size: usize,
+    exp: Vec<u32>,
+    log: Vec<u32>,
+}
+
+impl Field {
+    fn new(bits: u32, primitive: u32) -> Field {
+        let size = 1 << bits;
+        let mut exp = vec![0; 2 * size];
+        let mut log = vec![0; size];
+
+        let mut x = 1;
+        for (i, e) in exp.iter_mut().enumerate() {
+            *e = x;
+
+            if i < size - 1 {
+                log[x as usize] = i as u32;
+            }
+
+            x <<= 1;
+            if x as usize >= size {
+                x ^= primitive;
+            }
+        }
+
+        Field { size, exp, log }
+    }
+
+    /// GF(16), used for the mode message
+    pub fn gf16() -> Field {
+        Field::new(4, 0b1_0011)
+    }
+
+    /// Field for the codewords of an Aztec Code with the provided number of layers
+    pub fn for_layers(layers: u32) -> Field {
+        match layers {
+            0..=2 => Field::new(6, 0b100_0011),
+            3..=8 => Field::new(8, 0b1_0010_1101),
+            9..=22 => Field::new(10, 0b100_0000_1001),
+            _ => Field::new(12, 0b1_0000_0110_1001),
+        }
+    }
+
+    /// Number of bits per codeword
+    pub fn bits(&self) -> u32 {
+        self.size.trailing_zeros()
+    }
+
+    fn order(&self) -> usize {
+        self.size - 1
+    }
+
+    fn alpha(&self, power: usize) -> u32 {
+        self.exp[power % self.order()]
+    }
+
+    fn mul(&self, a: u32, b: u32) -> u32 {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+
+        self.exp[(self.log[a as usize] + self.log[b as usize]) as usize]
+    }
+
+    fn div(&self, a: u32, b: u32) -> u32 {
+        if a == 0 {
+            return 0;
+        }
+
+        self.exp[self.log[a as usize] as usize + self.order() - self.log[b as usize] as usize]
+    }
+
+    // Evaluate a polynomial with the lowest degree coefficient first
+    fn eval(&self, poly: &[u32], x: u32) -> u32 {
+        poly.iter().rev().fold(0, |acc, c| self.mul(acc, x) ^ c)
+    }
+}
+
+/// Correct a block of codewords, data codewords first, followed by `ec` error correction codewords
+///
+/// Returns the corrected block and the number of corrected codewords
+pub fn correct(
+    field: &Field,
+    mut block: Vec<u32>,
+    ec: usize,
+) -> Result<(Vec<u32>, usize), AztecError> {
+    let n = block.len();
+
+    if n > field.order() {
+        return Err(AztecError {
+            msg: format!("Too many codewords {} for field of size {}", n, field.size),
+        });
+    }
+
+    // The generator polynomial has roots a^1 to a^ec
+    let syndromes: Vec<u32> = (1..=ec)
+        .map(|i| {
+            block
+                .iter()
+                .fold(0, |acc, c| field.mul(acc, field.alpha(i)) ^ c)
+        })
+        .collect();
+
+    if syndromes.iter().all(|s| *s == 0) {
+        debug!("ALL SYNDROMES WERE ZERO, NO CORRECTION NEEDED");
+        return Ok((block, 0));
+    }
+
+    // Berlekamp-Massey to find the error locator polynomial
+    let mut sigma = vec![1];
+    let mut previous = vec![1];
+    let mut errors = 0;
+    let mut shift = 1;
+    let mut b = 1;
+
+    for i in 0..ec {
+        let discrepancy = (1..=errors.min(sigma.len() - 1)).fold(syndromes[i], |acc, j| {
+            acc ^ field.mul(sigma[j], syndromes[i - j])
+        });
+
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+
+        let factor = field.div(discrepancy, b);
+        let mut next = sigma.clone();
+        next.resize(next.len().max(previous.len() + shift), 0);
+
+        for (j, p) in previous.iter().enumerate() {
+            next[j + shift] ^= field.mul(factor, *p);
+        }
+
+        if 2 * errors <= i {
+            errors = i + 1 - errors;
+            previous = sigma;
+            b = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+
+        sigma = next;
+    }
+
+    sigma.resize(errors + 1, 0);
+
+    if errors == 0 || 2 * errors > ec {
+        return Err(AztecError {
+            msg: String::from("Too many errors to correct"),
+        });
+    }
+
+    // Error evaluator polynomial, omega = syndromes * sigma mod x^ec
+    let omega: Vec<u32> = (0..ec)
+        .map(|i| (0..=i.min(errors)).fold(0, |acc, j| acc ^ field.mul(sigma[j], syndromes[i - j])))
+        .collect();
+
+    // Formal derivative of sigma, only the odd powers remain
+    let derivative: Vec<u32> = (1..sigma.len())
+        .map(|i| match i % 2 {
+            1 => sigma[i],
+            _ => 0,
+        })
+        .collect();
+
+    // Chien search for the roots of sigma, which are the inverses of the error locations
+    let mut corrected = 0;
+
+    for position in 0..n {
+        let x_inv = field.alpha(field.order() - position);
+
+        if field.eval(&sigma, x_inv) != 0 {
+            continue;
+        }
+
+        // Forney, the generator starts at a^1 so no additional factor is needed
+        let magnitude = field.div(field.eval(&omega, x_inv), field.eval(&derivative, x_inv));
+
+        debug!(
+            "FIXING LOCATION {} FROM {:b} TO {:b}",
+            n - 1 - position,
+            block[n - 1 - position],
+            block[n - 1 - position] ^ magnitude
+        );
+
+        block[n - 1 - position] ^= magnitude;
+        corrected += 1;
+    }
+
+    if corrected != errors {
+        return Err(AztecError {
+            msg: String::from("Error correcting did not fix corrupted data"),
+        });
+    }
+
+    Ok((block, corrected))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Systematic encoding, only used to construct test blocks
+    fn encode(field: &Field, data: &[u32], ec: usize) -> Vec<u32> {
+        let mut generator = vec![1];
+        for i in 1..=ec {
+            let mut next = vec![0; generator.len() + 1];
+            for (j, g) in generator.iter().enumerate() {
+                next[j] ^= g;
+                next[j + 1] ^= field.mul(*g, field.alpha(i));
+            }
+            generator = next;
+        }
+
+        let mut remainder = data.to_vec();
+        remainder.resize(data.len() + ec, 0);
+
+        for i in 0..data.len() {
+            let c = remainder[i];
+            for (j, g) in generator.iter().enumerate().skip(1) {
+                remainder[i + j] ^= field.mul(*g, c);
+            }
+        }
+
+        let mut block = data.to_vec();
+        block.extend(&remainder[data.len()..]);
+        block
+    }
+
+    #[test]
+    pub fn test_fields() {
+        for layers in &[1, 4, 12, 32] {
+            let field = Field::for_layers(*layers);
+
+            // Every non-zero element is a power of the primitive element exactly once
+            let mut seen = vec![false; field.size];
+            for i in 0..field.order() {
+                assert!(!seen[field.alpha(i) as usize]);
+                seen[field.alpha(i) as usize] = true;
+            }
+        }
+
+        assert_eq!(4, Field::gf16().bits());
+        assert_eq!(6, Field::for_layers(2).bits());
+        assert_eq!(12, Field::for_layers(23).bits());
+    }
+
+    #[test]
+    pub fn test_correct() {
+        // Mode message of a compact Aztec Code with 1 layer and 4 data codewords
+        let field = Field::gf16();
+        let block = encode(&field, &[0, 3], 5);
+
+        assert_eq!(Ok((block.clone(), 0)), correct(&field, block.clone(), 5));
+
+        let mut corrupted = block.clone();
+        corrupted[1] = 12;
+        corrupted[5] ^= 7;
+        assert_eq!(Ok((block.clone(), 2)), correct(&field, corrupted, 5));
+
+        let field = Field::for_layers(9);
+        let data: Vec<u32> = (1..100).collect();
+        let block = encode(&field, &data, 20);
+
+        let mut corrupted = block.clone();
+        for i in 0..10 {
+            corrupted[i * 11] ^= 1000 - i as u32;
+        }
+        assert_eq!(Ok((block.clone(), 10)), correct(&field, corrupted, 20));
+
+        let mut corrupted = block.clone();
+        for i in 0..11 {
+            corrupted[i * 11] ^= 1000 - i as u32;
+        }
+        assert!(correct(&field, corrupted, 20).is_err());
+    }
+}
@@ -0,0 +1,310 @@
+use super::super::qr::eci::Charset;
+
+use crate::util::aztec::AztecError;
+
+const MIXED: [u8; 26] = [
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 27, 28, 29, 30, 31, b'@', b'\\', b'^', b'_', b'`',
+    b'|', b'~', 127,
+];
+
+const PUNCT: [&[u8]; 30] = [
+    b"\r", b"\r\n", b". ", b", ", b": ", b"!", b"\"", b"#", b"$", b"%", b"&", b"'", b"(", b")",
+    b"*", b"+", b",", b"-", b".", b"/", b":", b";", b"<", b"=", b">", b"?", b"[", b"]", b"{", b"}",
+];
+
+const GS: u8 = 0x1D;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Table {
+    Upper,
+    Lower,
+    Mixed,
+    Punct,
+    Digit,
+    Binary,
+}
+
+enum Code {
+    Text(&'static [u8]),
+    Byte(u8),
+    Shift(Table),
+    Latch(Table),
+    Flag,
+}
+
+fn code(table: Table, value: u32) -> Code {
+    // Common to all tables except Punct
+    match (table, value) {
+        (Table::Punct, _) | (Table::Binary, _) => {}
+        (_, 0) => return Code::Shift(Table::Punct),
+        (_, 1) => return Code::Text(b" "),
+        _ => {}
+    }
+
+    match (table, value) {
+        (Table::Upper, 2..=27) => Code::Byte(b'A' + value as u8 - 2),
+        (Table::Upper, 28) => Code::Latch(Table::Lower),
+        (Table::Upper, 29) => Code::Latch(Table::Mixed),
+        (Table::Upper, 30) => Code::Latch(Table::Digit),
+        (Table::Lower, 2..=27) => Code::Byte(b'a' + value as u8 - 2),
+        (Table::Lower, 28) => Code::Shift(Table::Upper),
+        (Table::Lower, 29) => Code::Latch(Table::Mixed),
+        (Table::Lower, 30) => Code::Latch(Table::Digit),
+        (Table::Mixed, 2..=27) => Code::Byte(MIXED[value as usize - 2]),
+        (Table::Mixed, 28) => Code::Latch(Table::Lower),
+        (Table::Mixed, 29) => Code::Latch(Table::Upper),
+        (Table::Mixed, 30) => Code::Latch(Table::Punct),
+        (Table::Upper, 31) | (Table::Lower, 31) | (Table::Mixed, 31) => Code::Shift(Table::Binary),
+        (Table::Punct, 0) => Code::Flag,
+        (Table::Punct, 1..=30) => Code::Text(PUNCT[value as usize - 1]),
+        (Table::Punct, _) => Code::Latch(Table::Upper),
+        (Table::Digit, 2..=11) => Code::Byte(b'0' + value as u8 - 2),
+        (Table::Digit, 12) => Code::Text(b","),
+        (Table::Digit, 13) => Code::Text(b"."),
+        (Table::Digit, 14) => Code::Latch(Table::Upper),
+        (Table::Digit, _) => Code::Shift(Table::Upper),
+        (_, _) => unreachable!(),
+    }
+}
+
+struct Bits {
+    bits: Vec<bool>,
+    pos: usize,
+}
+
+impl Bits {
+    fn remaining(&self) -> usize {
+        self.bits.len().saturating_sub(self.pos)
+    }
+
+    // Read a number of bits, or None if not enough bits are remaining
+    fn read(&mut self, count: usize) -> Option<u32> {
+        if self.remaining() < count {
+            return None;
+        }
+
+        let value = self.bits[self.pos..self.pos + count]
+            .iter()
+            .fold(0, |acc, bit| (acc << 1) | u32::from(*bit));
+        self.pos += count;
+
+        Some(value)
+    }
+}
+
+// Decoded bytes, split up every time an ECI designator selects a different character set
+struct Output {
+    segments: Vec<(Option<Charset>, Vec<u8>)>,
+}
+
+impl Output {
+    fn push(&mut self, bytes: &[u8]) {
+        self.segments.last_mut().unwrap().1.extend(bytes);
+    }
+
+    fn text(self) -> Result<String, AztecError> {
+        let mut text = String::new();
+
+        for (charset, bytes) in self.segments {
+            // The default character set for Aztec is ISO 8859-1
+            let charset = charset.unwrap_or(Charset::Iso88591);
+            let segment = charset
+                .decode(&bytes)
+                .map_err(|e| AztecError { msg: e.msg })?;
+
+            text.push_str(&segment);
+        }
+
+        Ok(text)
+    }
+}
+
+/// Decode the unstuffed data bits of an Aztec Code
+///
+/// Any bits left at the end that are too short to form a complete character are ignored
+pub fn data(bits: Vec<bool>) -> Result<String, AztecError> {
+    let mut bits = Bits { bits, pos: 0 };
+    let mut output = Output {
+        segments: vec![(None, vec![])],
+    };
+
+    let mut latch = Table::Upper;
+    let mut shift = Table::Upper;
+
+    loop {
+        if shift == Table::Binary {
+            let length = match bits.read(5) {
+                Some(0) => match bits.read(11) {
+                    Some(length) => length + 31,
+                    None => break,
+                },
+                Some(length) => length,
+                None => break,
+            };
+
+            for _ in 0..length {
+                match bits.read(8) {
+                    Some(byte) => output.push(&[byte as u8]),
+                    None => break,
+                }
+            }
+
+            shift = latch;
+            continue;
+        }
+
+        let size = if shift == Table::Digit { 4 } else { 5 };
+        let value = match bits.read(size) {
+            Some(value) => value,
+            None => break,
+        };
+
+        trace!("AZTEC {:?} {}", shift, value);
+
+        match code(shift, value) {
+            Code::Text(text) => {
+                output.push(text);
+                shift = latch;
+            }
+            Code::Byte(byte) => {
+                output.push(&[byte]);
+                shift = latch;
+            }
+            Code::Flag => {
+                let digits = match bits.read(3) {
+                    Some(digits) => digits,
+                    None => break,
+                };
+
+                match digits {
+                    // FNC1
+                    0 => output.push(&[GS]),
+                    7 => {
+                        return Err(AztecError {
+                            msg: String::from("Reserved FLG(7) in Aztec data"),
+                        })
+                    }
+                    _ => {
+                        let eci = eci_designator(&mut bits, digits)?;
+                        let charset =
+                            Charset::from_eci(eci).map_err(|e| AztecError { msg: e.msg })?;
+
+                        output.segments.push((Some(charset), vec![]));
+                    }
+                }
+
+                shift = latch;
+            }
+            Code::Shift(table) => {
+                // A shift returns to the table it was invoked from, even if that was itself a shift
+                latch = shift;
+                shift = table;
+            }
+            Code::Latch(table) => {
+                latch = table;
+                shift = table;
+            }
+        }
+    }
+
+    output.text()
+}
+
+// ECI designators are encoded as a number of digits from the Digit table
+fn eci_designator(bits: &mut Bits, digits: u32) -> Result<u32, AztecError> {
+    let mut eci = 0;
+
+    for _ in 0..digits {
+        match bits.read(4) {
+            Some(digit @ 2..=11) => eci = eci * 10 + digit - 2,
+            _ => {
+                return Err(AztecError {
+                    msg: String::from("Invalid ECI designator in Aztec data"),
+                })
+            }
+        }
+    }
+
+    Ok(eci)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bits(words: &[(u32, usize)]) -> Vec<bool> {
+        words
+            .iter()
+            .flat_map(|(value, size)| (0..*size).rev().map(move |i| (value >> i) & 1 == 1))
+            .collect()
+    }
+
+    #[test]
+    pub fn test_upper_lower() {
+        // "A b", then padding
+        let input = bits(&[(2, 5), (1, 5), (28, 5), (3, 5), (0b111, 3)]);
+        assert_eq!(Ok(String::from("A b")), data(input));
+
+        // Upper shift from Lower
+        let input = bits(&[(28, 5), (2, 5), (28, 5), (2, 5), (2, 5)]);
+        assert_eq!(Ok(String::from("aAa")), data(input));
+    }
+
+    #[test]
+    pub fn test_digit_punct() {
+        // "12.5!", latch back to Upper, "Z"
+        let input = bits(&[
+            (30, 5),
+            (3, 4),
+            (4, 4),
+            (13, 4),
+            (7, 4),
+            (0, 4),
+            (6, 5),
+            (14, 4),
+            (27, 5),
+        ]);
+        assert_eq!(Ok(String::from("12.5!Z")), data(input));
+    }
+
+    #[test]
+    pub fn test_mixed() {
+        // "@\r" then latch to Punct and ", "
+        let input = bits(&[(29, 5), (20, 5), (14, 5), (30, 5), (4, 5)]);
+        assert_eq!(Ok(String::from("@\r, ")), data(input));
+    }
+
+    #[test]
+    pub fn test_binary() {
+        // Binary shift of 2 bytes, back to Upper for "A"
+        let input = bits(&[(31, 5), (2, 5), (0xC3, 8), (0xA9, 8), (2, 5)]);
+        assert_eq!(Ok(String::from("\u{C3}\u{A9}A")), data(input));
+
+        // Long binary shift
+        let mut words = vec![(31, 5), (0, 5), (1, 11)];
+        words.extend(vec![(b'x' as u32, 8); 32]);
+        assert_eq!(Ok("x".repeat(32)), data(bits(&words)));
+    }
+
+    #[test]
+    pub fn test_flg() {
+        // FNC1 between "A" and "B", then ECI 26 (UTF-8) and "é" as binary
+        let input = bits(&[
+            (2, 5),
+            (0, 5),
+            (0, 5),
+            (0, 3),
+            (3, 5),
+            (0, 5),
+            (0, 5),
+            (2, 3),
+            (4, 4),
+            (8, 4),
+            (31, 5),
+            (2, 5),
+            (0xC3, 8),
+            (0xA9, 8),
+        ]);
+        assert_eq!(Ok(String::from("A\u{1D}Bé")), data(input));
+    }
+}
@@ -0,0 +1,143 @@
+use super::super::Decode;
+use super::correct::{correct, Field};
+
+use crate::util::aztec::{AztecData, AztecError};
+
+/// Decode an Aztec Code into a resulting String
+///
+/// This decoder will, in order:
+/// * Read the bits from the data layers, spiraling inwards from the outermost layer
+/// * Perform error correction, using the Galois field selected by the number of layers
+/// * Remove the stuffed bits from the data codewords
+/// * Decode the bits into a String
+pub struct AztecDecoder {}
+
+impl AztecDecoder {
+    /// Construct a new AztecDecoder
+    pub fn new() -> AztecDecoder {
+        AztecDecoder {}
+    }
+}
+
+impl Decode<AztecData, String, AztecError> for AztecDecoder {
+    fn decode(&self, data: Result<AztecData, AztecError>) -> Result<String, AztecError> {
+        let aztec_data = data?;
+
+        let raw = raw_bits(&aztec_data);
+
+        let field = Field::for_layers(aztec_data.layers);
+        let bits = field.bits() as usize;
+
+        // Any bits that do not fill up a complete codeword are at the start
+        let codewords: Vec<u32> = raw[raw.len() % bits..]
+            .chunks(bits)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0, |acc, bit| (acc << 1) | u32::from(*bit))
+            })
+            .collect();
+
+        let data_codewords = aztec_data.data_codewords as usize;
+
+        if data_codewords >= codewords.len() {
+            return Err(AztecError {
+                msg: format!(
+                    "Too many data codewords {} for {} codewords in total",
+                    data_codewords,
+                    codewords.len()
+                ),
+            });
+        }
+
+        let ec = codewords.len() - data_codewords;
+        let (corrected, errors) = correct(&field, codewords, ec)?;
+
+        debug!("CORRECTED {} ERRORS", errors);
+
+        let unstuffed = unstuff(&corrected[..data_codewords], bits)?;
+
+        super::data::data(unstuffed)
+    }
+}
+
+// Read the data bits in order, starting at the outermost layer
+fn raw_bits(aztec_data: &AztecData) -> Vec<bool> {
+    let compact = aztec_data.compact;
+    let layers = aztec_data.layers;
+
+    // Without the reference grid, which is only present in full-range Aztec Codes
+    let base = if compact { 11 } else { 14 } + 4 * layers;
+    let alignment = alignment_map(compact, base, aztec_data.size);
+
+    let mut raw = vec![];
+
+    for layer in 0..layers {
+        let row_size = (layers - layer) * 4 + if compact { 9 } else { 12 };
+        let low = layer * 2;
+        let high = base - 1 - low;
+
+        let mut sides = vec![vec![]; 4];
+
+        for j in 0..row_size {
+            for k in 0..2 {
+                let pixel = |x: u32, y: u32| {
+                    aztec_data[[alignment[x as usize], alignment[y as usize]]] == 1
+                };
+
+                // Left side going down, bottom going right, right side going up and top going left
+                sides[0].push(pixel(low + k, low + j));
+                sides[1].push(pixel(low + j, high - k));
+                sides[2].push(pixel(high - k, high - j));
+                sides[3].push(pixel(high - j, low + k));
+            }
+        }
+
+        for side in sides {
+            raw.extend(side);
+        }
+    }
+
+    raw
+}
+
+// Map the coordinates of the symbol without reference grid to the actual coordinates
+fn alignment_map(compact: bool, base: u32, size: u32) -> Vec<u32> {
+    if compact {
+        return (0..base).collect();
+    }
+
+    let mut alignment = vec![0; base as usize];
+    let base_center = base / 2;
+    let center = size / 2;
+
+    for i in 0..base_center {
+        let offset = i + i / 15;
+        alignment[(base_center - i - 1) as usize] = center - offset - 1;
+        alignment[(base_center + i) as usize] = center + offset + 1;
+    }
+
+    alignment
+}
+
+// Codewords with all bits but the last one the same have a stuffed bit, which needs to be removed
+fn unstuff(codewords: &[u32], bits: usize) -> Result<Vec<bool>, AztecError> {
+    let mask = (1 << bits) - 1;
+    let mut unstuffed = vec![];
+
+    for codeword in codewords {
+        if *codeword == 0 || *codeword == mask {
+            return Err(AztecError {
+                msg: format!("Invalid codeword {:b}", codeword),
+            });
+        }
+
+        if *codeword == 1 || *codeword == mask - 1 {
+            unstuffed.extend(vec![*codeword > 1; bits - 1]);
+        } else {
+            unstuffed.extend((0..bits).rev().map(|i| (codeword >> i) & 1 == 1));
+        }
+    }
+
+    Ok(unstuffed)
+}
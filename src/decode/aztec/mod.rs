@@ -0,0 +1,4 @@
+pub mod correct;
+pub mod data;
+pub mod decoder;
+pub mod mode;
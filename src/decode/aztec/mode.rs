@@ -0,0 +1,48 @@
+use super::correct::{correct, Field};
+
+use crate::util::aztec::AztecError;
+
+/// Decode the mode message read clockwise from the top left corner of the ring around the bullseye
+///
+/// Compact Aztec Codes have a mode message of 28 bits, full-range Aztec Codes one of 40 bits.
+/// Returns the number of layers and the number of data codewords
+pub fn mode_message(bits: u64, compact: bool) -> Result<(u32, u32), AztecError> {
+    let (words, data_words) = if compact { (7, 2) } else { (10, 4) };
+
+    let block: Vec<u32> = (0..words)
+        .map(|i| ((bits >> (4 * (words - 1 - i))) & 0b1111) as u32)
+        .collect();
+
+    let (corrected, _) = correct(&Field::gf16(), block, words - data_words)?;
+
+    let value = corrected
+        .iter()
+        .take(data_words)
+        .fold(0, |acc, word| (acc << 4) | word);
+
+    if compact {
+        Ok(((value >> 6) + 1, (value & 0b11_1111) + 1))
+    } else {
+        Ok(((value >> 11) + 1, (value & 0b111_1111_1111) + 1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_mode_message() {
+        // Compact, 1 layer and 4 data codewords
+        let bits = 0b0000_0011_1110_1100_1010_0110_0011;
+        assert_eq!(Ok((1, 4)), mode_message(bits, true));
+
+        // Up to two corrupted words can be corrected
+        assert_eq!(Ok((1, 4)), mode_message(bits ^ (0b1111 << 20), true));
+        assert_eq!(
+            Ok((1, 4)),
+            mode_message(bits ^ (0b1111 << 20) ^ 0b0110, true)
+        );
+        assert!(mode_message(bits ^ (0b1111 << 20) ^ (0b1001 << 12) ^ 0b0110, true).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+use super::super::linear::{either_direction, narrow_wide};
+use super::super::Decode;
+
+use crate::util::linear::{LinearData, LinearError, LinearSymbology};
+
+// The last four characters are the start and stop characters
+const ALPHABET: &[u8; 20] = b"0123456789-$:/.+ABCD";
+
+// Narrow and wide runs of the characters in the alphabet, with 1 for wide
+const PATTERNS: [u32; 20] = [
+    0x003, 0x006, 0x009, 0x060, 0x012, 0x042, 0x021, 0x024, 0x030, 0x048, 0x00C, 0x018, 0x045,
+    0x051, 0x054, 0x015, 0x01A, 0x029, 0x00B, 0x00E,
+];
+
+// Value of the first start and stop character
+const START_STOP: usize = 16;
+
+/// Decode a Codabar barcode into a resulting String
+///
+/// The start and stop characters are not included in the result
+///
+/// This decoder will, in order:
+/// * Classify the bars and spaces of every character as narrow or wide, reading the barcode from right to left if it is upside down
+/// * Validate and remove the modulo 16 check character, if enabled
+///
+/// Since the check character is optional and cannot be recognized from the barcode itself, it needs to be enabled explicitly when it is used
+pub struct CodabarDecoder {
+    check_character: bool,
+}
+
+impl CodabarDecoder {
+    /// Construct a new CodabarDecoder
+    ///
+    /// * check_character: the last character before the stop character is a modulo 16 check character
+    pub fn new(check_character: bool) -> CodabarDecoder {
+        CodabarDecoder { check_character }
+    }
+}
+
+impl Decode<LinearData, String, LinearError> for CodabarDecoder {
+    fn decode(&self, data: Result<LinearData, LinearError>) -> Result<String, LinearError> {
+        let linear_data = data?;
+
+        if linear_data.symbology != LinearSymbology::Codabar {
+            return Err(LinearError {
+                msg: format!("Cannot decode {:?} as Codabar", linear_data.symbology),
+            });
+        }
+
+        // An upside down barcode is scanned from right to left
+        let values = either_direction(&linear_data.runs, characters)?;
+
+        debug!("CODABAR VALUES {:?}", values);
+
+        let mut data = &values[1..values.len() - 1];
+
+        if self.check_character {
+            // The sum of all values, including the start and stop characters, is a multiple of 16
+            let sum: usize = values.iter().sum();
+
+            // There must be at least one character besides the check character
+            let valid = match sum % 16 {
+                0 => data.len() >= 2,
+                _ => false,
+            };
+
+            if !valid {
+                return Err(LinearError {
+                    msg: format!(
+                        "Invalid check character {}",
+                        char::from(ALPHABET[data[data.len() - 1]])
+                    ),
+                });
+            }
+
+            data = &data[..data.len() - 1];
+        }
+
+        Ok(data.iter().map(|v| char::from(ALPHABET[*v])).collect())
+    }
+}
+
+// Read the values of the characters in the alphabet, including the start and stop characters
+fn characters(runs: &[u32]) -> Result<Vec<usize>, LinearError> {
+    // Characters of 7 runs separated by a space, at least one besides the start and stop characters
+    let valid = match (runs.len() + 1) % 8 {
+        0 => runs.len() >= 23,
+        _ => false,
+    };
+
+    if !valid {
+        return Err(LinearError {
+            msg: format!("Unexpected number of bars and spaces {}", runs.len()),
+        });
+    }
+
+    let values = runs
+        .chunks(8)
+        .enumerate()
+        .map(|(i, chunk)| {
+            narrow_wide(&chunk[..7])
+                .and_then(|pattern| PATTERNS.iter().position(|p| *p == pattern))
+                .ok_or(LinearError {
+                    msg: format!("Invalid character pattern {:?} at {}", &chunk[..7], i),
+                })
+        })
+        .collect::<Result<Vec<usize>, LinearError>>()?;
+
+    let guards = |v: &usize| *v >= START_STOP;
+
+    if !guards(&values[0])
+        || !guards(&values[values.len() - 1])
+        || values[1..values.len() - 1].iter().any(guards)
+    {
+        return Err(LinearError {
+            msg: String::from("Missing start or stop character"),
+        });
+    }
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Widths of the runs for the text, including the start and stop characters
+    fn runs(text: &str) -> Vec<u32> {
+        let mut runs = vec![];
+
+        for c in text.bytes() {
+            let pattern = PATTERNS[ALPHABET.iter().position(|a| *a == c).unwrap()];
+
+            if !runs.is_empty() {
+                runs.push(2);
+            }
+            runs.extend((0..7).rev().map(|i| match (pattern >> i) & 1 {
+                1 => 5,
+                _ => 2,
+            }));
+        }
+
+        runs
+    }
+
+    #[test]
+    pub fn test_decode() {
+        let decode = |runs, check| {
+            CodabarDecoder::new(check).decode(Ok(LinearData::new(LinearSymbology::Codabar, runs)))
+        };
+
+        assert_eq!(Ok(String::from("40156")), decode(runs("A40156B"), false));
+
+        // Check character, A + 4 + 0 + 1 + 5 + 6 + B = 16 + 16 + 17 = 49, so 15 or + is needed
+        assert_eq!(Ok(String::from("40156")), decode(runs("A40156+B"), true));
+        assert!(decode(runs("A40156-B"), true).is_err());
+
+        let mut reversed = runs("C$12.50D");
+        reversed.reverse();
+        assert_eq!(Ok(String::from("$12.50")), decode(reversed, false));
+
+        // Stop character in the data
+        assert!(decode(runs("A12B34C"), false).is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use super::symbols::{START_A, START_B, START_C};
+
+use crate::util::gs1::GROUP_SEPARATOR;
+use crate::util::linear::LinearError;
+
+const FNC1: u8 = 102;
+const FNC2: u8 = 97;
+const FNC3: u8 = 96;
+const SHIFT: u8 = 98;
+const CODE_C: u8 = 99;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CodeSet {
+    A,
+    B,
+    C,
+}
+
+/// Decode the values of the symbols, starting with the start symbol and excluding the check symbol
+///
+/// Returns the decoded text, with FNC1 as the ASCII Group Separator, and whether the data is GS1 data as indicated by an FNC1 in the first position
+pub fn data(values: &[u8]) -> Result<(String, bool), LinearError> {
+    let (start, values) = values.split_first().ok_or(LinearError {
+        msg: String::from("Missing start symbol"),
+    })?;
+
+    let mut set = match *start {
+        START_A => CodeSet::A,
+        START_B => CodeSet::B,
+        START_C => CodeSet::C,
+        _ => {
+            return Err(LinearError {
+                msg: format!("Invalid start symbol {}", start),
+            })
+        }
+    };
+
+    let gs1 = values.first() == Some(&FNC1);
+
+    let mut result = String::new();
+
+    // Set by a single FNC4, which adds 128 to the next character
+    let mut extended_shift = false;
+
+    // Toggled by two consecutive FNC4, which adds 128 to all following characters
+    let mut extended_latch = false;
+
+    // Set by a SHIFT, which switches between A and B for the next character
+    let mut shift = false;
+
+    let mut i = 0;
+
+    while i < values.len() {
+        let value = values[i];
+        i += 1;
+
+        let current = match (set, shift) {
+            (CodeSet::A, true) => CodeSet::B,
+            (CodeSet::B, true) => CodeSet::A,
+            _ => set,
+        };
+        shift = false;
+
+        if value > FNC1 {
+            return Err(LinearError {
+                msg: format!("Unexpected start symbol {} in data", value),
+            });
+        }
+
+        if current == CodeSet::C {
+            match value {
+                0..=99 => result.push_str(&format!("{:02}", value)),
+                100 => set = CodeSet::B,
+                101 => set = CodeSet::A,
+                _ => {
+                    // FNC1 in the first position indicates GS1 data and is not output
+                    if i > 1 {
+                        result.push(GROUP_SEPARATOR);
+                    }
+                }
+            }
+
+            continue;
+        }
+
+        let fnc4 = match current {
+            CodeSet::A => 101,
+            _ => 100,
+        };
+
+        match value {
+            FNC1 => {
+                if i > 1 {
+                    result.push(GROUP_SEPARATOR);
+                }
+            }
+            FNC2 | FNC3 => {
+                // Message append and reader initialisation are not supported, ignore
+            }
+            SHIFT => shift = true,
+            CODE_C => set = CodeSet::C,
+            v if v == fnc4 => {
+                if values.get(i) == Some(&fnc4) {
+                    extended_latch = !extended_latch;
+                    i += 1;
+                } else {
+                    extended_shift = true;
+                }
+            }
+            100 | 101 => {
+                set = match current {
+                    CodeSet::A => CodeSet::B,
+                    _ => CodeSet::A,
+                }
+            }
+            _ => {
+                let mut c = match current {
+                    CodeSet::A if value >= 64 => value - 64,
+                    _ => value + 32,
+                };
+
+                if extended_latch != extended_shift {
+                    c += 128;
+                }
+                extended_shift = false;
+
+                result.push(char::from(c));
+            }
+        }
+    }
+
+    Ok((result, gs1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_code_sets() {
+        // "Hi" in B
+        assert_eq!(Ok((String::from("Hi"), false)), data(&[START_B, 40, 73]));
+
+        // "A\n" in A
+        assert_eq!(Ok((String::from("A\n"), false)), data(&[START_A, 33, 74]));
+
+        // "123456" in C, switching to B for "a"
+        assert_eq!(
+            Ok((String::from("123456a"), false)),
+            data(&[START_C, 12, 34, 56, 100, 65])
+        );
+
+        // Shift to A for a single "\t" in B
+        assert_eq!(
+            Ok((String::from("a\tb"), false)),
+            data(&[START_B, 65, SHIFT, 73, 66])
+        );
+    }
+
+    #[test]
+    pub fn test_extended() {
+        // FNC4 in B shifts a single character, "é" is 0xE9 or 0x69 + 128
+        assert_eq!(
+            Ok((String::from("éi"), false)),
+            data(&[START_B, 100, 73, 73])
+        );
+
+        // Two FNC4 latch until the next two FNC4
+        assert_eq!(
+            Ok((String::from("ééi"), false)),
+            data(&[START_B, 100, 100, 73, 73, 100, 100, 73])
+        );
+    }
+
+    #[test]
+    pub fn test_gs1() {
+        // FNC1 01 12345678901231 FNC1 10 AB
+        assert_eq!(
+            Ok((String::from("0112345678901231\u{1D}10AB"), true)),
+            data(&[START_C, FNC1, 1, 12, 34, 56, 78, 90, 12, 31, FNC1, 10, 100, 33, 34])
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use super::super::linear::either_direction;
+use super::super::Decode;
+use super::data::data;
+use super::symbols::{symbols, verify};
+
+use crate::util::gs1::parse;
+use crate::util::linear::{LinearData, LinearError, LinearInfo, LinearSymbology};
+
+/// Decode a Code 128 barcode into a resulting String
+///
+/// GS1-128 data is output in the human readable form, with the application identifiers in parentheses
+///
+/// This decoder will, in order:
+/// * Match the symbol patterns, reading the barcode from right to left if it is upside down
+/// * Validate the check symbol
+/// * Decode the data using code sets A, B and C
+/// * Parse the application identifiers if the data starts with FNC1
+pub struct Code128Decoder {}
+
+impl Code128Decoder {
+    /// Construct a new Code128Decoder
+    pub fn new() -> Code128Decoder {
+        Code128Decoder {}
+    }
+}
+
+impl Decode<LinearData, String, LinearError> for Code128Decoder {
+    fn decode(&self, data: Result<LinearData, LinearError>) -> Result<String, LinearError> {
+        decode_code128(&data?).map(|(text, _)| text)
+    }
+}
+
+/// Decode a Code 128 barcode into a resulting String. It also includes some information about the decoded barcode.
+///
+/// Functions the same as Code128Decoder, apart from also returning the parsed application identifiers of GS1-128 data
+pub struct Code128DecoderWithInfo {}
+
+impl Code128DecoderWithInfo {
+    /// Construct a new Code128DecoderWithInfo
+    pub fn new() -> Code128DecoderWithInfo {
+        Code128DecoderWithInfo {}
+    }
+}
+
+impl Decode<LinearData, (String, LinearInfo), LinearError> for Code128DecoderWithInfo {
+    fn decode(
+        &self,
+        data: Result<LinearData, LinearError>,
+    ) -> Result<(String, LinearInfo), LinearError> {
+        decode_code128(&data?)
+    }
+}
+
+fn decode_code128(linear_data: &LinearData) -> Result<(String, LinearInfo), LinearError> {
+    if linear_data.symbology != LinearSymbology::Code128 {
+        return Err(LinearError {
+            msg: format!("Cannot decode {:?} as Code 128", linear_data.symbology),
+        });
+    }
+
+    // An upside down barcode is scanned from right to left
+    let values = either_direction(&linear_data.runs, symbols)?;
+
+    debug!("CODE 128 VALUES {:?}", values);
+
+    verify(&values)?;
+
+    let (text, gs1) = data(&values[..values.len() - 1])?;
+
+    if !gs1 {
+        return Ok((
+            text,
+            LinearInfo {
+                symbology: LinearSymbology::Code128,
+                gs1: None,
+            },
+        ));
+    }
+
+    let ais = parse(&text).map_err(|e| LinearError { msg: e.msg })?;
+    let text = ais.iter().map(|ai| ai.to_string()).collect();
+
+    Ok((
+        text,
+        LinearInfo {
+            symbology: LinearSymbology::Code128,
+            gs1: Some(ais),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::util::gs1::ApplicationIdentifier;
+
+    #[test]
+    pub fn test_decode() {
+        // Start C, FNC1, 01 09 50 11 01 53 00 03, Code B, "1", "0", "A", check symbol
+        let widths = [
+            "211232", "411131", "222122", "221213", "231131", "231212", "222122", "213131",
+            "212222", "121223", "114131", "123221", "123122", "111323", "321122",
+        ];
+
+        let mut runs: Vec<u32> = vec![];
+        for value in widths.iter() {
+            runs.extend(value.bytes().map(|w| u32::from(w - b'0') * 3));
+        }
+        runs.extend([2, 3, 3, 1, 1, 1, 2].iter().map(|w| w * 3));
+
+        let (text, info) =
+            decode_code128(&LinearData::new(LinearSymbology::Code128, runs.clone())).unwrap();
+
+        assert_eq!("(01)09501101530003(10)A", text);
+        assert_eq!(
+            Some(vec![
+                ApplicationIdentifier {
+                    ai: String::from("01"),
+                    data: String::from("09501101530003"),
+                },
+                ApplicationIdentifier {
+                    ai: String::from("10"),
+                    data: String::from("A"),
+                },
+            ]),
+            info.gs1
+        );
+
+        // Upside down
+        runs.reverse();
+        assert_eq!(
+            "(01)09501101530003(10)A",
+            decode_code128(&LinearData::new(LinearSymbology::Code128, runs))
+                .unwrap()
+                .0
+        );
+    }
+}
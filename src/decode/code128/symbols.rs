@@ -0,0 +1,164 @@
+use crate::util::linear::LinearError;
+
+// Widths of the bars and spaces of the symbols in modules, starting with a bar
+// The values 103 to 105 are the start patterns for code sets A, B and C
+const PATTERNS: [[u32; 6]; 106] = [
+    [2, 1, 2, 2, 2, 2], [2, 2, 2, 1, 2, 2], [2, 2, 2, 2, 2, 1], [1, 2, 1, 2, 2, 3], [1, 2, 1, 3, 2, 2],
+    [1, 3, 1, 2, 2, 2], [1, 2, 2, 2, 1, 3], [1, 2, 2, 3, 1, 2], [1, 3, 2, 2, 1, 2], [2, 2, 1, 2, 1, 3],
+    [2, 2, 1, 3, 1, 2], [2, 3, 1, 2, 1, 2], [1, 1, 2, 2, 3, 2], [1, 2, 2, 1, 3, 2], [1, 2, 2, 2, 3, 1],
+    [1, 1, 3, 2, 2, 2], [1, 2, 3, 1, 2, 2], [1, 2, 3, 2, 2, 1], [2, 2, 3, 2, 1, 1], [2, 2, 1, 1, 3, 2],
+    [2, 2, 1, 2, 3, 1], [2, 1, 3, 2, 1, 2], [2, 2, 3, 1, 1, 2], [3, 1, 2, 1, 3, 1], [3, 1, 1, 2, 2, 2],
+    [3, 2, 1, 1, 2, 2], [3, 2, 1, 2, 2, 1], [3, 1, 2, 2, 1, 2], [3, 2, 2, 1, 1, 2], [3, 2, 2, 2, 1, 1],
+    [2, 1, 2, 1, 2, 3], [2, 1, 2, 3, 2, 1], [2, 3, 2, 1, 2, 1], [1, 1, 1, 3, 2, 3], [1, 3, 1, 1, 2, 3],
+    [1, 3, 1, 3, 2, 1], [1, 1, 2, 3, 1, 3], [1, 3, 2, 1, 1, 3], [1, 3, 2, 3, 1, 1], [2, 1, 1, 3, 1, 3],
+    [2, 3, 1, 1, 1, 3], [2, 3, 1, 3, 1, 1], [1, 1, 2, 1, 3, 3], [1, 1, 2, 3, 3, 1], [1, 3, 2, 1, 3, 1],
+    [1, 1, 3, 1, 2, 3], [1, 1, 3, 3, 2, 1], [1, 3, 3, 1, 2, 1], [3, 1, 3, 1, 2, 1], [2, 1, 1, 3, 3, 1],
+    [2, 3, 1, 1, 3, 1], [2, 1, 3, 1, 1, 3], [2, 1, 3, 3, 1, 1], [2, 1, 3, 1, 3, 1], [3, 1, 1, 1, 2, 3],
+    [3, 1, 1, 3, 2, 1], [3, 3, 1, 1, 2, 1], [3, 1, 2, 1, 1, 3], [3, 1, 2, 3, 1, 1], [3, 3, 2, 1, 1, 1],
+    [3, 1, 4, 1, 1, 1], [2, 2, 1, 4, 1, 1], [4, 3, 1, 1, 1, 1], [1, 1, 1, 2, 2, 4], [1, 1, 1, 4, 2, 2],
+    [1, 2, 1, 1, 2, 4], [1, 2, 1, 4, 2, 1], [1, 4, 1, 1, 2, 2], [1, 4, 1, 2, 2, 1], [1, 1, 2, 2, 1, 4],
+    [1, 1, 2, 4, 1, 2], [1, 2, 2, 1, 1, 4], [1, 2, 2, 4, 1, 1], [1, 4, 2, 1, 1, 2], [1, 4, 2, 2, 1, 1],
+    [2, 4, 1, 2, 1, 1], [2, 2, 1, 1, 1, 4], [4, 1, 3, 1, 1, 1], [2, 4, 1, 1, 1, 2], [1, 3, 4, 1, 1, 1],
+    [1, 1, 1, 2, 4, 2], [1, 2, 1, 1, 4, 2], [1, 2, 1, 2, 4, 1], [1, 1, 4, 2, 1, 2], [1, 2, 4, 1, 1, 2],
+    [1, 2, 4, 2, 1, 1], [4, 1, 1, 2, 1, 2], [4, 2, 1, 1, 1, 2], [4, 2, 1, 2, 1, 1], [2, 1, 2, 1, 4, 1],
+    [2, 1, 4, 1, 2, 1], [4, 1, 2, 1, 2, 1], [1, 1, 1, 1, 4, 3], [1, 1, 1, 3, 4, 1], [1, 3, 1, 1, 4, 1],
+    [1, 1, 4, 1, 1, 3], [1, 1, 4, 3, 1, 1], [4, 1, 1, 1, 1, 3], [4, 1, 1, 3, 1, 1], [1, 1, 3, 1, 4, 1],
+    [1, 1, 4, 1, 3, 1], [3, 1, 1, 1, 4, 1], [4, 1, 1, 1, 3, 1], [2, 1, 1, 4, 1, 2], [2, 1, 1, 2, 1, 4],
+    [2, 1, 1, 2, 3, 2],
+
+];
+
+const STOP: [u32; 7] = [2, 3, 3, 1, 1, 1, 2];
+
+// Maximum deviation of a single bar or space from the pattern, in modules
+const MAX_INDIVIDUAL_VARIANCE: f64 = 0.7;
+
+// Maximum total deviation of all bars and spaces from the pattern, in modules
+const MAX_TOTAL_VARIANCE: f64 = 2.0;
+
+/// Value of the start symbol for code set A
+pub const START_A: u8 = 103;
+
+/// Value of the start symbol for code set B
+pub const START_B: u8 = 104;
+
+/// Value of the start symbol for code set C
+pub const START_C: u8 = 105;
+
+/// Read the values of the symbols from the bars and spaces, including the start symbol and the check symbol
+///
+/// The runs must end with the stop pattern
+pub fn symbols(runs: &[u32]) -> Result<Vec<u8>, LinearError> {
+    // The start symbol and the check symbol of 6 runs each, followed by the stop pattern of 7 runs
+    let valid = match runs.len().checked_sub(7) {
+        Some(symbols) if symbols >= 12 => symbols % 6 == 0,
+        _ => false,
+    };
+
+    if !valid {
+        return Err(LinearError {
+            msg: format!("Unexpected number of bars and spaces {}", runs.len()),
+        });
+    }
+
+    let (symbols, stop) = runs.split_at(runs.len() - 7);
+
+    let module = f64::from(stop.iter().sum::<u32>()) / 13.0;
+
+    if stop.iter().zip(STOP.iter()).any(|(run, width)| {
+        (f64::from(*run) - f64::from(*width) * module).abs() >= MAX_INDIVIDUAL_VARIANCE * module
+    }) {
+        return Err(LinearError {
+            msg: String::from("Invalid stop pattern"),
+        });
+    }
+
+    symbols
+        .chunks(6)
+        .enumerate()
+        .map(|(i, chunk)| {
+            symbol(chunk).ok_or(LinearError {
+                msg: format!("Invalid symbol pattern {:?} at {}", chunk, i),
+            })
+        })
+        .collect()
+}
+
+/// Verify the check symbol, which is the last of the values
+///
+/// The check symbol is the sum of the start symbol and every other symbol weighted by its position, modulo 103
+pub fn verify(values: &[u8]) -> Result<(), LinearError> {
+    let (check, rest) = values.split_last().unwrap();
+
+    let sum: usize = rest
+        .iter()
+        .enumerate()
+        .map(|(i, v)| usize::from(*v) * i.max(1))
+        .sum();
+
+    if sum % 103 != usize::from(*check) {
+        return Err(LinearError {
+            msg: format!("Invalid check symbol {}, expected {}", check, sum % 103),
+        });
+    }
+
+    Ok(())
+}
+
+// Match the widths of a single symbol against all patterns, returning the closest match
+fn symbol(runs: &[u32]) -> Option<u8> {
+    let scale = 11.0 / f64::from(runs.iter().sum::<u32>());
+    let mut best: Option<(u8, f64)> = None;
+
+    for (value, pattern) in PATTERNS.iter().enumerate() {
+        let deviations: Vec<f64> = runs
+            .iter()
+            .zip(pattern.iter())
+            .map(|(run, width)| (f64::from(*run) * scale - f64::from(*width)).abs())
+            .collect();
+
+        if deviations.iter().any(|d| *d >= MAX_INDIVIDUAL_VARIANCE) {
+            continue;
+        }
+
+        let variance: f64 = deviations.iter().sum();
+
+        if variance <= MAX_TOTAL_VARIANCE && best.map(|b| variance < b.1).unwrap_or(true) {
+            best = Some((value as u8, variance));
+        }
+    }
+
+    best.map(|(value, _)| value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_patterns() {
+        for pattern in PATTERNS.iter() {
+            assert_eq!(11, pattern.iter().sum::<u32>());
+            assert_eq!(Some(pattern), PATTERNS.iter().find(|p| p == &pattern));
+        }
+    }
+
+    #[test]
+    pub fn test_symbols() {
+        // Start B, "Hi", check symbol (104 + 40 + 2 * 73) % 103 = 84
+        let mut runs = vec![];
+        for value in &[104, 40, 73, 84] {
+            runs.extend(PATTERNS[*value].iter().map(|w| w * 2));
+        }
+        runs.extend(STOP.iter().map(|w| w * 2));
+
+        let values = symbols(&runs).unwrap();
+        assert_eq!(vec![104, 40, 73, 84], values);
+        assert!(verify(&values).is_ok());
+
+        assert!(verify(&[104, 40, 73, 85]).is_err());
+
+        // Missing stop pattern
+        assert!(symbols(&runs[..runs.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+use super::super::linear::{either_direction, full_ascii, narrow_wide};
+use super::super::Decode;
+
+use crate::util::linear::{LinearData, LinearError, LinearSymbology};
+
+const ALPHABET: &[u8; 43] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-. $/+%";
+
+// Narrow and wide runs of the characters in the alphabet, with 1 for wide
+const PATTERNS: [u32; 43] = [
+    0x034, 0x121, 0x061, 0x160, 0x031, 0x130, 0x070, 0x025, 0x124, 0x064, 0x109, 0x049, 0x148,
+    0x019, 0x118, 0x058, 0x00D, 0x10C, 0x04C, 0x01C, 0x103, 0x043, 0x142, 0x013, 0x112, 0x052,
+    0x007, 0x106, 0x046, 0x016, 0x181, 0x0C1, 0x1C0, 0x091, 0x190, 0x0D0, 0x085, 0x184, 0x0C4,
+    0x0A8, 0x0A2, 0x08A, 0x02A,
+];
+
+// Start and stop character `*`
+const START_STOP: u32 = 0x094;
+
+/// Decode a Code 39 barcode into a resulting String
+///
+/// This decoder will, in order:
+/// * Classify the bars and spaces of every character as narrow or wide, reading the barcode from right to left if it is upside down
+/// * Validate and remove the modulo 43 check character, if enabled
+/// * Expand the Full ASCII characters, if enabled
+///
+/// Since both the check character and Full ASCII are optional and cannot be recognized from the barcode itself,
+/// they need to be enabled explicitly when they are used
+pub struct Code39Decoder {
+    check_character: bool,
+    full_ascii: bool,
+}
+
+impl Code39Decoder {
+    /// Construct a new Code39Decoder
+    ///
+    /// * check_character: the last character is a modulo 43 check character
+    /// * full_ascii: the data uses Full ASCII, with `$`, `%`, `/` and `+` followed by a letter for the other ASCII characters
+    pub fn new(check_character: bool, full_ascii: bool) -> Code39Decoder {
+        Code39Decoder {
+            check_character,
+            full_ascii,
+        }
+    }
+}
+
+impl Decode<LinearData, String, LinearError> for Code39Decoder {
+    fn decode(&self, data: Result<LinearData, LinearError>) -> Result<String, LinearError> {
+        let linear_data = data?;
+
+        if linear_data.symbology != LinearSymbology::Code39 {
+            return Err(LinearError {
+                msg: format!("Cannot decode {:?} as Code 39", linear_data.symbology),
+            });
+        }
+
+        // An upside down barcode is scanned from right to left
+        let mut values = either_direction(&linear_data.runs, characters)?;
+
+        debug!("CODE 39 VALUES {:?}", values);
+
+        if self.check_character {
+            let check = values.pop().ok_or(LinearError {
+                msg: String::from("Missing check character"),
+            })?;
+
+            let sum: usize = values.iter().sum();
+
+            if sum % 43 != check {
+                return Err(LinearError {
+                    msg: format!("Invalid check character {}, expected {}", check, sum % 43),
+                });
+            }
+        }
+
+        let text: String = values.iter().map(|v| char::from(ALPHABET[*v])).collect();
+
+        if self.full_ascii {
+            expand(&text)
+        } else {
+            Ok(text)
+        }
+    }
+}
+
+// Read the values of the characters in the alphabet, between the start and stop characters
+fn characters(runs: &[u32]) -> Result<Vec<usize>, LinearError> {
+    // Characters of 9 runs separated by a space, at least one besides the start and stop characters
+    let valid = match (runs.len() + 1) % 10 {
+        0 => runs.len() >= 29,
+        _ => false,
+    };
+
+    if !valid {
+        return Err(LinearError {
+            msg: format!("Unexpected number of bars and spaces {}", runs.len()),
+        });
+    }
+
+    let patterns: Vec<u32> = runs
+        .chunks(10)
+        .map(|chunk| narrow_wide(&chunk[..9]).unwrap_or(0))
+        .collect();
+
+    let (first, rest) = patterns.split_first().unwrap();
+    let (last, data) = rest.split_last().unwrap();
+
+    if *first != START_STOP || *last != START_STOP {
+        return Err(LinearError {
+            msg: String::from("Missing start or stop character"),
+        });
+    }
+
+    data.iter()
+        .enumerate()
+        .map(|(i, pattern)| {
+            PATTERNS
+                .iter()
+                .position(|p| p == pattern)
+                .ok_or(LinearError {
+                    msg: format!("Invalid character pattern {:09b} at {}", pattern, i),
+                })
+        })
+        .collect()
+}
+
+// Expand the Full ASCII characters, a shift character followed by a letter
+fn expand(text: &str) -> Result<String, LinearError> {
+    let mut result = String::new();
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '$' | '%' | '/' | '+' => {
+                let next = chars.next().and_then(|next| full_ascii(c, next));
+
+                result.push(next.ok_or(LinearError {
+                    msg: format!("Invalid Full ASCII sequence after {}", c),
+                })?);
+            }
+            _ => result.push(c),
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Widths of the runs for the text, including the start and stop characters
+    fn runs(text: &str) -> Vec<u32> {
+        let mut patterns = vec![START_STOP];
+        patterns.extend(
+            text.bytes()
+                .map(|c| PATTERNS[ALPHABET.iter().position(|a| *a == c).unwrap()]),
+        );
+        patterns.push(START_STOP);
+
+        let mut runs = vec![];
+        for pattern in patterns {
+            if !runs.is_empty() {
+                runs.push(2);
+            }
+            runs.extend((0..9).rev().map(|i| match (pattern >> i) & 1 {
+                1 => 5,
+                _ => 2,
+            }));
+        }
+
+        runs
+    }
+
+    #[test]
+    pub fn test_decode() {
+        let decode = |runs, check, ascii| {
+            Code39Decoder::new(check, ascii)
+                .decode(Ok(LinearData::new(LinearSymbology::Code39, runs)))
+        };
+
+        assert_eq!(
+            Ok(String::from("CODE 39")),
+            decode(runs("CODE 39"), false, false)
+        );
+
+        // Check character, C + O + D + E + space + 3 + 9 = 12 + 24 + 13 + 14 + 38 + 3 + 9 = 113, modulo 43 is 27 or R
+        assert_eq!(
+            Ok(String::from("CODE 39")),
+            decode(runs("CODE 39R"), true, false)
+        );
+        assert!(decode(runs("CODE 39S"), true, false).is_err());
+
+        // Full ASCII
+        assert_eq!(
+            Ok(String::from("Code 39")),
+            decode(runs("C+O+D+E 39"), false, true)
+        );
+        assert!(decode(runs("C+O+D+E 39+"), false, true).is_err());
+
+        // Upside down
+        let mut reversed = runs("CODE 39");
+        reversed.reverse();
+        assert_eq!(Ok(String::from("CODE 39")), decode(reversed, false, false));
+    }
+}
@@ -0,0 +1,266 @@
+use super::super::linear::{either_direction, full_ascii};
+use super::super::Decode;
+
+use crate::util::linear::{LinearData, LinearError, LinearSymbology};
+
+// The last four characters are the shift characters ($), (%), (/) and (+) used for Full ASCII
+const ALPHABET: &[u8; 47] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-. $/+%$%/+";
+
+// Modules of the characters in the alphabet, with 1 for a bar
+const PATTERNS: [u32; 47] = [
+    0x114, 0x148, 0x144, 0x142, 0x128, 0x124, 0x122, 0x150, 0x112, 0x10A, 0x1A8, 0x1A4, 0x1A2,
+    0x194, 0x192, 0x18A, 0x168, 0x164, 0x162, 0x134, 0x11A, 0x158, 0x14C, 0x146, 0x12C, 0x116,
+    0x1B4, 0x1B2, 0x1AC, 0x1A6, 0x196, 0x19A, 0x16C, 0x166, 0x136, 0x13A, 0x12E, 0x1D4, 0x1D2,
+    0x1CA, 0x16E, 0x176, 0x1AE, 0x126, 0x1DA, 0x1D6, 0x132,
+];
+
+// Start and stop character `*`
+const START_STOP: u32 = 0x15E;
+
+// Value of the first shift character
+const SHIFT: usize = 43;
+
+// Maximum deviation of a single bar or space from the pattern, in modules
+const MAX_INDIVIDUAL_VARIANCE: f64 = 0.7;
+
+/// Decode a Code 93 barcode into a resulting String
+///
+/// This decoder will, in order:
+/// * Match the character patterns, reading the barcode from right to left if it is upside down
+/// * Validate and remove the two modulo 47 check characters
+/// * Expand the Full ASCII characters, which use their own shift characters
+pub struct Code93Decoder {}
+
+impl Code93Decoder {
+    /// Construct a new Code93Decoder
+    pub fn new() -> Code93Decoder {
+        Code93Decoder {}
+    }
+}
+
+impl Decode<LinearData, String, LinearError> for Code93Decoder {
+    fn decode(&self, data: Result<LinearData, LinearError>) -> Result<String, LinearError> {
+        let linear_data = data?;
+
+        if linear_data.symbology != LinearSymbology::Code93 {
+            return Err(LinearError {
+                msg: format!("Cannot decode {:?} as Code 93", linear_data.symbology),
+            });
+        }
+
+        // An upside down barcode is scanned from right to left
+        let mut values = either_direction(&linear_data.runs, characters)?;
+
+        debug!("CODE 93 VALUES {:?}", values);
+
+        // Both check characters are mandatory
+        let k = values.pop().ok_or(LinearError {
+            msg: String::from("Missing check character K"),
+        })?;
+        verify(&values, k, 15, "K")?;
+
+        let c = values.pop().ok_or(LinearError {
+            msg: String::from("Missing check character C"),
+        })?;
+        verify(&values, c, 20, "C")?;
+
+        let mut result = String::new();
+        let mut iter = values.iter();
+
+        while let Some(value) = iter.next() {
+            if *value < SHIFT {
+                result.push(char::from(ALPHABET[*value]));
+                continue;
+            }
+
+            let expanded = iter.next().and_then(|next| {
+                full_ascii(char::from(ALPHABET[*value]), char::from(ALPHABET[*next]))
+            });
+
+            result.push(expanded.ok_or(LinearError {
+                msg: String::from("Invalid Full ASCII sequence"),
+            })?);
+        }
+
+        Ok(result)
+    }
+}
+
+// Read the values of the characters in the alphabet, between the start and stop characters
+fn characters(runs: &[u32]) -> Result<Vec<usize>, LinearError> {
+    // Characters of 6 runs followed by a termination bar, at least the two check characters besides the start and stop characters
+    let valid = match runs.len().checked_sub(1) {
+        Some(characters) if characters >= 24 => characters % 6 == 0,
+        _ => false,
+    };
+
+    if !valid {
+        return Err(LinearError {
+            msg: format!("Unexpected number of bars and spaces {}", runs.len()),
+        });
+    }
+
+    let (first, rest) = runs[..runs.len() - 1].split_at(6);
+    let (data, last) = rest.split_at(rest.len() - 6);
+
+    if character(first) != Some(START_STOP) || character(last) != Some(START_STOP) {
+        return Err(LinearError {
+            msg: String::from("Missing start or stop character"),
+        });
+    }
+
+    data.chunks(6)
+        .enumerate()
+        .map(|(i, chunk)| {
+            character(chunk)
+                .and_then(|pattern| PATTERNS.iter().position(|p| *p == pattern))
+                .ok_or(LinearError {
+                    msg: format!("Invalid character pattern {:?} at {}", chunk, i),
+                })
+        })
+        .collect()
+}
+
+// Convert the widths of a character to its modules, by rounding each run to a whole number of modules
+fn character(runs: &[u32]) -> Option<u32> {
+    let module = f64::from(runs.iter().sum::<u32>()) / 9.0;
+    let mut pattern = 0;
+    let mut modules = 0;
+
+    for (i, run) in runs.iter().enumerate() {
+        let width = f64::from(*run) / module;
+        let rounded = width.round().max(1.0);
+
+        if (width - rounded).abs() >= MAX_INDIVIDUAL_VARIANCE || rounded > 4.0 {
+            return None;
+        }
+
+        for _ in 0..rounded as u32 {
+            pattern = (pattern << 1)
+                | match i % 2 {
+                    0 => 1,
+                    _ => 0,
+                };
+            modules += 1;
+        }
+    }
+
+    match modules {
+        9 => Some(pattern),
+        _ => None,
+    }
+}
+
+// Verify a check character, the sum of the values weighted by their position from the right, modulo 47
+fn verify(
+    values: &[usize],
+    check: usize,
+    max_weight: usize,
+    name: &str,
+) -> Result<(), LinearError> {
+    let sum: usize = values
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, v)| v * (i % max_weight + 1))
+        .sum();
+
+    if sum % 47 != check {
+        return Err(LinearError {
+            msg: format!(
+                "Invalid check character {} {}, expected {}",
+                name,
+                check,
+                sum % 47
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Widths of the runs for the values, including the start and stop characters and the termination bar
+    fn runs(values: &[usize]) -> Vec<u32> {
+        let mut patterns = vec![START_STOP];
+        patterns.extend(values.iter().map(|v| PATTERNS[*v]));
+        patterns.push(START_STOP);
+
+        let mut runs: Vec<u32> = vec![];
+        let mut previous = 0;
+
+        for pattern in patterns {
+            for i in (0..9).rev() {
+                let bit = (pattern >> i) & 1;
+
+                match runs.last_mut() {
+                    Some(run) if bit == previous => *run += 3,
+                    _ => runs.push(3),
+                }
+                previous = bit;
+            }
+        }
+        runs.push(3);
+
+        runs
+    }
+
+    #[test]
+    pub fn test_decode() {
+        let decode =
+            |runs| Code93Decoder::new().decode(Ok(LinearData::new(LinearSymbology::Code93, runs)));
+
+        // "TEST93", check characters C = 41 or + and K = 6
+        let values = [29, 14, 28, 29, 9, 3, 41, 6];
+        assert_eq!(Ok(String::from("TEST93")), decode(runs(&values)));
+
+        let mut reversed = runs(&values);
+        reversed.reverse();
+        assert_eq!(Ok(String::from("TEST93")), decode(reversed));
+
+        assert!(decode(runs(&[29, 14, 28, 29, 9, 3, 41, 7])).is_err());
+
+        // Full ASCII, (+)C (+)O (+)D (+)E
+        let values = [46, 12, 46, 24, 46, 13, 46, 14];
+        let c = values
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, v)| v * (i % 20 + 1))
+            .sum::<usize>()
+            % 47;
+        let k = values
+            .iter()
+            .chain(&[c])
+            .rev()
+            .enumerate()
+            .map(|(i, v)| v * (i % 15 + 1))
+            .sum::<usize>()
+            % 47;
+        assert_eq!(
+            Ok(String::from("code")),
+            decode(runs(&[&values[..], &[c, k]].concat()))
+        );
+    }
+    #[test]
+    pub fn test_truncated() {
+        let decode =
+            |runs| Code93Decoder::new().decode(Ok(LinearData::new(LinearSymbology::Code93, runs)));
+
+        assert!(characters(&[]).is_err());
+        assert!(decode(vec![]).is_err());
+
+        // Cut off anywhere, including right after the start character or without the check characters
+        let complete = runs(&[29, 14, 28, 29, 9, 3, 41, 6]);
+
+        for len in 0..complete.len() {
+            assert!(decode(complete[..len].to_vec()).is_err());
+        }
+
+        assert!(decode(runs(&[])).is_err());
+        assert!(decode(runs(&[41])).is_err());
+    }
+}
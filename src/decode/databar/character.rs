@@ -0,0 +1,288 @@
+/// Finder patterns of GS1 DataBar Omnidirectional, in modules
+pub const OMNI_FINDERS: [[u32; 5]; 9] = [
+    [3, 8, 2, 1, 1],
+    [3, 5, 5, 1, 1],
+    [3, 3, 7, 1, 1],
+    [3, 1, 9, 1, 1],
+    [2, 7, 4, 1, 1],
+    [2, 5, 6, 1, 1],
+    [2, 3, 8, 1, 1],
+    [1, 5, 7, 1, 1],
+    [1, 3, 9, 1, 1],
+];
+
+/// Finder patterns A to F of GS1 DataBar Expanded, in modules
+pub const EXPANDED_FINDERS: [[u32; 5]; 6] = [
+    [1, 8, 4, 1, 1],
+    [3, 6, 4, 1, 1],
+    [3, 4, 6, 1, 1],
+    [3, 2, 8, 1, 1],
+    [2, 6, 5, 1, 1],
+    [2, 2, 9, 1, 1],
+];
+
+// Total width of a finder pattern in modules
+const FINDER_MODULES: u32 = 15;
+
+/// Kind of data character, which determines its width and value tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Outside character of a GS1 DataBar Omnidirectional pair, 16 modules wide
+    Outside,
+
+    /// Inside character of a GS1 DataBar Omnidirectional pair, next to the center, 15 modules wide
+    Inside,
+
+    /// Character of GS1 DataBar Expanded, 17 modules wide
+    Expanded,
+}
+
+impl Kind {
+    /// Width of the character in modules
+    pub fn modules(self) -> u32 {
+        match self {
+            Kind::Outside => 16,
+            Kind::Inside => 15,
+            Kind::Expanded => 17,
+        }
+    }
+}
+
+// Per group: modules in the odd elements, widest odd element, combinations of the elements that vary fastest and the first value
+const OUTSIDE_GROUPS: [(u32, u32, u32, u32); 5] = [
+    (12, 8, 1, 0),
+    (10, 6, 10, 161),
+    (8, 4, 34, 961),
+    (6, 3, 70, 2015),
+    (4, 1, 126, 2715),
+];
+const INSIDE_GROUPS: [(u32, u32, u32, u32); 4] = [
+    (5, 2, 4, 0),
+    (7, 4, 20, 336),
+    (9, 6, 48, 1036),
+    (11, 8, 81, 1516),
+];
+const EXPANDED_GROUPS: [(u32, u32, u32, u32); 5] = [
+    (12, 7, 4, 0),
+    (10, 5, 20, 348),
+    (8, 4, 52, 1388),
+    (6, 3, 104, 2948),
+    (4, 1, 204, 3988),
+];
+
+/// Data character decoded from its 8 elements
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Character {
+    /// Value of the character
+    pub value: u32,
+
+    /// Widths of the elements in modules, used for the checksum
+    pub widths: [u32; 8],
+}
+
+/// Match 5 runs against the finder patterns, both as read and reversed
+///
+/// Returns the index of the best matching pattern and whether it is reversed
+pub fn finder(runs: &[u32], patterns: &[[u32; 5]]) -> Option<(usize, bool)> {
+    if runs.len() != 5 {
+        return None;
+    }
+
+    let module = f64::from(runs.iter().sum::<u32>()) / f64::from(FINDER_MODULES);
+
+    let mut best: Option<(usize, bool)> = None;
+    let mut best_variance = 0.7 * module;
+
+    for (i, pattern) in patterns.iter().enumerate() {
+        for &reversed in [false, true].iter() {
+            let variance = (0..5)
+                .map(|j| {
+                    let width = match reversed {
+                        false => pattern[j],
+                        true => pattern[4 - j],
+                    };
+                    (f64::from(runs[j]) - f64::from(width) * module).abs()
+                })
+                .fold(0.0, f64::max);
+
+            if variance < best_variance {
+                best = Some((i, reversed));
+                best_variance = variance;
+            }
+        }
+    }
+
+    best
+}
+
+/// Decode a data character from the widths of its 8 runs in pixels
+///
+/// The odd elements are the first, third, fifth and seventh run.
+pub fn character(runs: &[u32], kind: Kind) -> Option<Character> {
+    let widths = modules(runs, kind.modules())?;
+
+    let odd: Vec<u32> = widths.iter().step_by(2).copied().collect();
+    let even: Vec<u32> = widths.iter().skip(1).step_by(2).copied().collect();
+    let odd_sum: u32 = odd.iter().sum();
+
+    let (groups, no_narrow_odd): (&[(u32, u32, u32, u32)], bool) = match kind {
+        Kind::Outside => (&OUTSIDE_GROUPS, false),
+        Kind::Inside => (&INSIDE_GROUPS, true),
+        Kind::Expanded => (&EXPANDED_GROUPS, true),
+    };
+
+    let &(_, widest, combinations, first) = groups.iter().find(|g| g.0 == odd_sum)?;
+
+    if odd.iter().any(|w| *w > widest) || even.iter().any(|w| *w > 9 - widest) {
+        return None;
+    }
+
+    let odd_value = rss_value(&odd, widest, no_narrow_odd)?;
+    let even_value = rss_value(&even, 9 - widest, !no_narrow_odd)?;
+
+    // The inside characters have the odd elements varying fastest, the others the even elements
+    let value = match kind {
+        Kind::Inside if odd_value < combinations => even_value * combinations + odd_value,
+        Kind::Outside | Kind::Expanded if even_value < combinations => {
+            odd_value * combinations + even_value
+        }
+        _ => return None,
+    };
+
+    Some(Character {
+        value: value + first,
+        widths,
+    })
+}
+
+// Round the runs to the number of modules of the character, correcting rounding errors of single runs
+fn modules(runs: &[u32], total: u32) -> Option<[u32; 8]> {
+    if runs.len() != 8 {
+        return None;
+    }
+
+    let module = f64::from(runs.iter().sum::<u32>()) / f64::from(total);
+    let exact: Vec<f64> = runs.iter().map(|r| f64::from(*r) / module).collect();
+
+    let mut widths = [0; 8];
+    for (width, exact) in widths.iter_mut().zip(&exact) {
+        *width = (exact.round() as u32).clamp(1, 9);
+    }
+
+    for _ in 0..2 {
+        let sum: u32 = widths.iter().sum();
+
+        let error = |i: usize| exact[i] - f64::from(widths[i]);
+        let indices = 0..8;
+
+        if sum < total {
+            let i = indices.max_by(|a, b| error(*a).partial_cmp(&error(*b)).unwrap())?;
+            widths[i] += 1;
+        } else if sum > total {
+            let i = indices
+                .filter(|i| widths[*i] > 1)
+                .min_by(|a, b| error(*a).partial_cmp(&error(*b)).unwrap())?;
+            widths[i] -= 1;
+        }
+    }
+
+    match widths.iter().sum::<u32>() == total {
+        true => Some(widths),
+        false => None,
+    }
+}
+
+// Value of a combination of element widths, among all combinations with the same total width and no element wider than the widest.
+// If no_narrow is set, combinations without any element of a single module are excluded.
+fn rss_value(widths: &[u32], widest: u32, no_narrow: bool) -> Option<u32> {
+    let elements = widths.len() as i64;
+    let widest = i64::from(widest);
+
+    let mut n: i64 = widths.iter().map(|w| i64::from(*w)).sum();
+    let mut value: i64 = 0;
+    let mut narrow = false;
+
+    for (bar, width) in widths[..widths.len() - 1].iter().enumerate() {
+        let bar = bar as i64;
+        let width = i64::from(*width);
+
+        // Count the combinations in which this element is narrower than its actual width
+        for w in 1..width {
+            let narrow = narrow || w == 1;
+            let remaining = elements - bar - 1;
+
+            let mut sub = combinations(n - w - 1, remaining - 1);
+
+            if no_narrow && !narrow && n - w - remaining >= remaining {
+                sub -= combinations(n - w - remaining - 1, remaining - 1);
+            }
+
+            if remaining > 1 {
+                let mut less = 0;
+                let mut max = n - w - (remaining - 1);
+                while max > widest {
+                    less += combinations(n - w - max - 1, remaining - 2);
+                    max -= 1;
+                }
+                sub -= less * remaining;
+            } else if n - w > widest {
+                sub -= 1;
+            }
+
+            value += sub;
+        }
+
+        narrow = narrow || width == 1;
+        n -= width;
+    }
+
+    match value {
+        0..=0xFFFF => Some(value as u32),
+        _ => None,
+    }
+}
+
+// Number of ways to choose r out of n
+fn combinations(n: i64, r: i64) -> i64 {
+    if r < 0 || n < r {
+        return 0;
+    }
+
+    (0..r).fold(1, |c, i| c * (n - i) / (i + 1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_finder() {
+        assert_eq!(Some((0, false)), finder(&[9, 24, 6, 3, 3], &OMNI_FINDERS));
+        assert_eq!(
+            Some((5, true)),
+            finder(&[2, 2, 18, 4, 4], &EXPANDED_FINDERS)
+        );
+        assert_eq!(None, finder(&[3, 3, 3, 3, 3], &OMNI_FINDERS));
+    }
+
+    #[test]
+    pub fn test_character() {
+        // Highest value of each kind
+        let outside = character(&[1, 8, 1, 2, 1, 1, 1, 1], Kind::Outside).unwrap();
+        assert_eq!(2840, outside.value);
+
+        let inside = character(&[4, 1, 5, 1, 1, 1, 1, 1], Kind::Inside).unwrap();
+        assert_eq!(1596, inside.value);
+
+        let expanded = character(&[1, 8, 1, 3, 1, 1, 1, 1], Kind::Expanded).unwrap();
+        assert_eq!(4191, expanded.value);
+
+        // Scaled and slightly distorted
+        let scaled = character(&[3, 26, 2, 9, 3, 3, 2, 3], Kind::Expanded).unwrap();
+        assert_eq!(4191, scaled.value);
+        assert_eq!([1, 8, 1, 3, 1, 1, 1, 1], scaled.widths);
+
+        // Odd elements of 11 modules do not form an outside character
+        assert_eq!(None, character(&[3, 1, 3, 1, 3, 1, 2, 2], Kind::Outside));
+    }
+}
@@ -0,0 +1,89 @@
+use super::super::Decode;
+use super::expanded::bits;
+use super::fields::fields;
+use super::omni::digits;
+
+use crate::util::gs1::ApplicationIdentifier;
+use crate::util::linear::{LinearData, LinearError, LinearInfo, LinearSymbology};
+
+/// Decode a GS1 DataBar barcode into a resulting String
+///
+/// Both GS1 DataBar Omnidirectional and Expanded are supported, including their stacked variants.
+/// The data is output in the human readable form, with the application identifiers in parentheses
+///
+/// This decoder will, in order:
+/// * Find the pairs of characters around the finder patterns on all rows, in both directions
+/// * Combine the left and right pair of an Omnidirectional barcode that match the checksum,
+///   or reassemble the pairs of an Expanded barcode in the order of its finder patterns and validate the check character
+/// * Decode the GTIN of an Omnidirectional barcode, or the compressed and general purpose data of an Expanded barcode
+///
+/// GS1 DataBar Limited and composite components are not supported
+pub struct DataBarDecoder {}
+
+impl DataBarDecoder {
+    /// Construct a new DataBarDecoder
+    pub fn new() -> DataBarDecoder {
+        DataBarDecoder {}
+    }
+}
+
+impl Decode<LinearData, String, LinearError> for DataBarDecoder {
+    fn decode(&self, data: Result<LinearData, LinearError>) -> Result<String, LinearError> {
+        decode_databar(&data?).map(|(text, _)| text)
+    }
+}
+
+/// Decode a GS1 DataBar barcode into a resulting String. It also includes some information about the decoded barcode.
+///
+/// Functions the same as DataBarDecoder, apart from also returning the parsed application identifiers
+pub struct DataBarDecoderWithInfo {}
+
+impl DataBarDecoderWithInfo {
+    /// Construct a new DataBarDecoderWithInfo
+    pub fn new() -> DataBarDecoderWithInfo {
+        DataBarDecoderWithInfo {}
+    }
+}
+
+impl Decode<LinearData, (String, LinearInfo), LinearError> for DataBarDecoderWithInfo {
+    fn decode(
+        &self,
+        data: Result<LinearData, LinearError>,
+    ) -> Result<(String, LinearInfo), LinearError> {
+        decode_databar(&data?)
+    }
+}
+
+fn decode_databar(linear_data: &LinearData) -> Result<(String, LinearInfo), LinearError> {
+    let mut rows = vec![&linear_data.runs[..]];
+    rows.extend(linear_data.stacked.iter().map(|runs| &runs[..]));
+
+    let ais = match linear_data.symbology {
+        LinearSymbology::DataBar => vec![ApplicationIdentifier {
+            ai: String::from("01"),
+            data: digits(&rows)?,
+        }],
+        LinearSymbology::DataBarExpanded => {
+            let bits = bits(&rows)?;
+
+            debug!("DATABAR EXPANDED BITS {:?}", bits);
+
+            fields(&bits)?
+        }
+        _ => {
+            return Err(LinearError {
+                msg: format!("Cannot decode {:?} as GS1 DataBar", linear_data.symbology),
+            })
+        }
+    };
+
+    let text = ais.iter().map(|ai| ai.to_string()).collect();
+
+    Ok((
+        text,
+        LinearInfo {
+            symbology: linear_data.symbology,
+            gs1: Some(ais),
+        },
+    ))
+}
@@ -0,0 +1,205 @@
+use super::character::{character, finder, Character, Kind, EXPANDED_FINDERS};
+
+use crate::util::linear::LinearError;
+
+// Finder patterns of the pairs for every number of pairs, as 2 * pattern + 1 with 1 added for reversed patterns
+const SEQUENCES: [&[usize]; 10] = [
+    &[1, 2],
+    &[1, 4, 3],
+    &[1, 6, 3, 8],
+    &[1, 10, 3, 8, 5],
+    &[1, 10, 3, 8, 7, 12],
+    &[1, 10, 3, 8, 9, 12, 11],
+    &[1, 2, 3, 4, 5, 6, 7, 8],
+    &[1, 2, 3, 4, 5, 6, 7, 10, 9],
+    &[1, 2, 3, 4, 5, 6, 7, 10, 11, 12],
+    &[1, 2, 3, 4, 5, 8, 7, 10, 9, 12, 11],
+];
+
+// Maximum number of rows of a stacked barcode
+const MAX_ROWS: usize = 11;
+
+#[derive(Debug, Clone)]
+struct Pair {
+    // 2 * pattern + 1 if reversed
+    finder: usize,
+    left: Character,
+    right: Option<Character>,
+}
+
+/// Decode the data characters of a GS1 DataBar Expanded barcode into its data bits
+///
+/// Every row is read as a sequence of pairs of characters around finder patterns, in both directions.
+/// The segments of a stacked barcode are reassembled by trying the rows in either direction and order,
+/// until the finder patterns form a valid sequence and the check character matches.
+pub fn bits(rows: &[&[u32]]) -> Result<Vec<bool>, LinearError> {
+    if rows.len() > MAX_ROWS {
+        return Err(LinearError {
+            msg: format!("Too many rows {}", rows.len()),
+        });
+    }
+
+    let parsed: Vec<[Option<Vec<Pair>>; 2]> = rows
+        .iter()
+        .map(|row| {
+            let reversed: Vec<u32> = row.iter().rev().copied().collect();
+            [pairs(row), pairs(&reversed)]
+        })
+        .collect();
+
+    debug!("DATABAR EXPANDED PAIRS {:?}", parsed);
+
+    // Upside down the rows are in reverse order as well
+    let orders: [Vec<usize>; 2] = [(0..rows.len()).collect(), (0..rows.len()).rev().collect()];
+
+    for order in orders.iter() {
+        for directions in 0..1 << rows.len() {
+            let sequence: Option<Vec<Vec<Pair>>> = order
+                .iter()
+                .enumerate()
+                .map(|(i, row)| parsed[*row][(directions >> i) & 1].clone())
+                .collect();
+
+            if let Some(values) = sequence.and_then(|sequence| check(&sequence.concat())) {
+                return Ok(values
+                    .iter()
+                    .flat_map(|v| (0..12).rev().map(move |i| (v >> i) & 1 == 1))
+                    .collect());
+            }
+        }
+    }
+
+    Err(LinearError {
+        msg: String::from("No valid sequence of finder patterns found"),
+    })
+}
+
+// Read a row as pairs of characters, starting after the guard
+fn pairs(runs: &[u32]) -> Option<Vec<Pair>> {
+    // The left guard is a bar, or a bar and a space
+    [9, 10].iter().find_map(|first| {
+        let mut pairs = vec![];
+        let mut i = *first;
+
+        loop {
+            let (pattern, reversed) = finder(runs.get(i..i + 5)?, &EXPANDED_FINDERS)?;
+
+            // Patterns that are read as is start with a space, reversed ones with a bar
+            if (i % 2 == 1) == reversed {
+                return None;
+            }
+
+            let module = f64::from(runs[i..i + 5].iter().sum::<u32>()) / 15.0;
+            let char = |runs: &[u32]| {
+                let width = f64::from(runs.iter().sum::<u32>()) / 17.0;
+                match (width - module).abs() < 0.25 * module {
+                    true => character(runs, Kind::Expanded),
+                    false => None,
+                }
+            };
+
+            // The characters are read from the end away from the finder pattern
+            let left = char(&runs[i - 8..i])?;
+            let right = match runs.len() > i + 13 {
+                true => {
+                    let after: Vec<u32> = runs[i + 5..i + 13].iter().rev().copied().collect();
+                    Some(char(&after)?)
+                }
+                false => None,
+            };
+
+            let last = right.is_none() || runs.len() < i + 21 + 5;
+
+            pairs.push(Pair {
+                finder: 2 * pattern + usize::from(reversed) + 1,
+                left,
+                right,
+            });
+
+            if last {
+                // Only the right guard may follow
+                let end = match pairs.last().unwrap().right {
+                    Some(_) => i + 13,
+                    None => i + 5,
+                };
+
+                return match runs.len() - end {
+                    1 | 2 => Some(pairs),
+                    _ => None,
+                };
+            }
+
+            i += 21;
+        }
+    })
+}
+
+// Check the sequence of finder patterns and the check character, and return the values of the data characters
+fn check(pairs: &[Pair]) -> Option<Vec<u32>> {
+    let sequence = SEQUENCES.get(pairs.len().checked_sub(2)?)?;
+
+    if pairs
+        .iter()
+        .zip(sequence.iter())
+        .any(|(p, f)| p.finder != *f)
+    {
+        return None;
+    }
+
+    // Only the last pair may lack its right character
+    if pairs[..pairs.len() - 1].iter().any(|p| p.right.is_none()) {
+        return None;
+    }
+
+    let mut values = vec![];
+    let mut sum = 0;
+
+    for pair in pairs {
+        // The weights depend on the finder pattern, except for the check character which is the first character
+        let row = 2 * (pair.finder - 1);
+
+        for (side, char) in [Some(&pair.left), pair.right.as_ref()].iter().enumerate() {
+            let char = match char {
+                Some(char) => char,
+                None => continue,
+            };
+
+            if row + side == 0 {
+                continue;
+            }
+
+            sum += char
+                .widths
+                .iter()
+                .enumerate()
+                .map(|(i, w)| w * weight(8 * (row + side - 1) + i))
+                .sum::<u32>();
+            values.push(char.value);
+        }
+    }
+
+    let count = values.len() as u32 + 1;
+
+    match count >= 4 && pairs[0].left.value == 211 * (count - 4) + sum % 211 {
+        true => Some(values),
+        false => None,
+    }
+}
+
+// Weight of an element for the checksum, 3 to the power of the element number modulo 211
+fn weight(element: usize) -> u32 {
+    (0..element).fold(1, |w, _| 3 * w % 211)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_weight() {
+        assert_eq!(1, weight(0));
+        assert_eq!(32, weight(5));
+        assert_eq!(20, weight(8));
+        assert_eq!(89, weight(22 * 8 + 7));
+    }
+}
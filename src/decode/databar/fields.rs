@@ -0,0 +1,323 @@
+use super::super::ean::digits::{check_digit, to_string};
+
+use crate::util::gs1::{parse, ApplicationIdentifier, GROUP_SEPARATOR};
+use crate::util::linear::LinearError;
+
+// Special characters of the ISO/IEC 646 encodation, in the order of their values
+const ISO_646_SPECIAL: &[u8; 21] = b"!\"%&'()*+,-./:;<=>?_ ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Numeric,
+    Alphanumeric,
+    Iso646,
+}
+
+struct Bits<'a> {
+    bits: &'a [bool],
+}
+
+impl<'a> Bits<'a> {
+    fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    fn get(&self, pos: usize) -> bool {
+        self.bits.get(pos) == Some(&true)
+    }
+
+    fn value(&self, pos: usize, count: usize) -> Option<u32> {
+        self.bits
+            .get(pos..pos + count)
+            .map(|bits| bits.iter().fold(0, |v, b| (v << 1) | u32::from(*b)))
+    }
+}
+
+/// Decode the data of a GS1 DataBar Expanded barcode into application identifiers
+///
+/// The encodation method in the first bits either compresses a GTIN with common application identifiers like weights and dates,
+/// or encodes all data as a general purpose field. The linkage flag to a composite component in the first bit is ignored.
+pub fn fields(bits: &[bool]) -> Result<Vec<ApplicationIdentifier>, LinearError> {
+    let bits = Bits { bits };
+
+    let value = |pos, count| {
+        bits.value(pos, count).ok_or(LinearError {
+            msg: String::from("Unexpected end of data"),
+        })
+    };
+
+    // GTIN with any other application identifiers
+    if bits.get(1) {
+        let first = value(4, 4)?;
+        let mut ais = vec![gtin(&bits, first, 8)?];
+        ais.extend(general_ais(&general(&bits, 48)?)?);
+        return Ok(ais);
+    }
+
+    // Only a general purpose field
+    if !bits.get(2) {
+        return general_ais(&general(&bits, 5)?);
+    }
+
+    // The other methods compress a GTIN starting with 9, after a header of 5 or 8 bits
+    let method = value(1, 4)?;
+    let mut ais = vec![gtin(&bits, 9, if method < 6 { 5 } else { 8 })?];
+
+    match (method, value(1, 5)?, value(1, 7)?) {
+        // Net weight in kilograms with 3 decimals
+        (4, _, _) => {
+            ais.push(ai("3103", format!("{:06}", value(45, 15)?)));
+        }
+        // Net weight in pounds with 2 or 3 decimals
+        (5, _, _) => {
+            let weight = value(45, 15)?;
+            ais.push(match weight {
+                0..=9999 => ai("3202", format!("{:06}", weight)),
+                _ => ai("3203", format!("{:06}", weight - 10000)),
+            });
+        }
+        // Price
+        (_, 12, _) => {
+            let decimals = value(48, 2)?;
+            let (price, rest) = first_field(general(&bits, 50)?);
+            ais.push(ai(&format!("392{}", decimals), price));
+            ais.extend(general_ais(&rest)?);
+        }
+        // Price with ISO currency code
+        (_, 13, _) => {
+            let decimals = value(48, 2)?;
+            let currency = value(50, 10)?;
+            let (price, rest) = first_field(general(&bits, 60)?);
+            ais.push(ai(
+                &format!("393{}", decimals),
+                format!("{:03}{}", currency, price),
+            ));
+            ais.extend(general_ais(&rest)?);
+        }
+        // Net weight in kilograms or pounds with a date
+        (_, _, dated @ 56..=63) => {
+            let weight_ai = ["310", "320"][(dated as usize - 56) % 2];
+            let date_ai = ["11", "13", "15", "17"][(dated as usize - 56) / 2];
+
+            let weight = value(48, 20)?;
+            ais.push(ai(
+                &format!("{}{}", weight_ai, weight / 100_000),
+                format!("{:06}", weight % 100_000),
+            ));
+
+            // No date is encoded as 38400
+            let date = value(68, 16)?;
+            if date != 38400 {
+                ais.push(ai(
+                    date_ai,
+                    format!("{:02}{:02}{:02}", date / 384, date / 32 % 12 + 1, date % 32),
+                ));
+            }
+        }
+        (method, _, _) => {
+            return Err(LinearError {
+                msg: format!("Unknown encodation method {:04b}", method),
+            })
+        }
+    }
+
+    Ok(ais)
+}
+
+fn ai(ai: &str, data: String) -> ApplicationIdentifier {
+    ApplicationIdentifier {
+        ai: String::from(ai),
+        data,
+    }
+}
+
+// GTIN with the provided first digit, followed by 12 digits compressed in groups of three and the check digit
+fn gtin(bits: &Bits, first: u32, pos: usize) -> Result<ApplicationIdentifier, LinearError> {
+    let mut digits = vec![first as u8];
+
+    for i in 0..4 {
+        let group = bits.value(pos + 10 * i, 10).unwrap_or(1000);
+
+        if first > 9 || group > 999 {
+            return Err(LinearError {
+                msg: String::from("Invalid GTIN"),
+            });
+        }
+
+        digits.extend(
+            [group / 100, group / 10 % 10, group % 10]
+                .iter()
+                .map(|d| *d as u8),
+        );
+    }
+
+    digits.push(check_digit(&digits));
+
+    Ok(ai("01", to_string(&digits)))
+}
+
+// Split the data of the first application identifier of a general purpose field from the rest
+fn first_field(text: String) -> (String, String) {
+    match text.find(GROUP_SEPARATOR) {
+        Some(i) => (String::from(&text[..i]), String::from(&text[i..])),
+        None => (text, String::new()),
+    }
+}
+
+fn general_ais(text: &str) -> Result<Vec<ApplicationIdentifier>, LinearError> {
+    parse(text).map_err(|e| LinearError { msg: e.msg })
+}
+
+// Decode a general purpose field, switching between the numeric, alphanumeric and ISO/IEC 646 encodations.
+// FNC1 is decoded as a Group Separator.
+fn general(bits: &Bits, mut pos: usize) -> Result<String, LinearError> {
+    let len = bits.len();
+    let value = |pos, count| bits.value(pos, count).unwrap_or(0);
+
+    let digit = |d: u32| match d {
+        10 => GROUP_SEPARATOR,
+        _ => char::from(b'0' + d as u8),
+    };
+
+    // All bits zero, or the bits that remain
+    let zeros = |pos: usize, count: usize| (pos..pos + count).all(|i| !bits.get(i));
+
+    // Latch from the alphanumeric or ISO/IEC 646 encodation, 00100 with the bits that remain
+    let latch =
+        |pos: usize| pos < len && (pos..(pos + 5).min(len)).all(|i| bits.get(i) == (i == pos + 2));
+
+    let mut text = String::new();
+    let mut mode = Mode::Numeric;
+
+    loop {
+        let start = pos;
+
+        match mode {
+            Mode::Numeric => {
+                loop {
+                    if pos + 7 > len {
+                        // A final digit in 4 bits, with 0 for FNC1
+                        if pos + 4 > len || zeros(pos, 4) {
+                            break;
+                        }
+
+                        let v = value(pos, 4);
+                        if v > 10 {
+                            return Err(LinearError {
+                                msg: format!("Invalid numeric value {}", v),
+                            });
+                        }
+                        text.push(digit(v - 1));
+                        pos += 4;
+                    } else {
+                        if zeros(pos, 4) {
+                            break;
+                        }
+
+                        let v = value(pos, 7);
+                        if v < 8 {
+                            return Err(LinearError {
+                                msg: format!("Invalid numeric value {}", v),
+                            });
+                        }
+                        text.push(digit((v - 8) / 11));
+                        text.push(digit((v - 8) % 11));
+                        pos += 7;
+                    }
+                }
+
+                // Latch to alphanumeric, 0000 with the bits that remain
+                if pos < len && zeros(pos, (len - pos).min(4)) {
+                    mode = Mode::Alphanumeric;
+                    pos += 4;
+                }
+            }
+            Mode::Alphanumeric | Mode::Iso646 => {
+                while let Some((c, size)) = character(bits, pos, mode) {
+                    text.push(c);
+                    pos += size;
+                }
+
+                if pos + 3 <= len && zeros(pos, 3) {
+                    mode = Mode::Numeric;
+                    pos += 3;
+                } else if latch(pos) {
+                    mode = match mode {
+                        Mode::Alphanumeric => Mode::Iso646,
+                        _ => Mode::Alphanumeric,
+                    };
+                    pos = (pos + 5).min(len);
+                }
+            }
+        }
+
+        if pos == start || pos >= len {
+            break;
+        }
+    }
+
+    Ok(String::from(text.trim_end_matches(GROUP_SEPARATOR)))
+}
+
+// Character of the alphanumeric or ISO/IEC 646 encodation, with its size in bits
+fn character(bits: &Bits, pos: usize, mode: Mode) -> Option<(char, usize)> {
+    match bits.value(pos, 5)? {
+        15 => return Some((GROUP_SEPARATOR, 5)),
+        v @ 5..=14 => return Some((char::from(b'0' + v as u8 - 5), 5)),
+        _ => {}
+    }
+
+    if mode == Mode::Alphanumeric {
+        return match bits.value(pos, 6)? {
+            v @ 32..=57 => Some((char::from(b'A' + v as u8 - 32), 6)),
+            v @ 58..=62 => Some((char::from(b"*,-./"[v as usize - 58]), 6)),
+            _ => None,
+        };
+    }
+
+    match bits.value(pos, 7)? {
+        v @ 64..=89 => return Some((char::from(b'A' + v as u8 - 64), 7)),
+        v @ 90..=115 => return Some((char::from(b'a' + v as u8 - 90), 7)),
+        _ => {}
+    }
+
+    match bits.value(pos, 8)? {
+        v @ 232..=252 => Some((char::from(ISO_646_SPECIAL[v as usize - 232]), 8)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bits(s: &str) -> Vec<bool> {
+        s.bytes().map(|b| b == b'1').collect()
+    }
+
+    fn ai(ai: &str, data: &str) -> ApplicationIdentifier {
+        ApplicationIdentifier {
+            ai: String::from(ai),
+            data: String::from(data),
+        }
+    }
+
+    #[test]
+    pub fn test_fields() {
+        // Compressed GTIN with a net weight in kilograms
+        assert_eq!(
+            Ok(vec![ai("01", "90012345678908"), ai("3103", "001750")]),
+            fields(&bits(
+                "001000000000001001110101010001101111101111010000011011010110"
+            ))
+        );
+
+        // General purpose field latching from numeric to alphanumeric to ISO/IEC 646
+        assert_eq!(
+            Ok(vec![ai("10", "A1b")]),
+            fields(&bits("000000010011000010000000110001001011011"))
+        );
+
+        assert!(fields(&bits("0100")).is_err());
+    }
+}
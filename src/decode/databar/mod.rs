@@ -0,0 +1,5 @@
+pub(crate) mod character;
+pub mod decoder;
+mod expanded;
+mod fields;
+mod omni;
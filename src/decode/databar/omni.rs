@@ -0,0 +1,124 @@
+use super::super::ean::digits::{check_digit, to_string};
+use super::character::{character, finder, Kind, OMNI_FINDERS};
+
+use crate::util::linear::LinearError;
+
+#[derive(Debug)]
+struct Pair {
+    finder: u32,
+    value: u64,
+    checksum: u32,
+}
+
+/// Decode the 13 digits of a GS1 DataBar Omnidirectional barcode, followed by the check digit
+///
+/// The left and right half of the barcode each form a pair of characters around a finder pattern.
+/// Pairs are collected from all rows, read in both directions, so the halves of a stacked barcode are combined the same way as those of a single row.
+/// The first left and right pair that match the checksum form the result.
+pub fn digits(rows: &[&[u32]]) -> Result<String, LinearError> {
+    let mut lefts = vec![];
+    let mut rights = vec![];
+
+    for row in rows {
+        let reversed: Vec<u32> = row.iter().rev().copied().collect();
+
+        for runs in [row.to_vec(), reversed].iter() {
+            for (left, pair) in pairs(runs) {
+                match left {
+                    true => lefts.push(pair),
+                    false => rights.push(pair),
+                }
+            }
+        }
+    }
+
+    debug!("DATABAR PAIRS {:?} {:?}", lefts, rights);
+
+    for left in lefts.iter() {
+        for right in rights.iter() {
+            // The checksum determines the combination of finder patterns, excluding the combinations of two equal outer patterns
+            let mut target = 9 * left.finder + right.finder;
+            if target > 72 {
+                target -= 1;
+            }
+            if target > 8 {
+                target -= 1;
+            }
+
+            if (left.checksum + 16 * right.checksum) % 79 != target {
+                continue;
+            }
+
+            // The linkage flag to a composite component is ignored
+            let value = (4_537_077 * left.value + right.value) % 10_000_000_000_000;
+
+            let mut digits: Vec<u8> = format!("{:013}", value).bytes().map(|d| d - b'0').collect();
+            digits.push(check_digit(&digits));
+
+            return Ok(to_string(&digits));
+        }
+    }
+
+    Err(LinearError {
+        msg: String::from("No matching left and right pair found"),
+    })
+}
+
+// Pairs of an outside and an inside character around the finder patterns of a row, and whether they are the left half
+fn pairs(runs: &[u32]) -> Vec<(bool, Pair)> {
+    let mut pairs = vec![];
+
+    for i in 8..runs.len().saturating_sub(12) {
+        let (value, reversed) = match finder(&runs[i..i + 5], &OMNI_FINDERS) {
+            Some(finder) => finder,
+            None => continue,
+        };
+
+        // The left finder pattern starts with a space and is read as is, the right one starts with a bar and is reversed
+        let left = match (i % 2, reversed) {
+            (1, false) => true,
+            (0, true) => false,
+            _ => continue,
+        };
+
+        // The characters are read from the end away from the finder pattern
+        let before = runs[i - 8..i].to_vec();
+        let after: Vec<u32> = runs[i + 5..i + 13].iter().rev().copied().collect();
+
+        let (outside, inside) = match left {
+            true => (before, after),
+            false => (after, before),
+        };
+
+        let module = f64::from(runs[i..i + 5].iter().sum::<u32>()) / 15.0;
+        let consistent = |runs: &[u32], kind: Kind| {
+            (f64::from(runs.iter().sum::<u32>()) / f64::from(kind.modules()) - module).abs()
+                < 0.25 * module
+        };
+
+        if !consistent(&outside, Kind::Outside) || !consistent(&inside, Kind::Inside) {
+            continue;
+        }
+
+        if let (Some(outside), Some(inside)) = (
+            character(&outside, Kind::Outside),
+            character(&inside, Kind::Inside),
+        ) {
+            pairs.push((
+                left,
+                Pair {
+                    finder: value as u32,
+                    value: 1597 * u64::from(outside.value) + u64::from(inside.value),
+                    checksum: checksum(&outside.widths) + 4 * checksum(&inside.widths),
+                },
+            ));
+        }
+    }
+
+    pairs
+}
+
+// Checksum of a character, with weights of increasing powers of 3
+fn checksum(widths: &[u32; 8]) -> u32 {
+    widths.iter().rev().fold(0, |sum, w| 3 * sum + w)
+}
@@ -0,0 +1,228 @@
+use crate::util::datamatrix::DataMatrixError;
+
+// Data Matrix uses a different primitive polynomial than QR, x^8 + x^5 + x^3 + x^2 + 1
+const PRIMITIVE: u32 = 0x12D;
+
+const fn exp_table() -> [u8; 512] {
+    let mut exp = [0; 512];
+    let mut x: u32 = 1;
+    let mut i = 0;
+
+    while i < 512 {
+        exp[i] = x as u8;
+
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= PRIMITIVE;
+        }
+
+        i += 1;
+    }
+
+    exp
+}
+
+const fn log_table() -> [u8; 256] {
+    let mut log = [0; 256];
+    let mut i = 0;
+
+    while i < 255 {
+        log[EXP[i] as usize] = i as u8;
+        i += 1;
+    }
+
+    log
+}
+
+// The table is twice as long as needed so products of logarithms do not need to be reduced
+const EXP: [u8; 512] = exp_table();
+const LOG: [u8; 256] = log_table();
+
+fn mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+
+    EXP[LOG[a as usize] as usize + LOG[b as usize] as usize]
+}
+
+fn div(a: u8, b: u8) -> u8 {
+    if a == 0 {
+        return 0;
+    }
+
+    EXP[LOG[a as usize] as usize + 255 - LOG[b as usize] as usize]
+}
+
+// Evaluate a polynomial with the lowest degree coefficient first
+fn eval(poly: &[u8], x: u8) -> u8 {
+    poly.iter().rev().fold(0, |acc, c| mul(acc, x) ^ c)
+}
+
+/// Correct a single block of codewords, data codewords first, followed by `ec` error correction codewords
+///
+/// Returns the corrected block and the number of corrected codewords
+pub fn correct(mut block: Vec<u8>, ec: usize) -> Result<(Vec<u8>, usize), DataMatrixError> {
+    let n = block.len();
+
+    // The generator polynomial has roots a^1 to a^ec
+    let syndromes: Vec<u8> = (1..=ec)
+        .map(|i| block.iter().fold(0, |acc, c| mul(acc, EXP[i]) ^ c))
+        .collect();
+
+    if syndromes.iter().all(|s| *s == 0) {
+        debug!("ALL SYNDROMES WERE ZERO, NO CORRECTION NEEDED");
+        return Ok((block, 0));
+    }
+
+    // Berlekamp-Massey to find the error locator polynomial
+    let mut sigma = vec![1];
+    let mut previous = vec![1];
+    let mut errors = 0;
+    let mut shift = 1;
+    let mut b = 1;
+
+    for i in 0..ec {
+        let discrepancy = (0..=errors.min(sigma.len() - 1)).fold(syndromes[i], |acc, j| {
+            if j == 0 {
+                acc
+            } else {
+                acc ^ mul(sigma[j], syndromes[i - j])
+            }
+        });
+
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+
+        let factor = div(discrepancy, b);
+        let mut next = sigma.clone();
+        next.resize(next.len().max(previous.len() + shift), 0);
+
+        for (j, p) in previous.iter().enumerate() {
+            next[j + shift] ^= mul(factor, *p);
+        }
+
+        if 2 * errors <= i {
+            errors = i + 1 - errors;
+            previous = sigma;
+            b = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+
+        sigma = next;
+    }
+
+    sigma.truncate(errors + 1);
+
+    if errors == 0 || 2 * errors > ec {
+        return Err(DataMatrixError {
+            msg: String::from("Too many errors to correct"),
+        });
+    }
+
+    // Error evaluator polynomial, omega = syndromes * sigma mod x^ec
+    let omega: Vec<u8> = (0..ec)
+        .map(|i| (0..=i.min(errors)).fold(0, |acc, j| acc ^ mul(sigma[j], syndromes[i - j])))
+        .collect();
+
+    // Formal derivative of sigma, only the odd powers remain
+    let derivative: Vec<u8> = (1..sigma.len())
+        .map(|i| if i % 2 == 1 { sigma[i] } else { 0 })
+        .collect();
+
+    // Chien search for the roots of sigma, which are the inverses of the error locations
+    let mut corrected = 0;
+
+    for position in 0..n {
+        let x_inv = EXP[(255 - position % 255) % 255];
+
+        if eval(&sigma, x_inv) != 0 {
+            continue;
+        }
+
+        // Forney, the generator starts at a^1 so no additional factor is needed
+        let magnitude = div(eval(&omega, x_inv), eval(&derivative, x_inv));
+
+        debug!(
+            "FIXING LOCATION {} FROM {:08b} TO {:08b}",
+            n - 1 - position,
+            block[n - 1 - position],
+            block[n - 1 - position] ^ magnitude
+        );
+
+        block[n - 1 - position] ^= magnitude;
+        corrected += 1;
+    }
+
+    if corrected != errors {
+        return Err(DataMatrixError {
+            msg: String::from("Error correcting did not fix corrupted data"),
+        });
+    }
+
+    Ok((block, corrected))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Systematic encoding, only used to construct test blocks
+    fn encode(data: &[u8], ec: usize) -> Vec<u8> {
+        let mut generator = vec![1];
+        for root in EXP.iter().take(ec + 1).skip(1) {
+            let mut next = vec![0; generator.len() + 1];
+            for (j, g) in generator.iter().enumerate() {
+                next[j] ^= g;
+                next[j + 1] ^= mul(*g, *root);
+            }
+            generator = next;
+        }
+
+        let mut remainder = data.to_vec();
+        remainder.resize(data.len() + ec, 0);
+
+        for i in 0..data.len() {
+            let c = remainder[i];
+            for (j, g) in generator.iter().enumerate().skip(1) {
+                remainder[i + j] ^= mul(*g, c);
+            }
+        }
+
+        let mut block = data.to_vec();
+        block.extend(&remainder[data.len()..]);
+        block
+    }
+
+    #[test]
+    pub fn test_tables() {
+        assert_eq!(1, EXP[0]);
+        assert_eq!(0x2D, EXP[8]);
+        assert_eq!(1, EXP[255]);
+        assert_eq!(1, mul(0x80, div(1, 0x80)));
+    }
+
+    #[test]
+    pub fn test_correct() {
+        // "123456" in ASCII encodation for a 10x10 symbol, 5 error correction codewords
+        let block = encode(&[142, 164, 186], 5);
+        assert_eq!(vec![142, 164, 186, 114, 25, 5, 88, 102], block);
+
+        assert_eq!(Ok((block.clone(), 0)), correct(block.clone(), 5));
+
+        let mut corrupted = block.clone();
+        corrupted[0] = 0;
+        corrupted[6] ^= 0xFF;
+        assert_eq!(Ok((block.clone(), 2)), correct(corrupted, 5));
+
+        let mut corrupted = block.clone();
+        corrupted[0] = 0;
+        corrupted[1] = 0;
+        corrupted[2] = 0;
+        assert!(correct(corrupted, 5).is_err());
+    }
+}
@@ -0,0 +1,433 @@
+use super::super::qr::eci::Charset;
+
+use crate::util::datamatrix::DataMatrixError;
+
+const C40_SHIFT2: &[u8] = b"!\"#$%&'()*+,-./:;<=>?@[\\]^_";
+const TEXT_SHIFT3: &[u8] = b"`ABCDEFGHIJKLMNOPQRSTUVWXYZ{|}~\x7F";
+
+const GS: u8 = 0x1D;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Mode {
+    Ascii,
+    C40,
+    Text,
+    X12,
+    Edifact,
+    Base256,
+    Done,
+}
+
+struct Reader {
+    codewords: Vec<u8>,
+    pos: usize,
+}
+
+impl Reader {
+    fn next(&mut self) -> Option<u8> {
+        let codeword = self.codewords.get(self.pos).copied();
+        self.pos += 1;
+        codeword
+    }
+
+    fn remaining(&self) -> usize {
+        self.codewords.len().saturating_sub(self.pos)
+    }
+
+    fn require(&mut self) -> Result<u8, DataMatrixError> {
+        self.next().ok_or(DataMatrixError {
+            msg: String::from("Unexpected end of data"),
+        })
+    }
+}
+
+// Decoded bytes, split up every time an ECI designator selects a different character set
+struct Output {
+    segments: Vec<(Option<Charset>, Vec<u8>)>,
+    upper_shift: bool,
+}
+
+impl Output {
+    fn push(&mut self, byte: u8) {
+        let byte = if self.upper_shift { byte + 128 } else { byte };
+        self.upper_shift = false;
+
+        self.segments.last_mut().unwrap().1.push(byte);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.segments.iter().all(|(_, bytes)| bytes.is_empty())
+    }
+
+    fn text(self) -> Result<String, DataMatrixError> {
+        let mut text = String::new();
+
+        for (charset, bytes) in self.segments {
+            // The default character set for Data Matrix is ISO 8859-1
+            let charset = charset.unwrap_or(Charset::Iso88591);
+            let segment = charset
+                .decode(&bytes)
+                .map_err(|e| DataMatrixError { msg: e.msg })?;
+
+            text.push_str(&segment);
+        }
+
+        Ok(text)
+    }
+}
+
+/// Decode the data codewords of a Data Matrix Code
+pub fn data(codewords: Vec<u8>) -> Result<String, DataMatrixError> {
+    let mut reader = Reader { codewords, pos: 0 };
+    let mut output = Output {
+        segments: vec![(None, vec![])],
+        upper_shift: false,
+    };
+    let mut trailer: &[u8] = &[];
+    let mut mode = Mode::Ascii;
+
+    while mode != Mode::Done && reader.remaining() > 0 {
+        debug!("DATA MATRIX MODE {:?}", mode);
+
+        mode = match mode {
+            Mode::Ascii => ascii(&mut reader, &mut output, &mut trailer)?,
+            Mode::C40 | Mode::Text => c40_text(&mut reader, &mut output, mode)?,
+            Mode::X12 => x12(&mut reader, &mut output)?,
+            Mode::Edifact => edifact(&mut reader, &mut output)?,
+            Mode::Base256 => base256(&mut reader, &mut output)?,
+            Mode::Done => Mode::Done,
+        };
+    }
+
+    for byte in trailer {
+        output.push(*byte);
+    }
+
+    output.text()
+}
+
+fn ascii(
+    reader: &mut Reader,
+    output: &mut Output,
+    trailer: &mut &[u8],
+) -> Result<Mode, DataMatrixError> {
+    while let Some(codeword) = reader.next() {
+        match codeword {
+            1..=128 => output.push(codeword - 1),
+            129 => return Ok(Mode::Done),
+            130..=229 => {
+                let digits = codeword - 130;
+                output.push(b'0' + digits / 10);
+                output.push(b'0' + digits % 10);
+            }
+            230 => return Ok(Mode::C40),
+            231 => return Ok(Mode::Base256),
+            232 => {
+                // FNC1 in the first position marks GS1 data, anywhere else it is a field separator
+                if !output.is_empty() {
+                    output.push(GS);
+                }
+            }
+            233 => {
+                // Structured append, symbol sequence and file identification
+                for _ in 0..3 {
+                    reader.require()?;
+                }
+            }
+            235 => output.upper_shift = true,
+            236 | 237 => {
+                let header: &[u8] = if codeword == 236 {
+                    b"[)>\x1E05\x1D"
+                } else {
+                    b"[)>\x1E06\x1D"
+                };
+
+                for byte in header {
+                    output.push(*byte);
+                }
+
+                *trailer = b"\x1E\x04";
+            }
+            238 => return Ok(Mode::X12),
+            239 => return Ok(Mode::Text),
+            240 => return Ok(Mode::Edifact),
+            241 => {
+                let designator = eci_designator(reader)?;
+                let charset =
+                    Charset::from_eci(designator).map_err(|e| DataMatrixError { msg: e.msg })?;
+
+                output.segments.push((Some(charset), vec![]));
+            }
+            // Unlatch, some encoders add it even when already in ASCII
+            254 => {}
+            _ => {
+                return Err(DataMatrixError {
+                    msg: format!("Invalid ASCII codeword {}", codeword),
+                })
+            }
+        }
+    }
+
+    Ok(Mode::Done)
+}
+
+fn eci_designator(reader: &mut Reader) -> Result<u32, DataMatrixError> {
+    let c1 = u32::from(reader.require()?);
+
+    if c1 <= 127 {
+        return Ok(c1 - 1);
+    }
+
+    let c2 = u32::from(reader.require()?);
+
+    if c1 <= 191 {
+        return Ok((c1 - 128) * 254 + (c2 - 1) + 127);
+    }
+
+    let c3 = u32::from(reader.require()?);
+
+    Ok((c1 - 192) * 64516 + (c2 - 1) * 254 + (c3 - 1) + 16383)
+}
+
+// C40 and Text pack three values in two codewords
+fn triplet(reader: &mut Reader) -> Option<[u8; 3]> {
+    // A single remaining codeword is encoded in ASCII
+    if reader.remaining() < 2 {
+        return None;
+    }
+
+    let c1 = reader.next()?;
+    if c1 == 254 {
+        return None;
+    }
+
+    let c2 = reader.next()?;
+    let value = u32::from(c1) * 256 + u32::from(c2) - 1;
+
+    Some([
+        (value / 1600) as u8,
+        (value / 40 % 40) as u8,
+        (value % 40) as u8,
+    ])
+}
+
+fn c40_text(reader: &mut Reader, output: &mut Output, mode: Mode) -> Result<Mode, DataMatrixError> {
+    let mut shift = 0;
+
+    while let Some(values) = triplet(reader) {
+        for value in values.iter().copied() {
+            match (shift, value) {
+                (0, 0..=2) => {
+                    shift = value + 1;
+                    continue;
+                }
+                (0, 3) => output.push(b' '),
+                (0, 4..=13) => output.push(b'0' + value - 4),
+                (0, 14..=39) if mode == Mode::C40 => output.push(b'A' + value - 14),
+                (0, 14..=39) => output.push(b'a' + value - 14),
+                (1, 0..=31) => output.push(value),
+                (2, 0..=26) => output.push(C40_SHIFT2[value as usize]),
+                (2, 27) => output.push(GS),
+                (2, 30) => output.upper_shift = true,
+                (3, 0..=31) if mode == Mode::C40 => output.push(96 + value),
+                (3, 0..=31) => output.push(TEXT_SHIFT3[value as usize]),
+                _ => {
+                    return Err(DataMatrixError {
+                        msg: format!("Invalid {:?} value {} in shift set {}", mode, value, shift),
+                    })
+                }
+            }
+
+            shift = 0;
+        }
+    }
+
+    Ok(Mode::Ascii)
+}
+
+fn x12(reader: &mut Reader, output: &mut Output) -> Result<Mode, DataMatrixError> {
+    while let Some(values) = triplet(reader) {
+        for value in values.iter().copied() {
+            match value {
+                0 => output.push(b'\r'),
+                1 => output.push(b'*'),
+                2 => output.push(b'>'),
+                3 => output.push(b' '),
+                4..=13 => output.push(b'0' + value - 4),
+                14..=39 => output.push(b'A' + value - 14),
+                _ => {
+                    return Err(DataMatrixError {
+                        msg: format!("Invalid X12 value {}", value),
+                    })
+                }
+            }
+        }
+    }
+
+    Ok(Mode::Ascii)
+}
+
+fn edifact(reader: &mut Reader, output: &mut Output) -> Result<Mode, DataMatrixError> {
+    // EDIFACT packs four 6 bit values in three codewords
+    let mut bit = reader.pos * 8;
+    let end = reader.codewords.len() * 8;
+
+    // Any codewords at the end that are too short for a full set of values are encoded in ASCII
+    'values: while end - bit > 16 {
+        for _ in 0..4 {
+            if end - bit < 6 {
+                break;
+            }
+
+            let mut value = 0;
+            for _ in 0..6 {
+                value = value * 2 + ((reader.codewords[bit / 8] >> (7 - bit % 8)) & 1);
+                bit += 1;
+            }
+
+            // Unlatch, the rest of the codeword is padding
+            if value == 0b01_1111 {
+                break 'values;
+            }
+
+            // Values 0 to 31 represent ASCII 64 to 95
+            if value & 0b10_0000 == 0 {
+                value |= 0b100_0000;
+            }
+
+            output.push(value);
+        }
+    }
+
+    // Continue in ASCII from the next full codeword
+    reader.pos = match bit % 8 {
+        0 => bit / 8,
+        _ => bit / 8 + 1,
+    };
+    Ok(Mode::Ascii)
+}
+
+fn base256(reader: &mut Reader, output: &mut Output) -> Result<Mode, DataMatrixError> {
+    let length = unrandomize(reader)?;
+
+    let count = match length {
+        0 => reader.remaining(),
+        1..=249 => length as usize,
+        _ => 250 * (length as usize - 249) + unrandomize(reader)? as usize,
+    };
+
+    for _ in 0..count {
+        output.push(unrandomize(reader)?);
+    }
+
+    Ok(Mode::Ascii)
+}
+
+// Base256 codewords are randomized with their position in the data
+fn unrandomize(reader: &mut Reader) -> Result<u8, DataMatrixError> {
+    let position = reader.pos as u32 + 1;
+    let codeword = u32::from(reader.require()?);
+    let pseudo_random = 149 * position % 255 + 1;
+
+    Ok(((codeword + 256 - pseudo_random) % 256) as u8)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_ascii() {
+        // "123456", then padding
+        assert_eq!(Ok(String::from("123456")), data(vec![142, 164, 186]));
+        assert_eq!(
+            Ok(String::from("Ab1")),
+            data(vec![66, 99, 50, 129, 251, 147])
+        );
+
+        // upper shift
+        assert_eq!(Ok(String::from("é")), data(vec![235, 106]));
+    }
+
+    #[test]
+    pub fn test_c40() {
+        // "AIMAIM" in C40
+        assert_eq!(
+            Ok(String::from("AIMAIM")),
+            data(vec![230, 91, 11, 91, 11, 254])
+        );
+
+        // "aimaim" in Text, then a single ASCII codeword
+        assert_eq!(
+            Ok(String::from("aimaim!")),
+            data(vec![239, 91, 11, 91, 11, 34])
+        );
+    }
+
+    #[test]
+    pub fn test_x12() {
+        // "A*B>"
+        // 14 1 15 -> 1600 * 14 + 40 * 1 + 15 + 1 = 22456, then '>' followed by ASCII
+        assert_eq!(
+            Ok(String::from("A*B>1")),
+            data(vec![238, 87, 184, 254, 63, 50])
+        );
+    }
+
+    #[test]
+    pub fn test_edifact() {
+        // "DATA" followed by unlatch, then ASCII "123"
+        // D=000100 A=000001 T=010100 A=000001 011111
+        assert_eq!(
+            Ok(String::from("DATA123")),
+            data(vec![
+                240,
+                0b0001_0000,
+                0b0001_0101,
+                0b0000_0001,
+                0b0111_1100,
+                50,
+                51,
+                52
+            ])
+        );
+
+        // Without unlatch, the last codeword is implicitly ASCII
+        assert_eq!(
+            Ok(String::from("DATA1")),
+            data(vec![240, 0b0001_0000, 0b0001_0101, 0b0000_0001, 50])
+        );
+    }
+
+    #[test]
+    pub fn test_base256() {
+        // Length 2 and bytes 0xAB 0xCD, randomized for positions 2 to 4
+        let randomize =
+            |value: u32, position: u32| ((value + 149 * position % 255 + 1) % 256) as u8;
+
+        assert_eq!(
+            Ok(String::from("\u{AB}\u{CD}")),
+            data(vec![
+                231,
+                randomize(2, 2),
+                randomize(0xAB, 3),
+                randomize(0xCD, 4)
+            ])
+        );
+    }
+
+    #[test]
+    pub fn test_gs1() {
+        // FNC1, "01", FNC1, "10"
+        assert_eq!(
+            Ok(String::from("01\u{1D}10")),
+            data(vec![232, 131, 232, 140])
+        );
+    }
+
+    #[test]
+    pub fn test_eci() {
+        // ECI 26 (UTF-8), "é"
+        assert_eq!(Ok(String::from("é")), data(vec![241, 27, 235, 68, 235, 42]));
+    }
+}
@@ -0,0 +1,67 @@
+use super::super::Decode;
+
+use crate::util::datamatrix::{DataMatrixData, DataMatrixError};
+
+/// Decode a Data Matrix Code (ECC 200) into a resulting String
+///
+/// This decoder will, in order:
+/// * Determine the symbol layout from the number of rows and columns
+/// * Read the codewords from the data regions
+/// * Split the interleaved codewords into blocks and perform error correction
+/// * Decode the data codewords into a String
+pub struct DataMatrixDecoder {}
+
+impl DataMatrixDecoder {
+    /// Construct a new DataMatrixDecoder
+    pub fn new() -> DataMatrixDecoder {
+        DataMatrixDecoder {}
+    }
+}
+
+impl Decode<DataMatrixData, String, DataMatrixError> for DataMatrixDecoder {
+    fn decode(
+        &self,
+        data: Result<DataMatrixData, DataMatrixError>,
+    ) -> Result<String, DataMatrixError> {
+        let dm_data = data?;
+
+        let info = super::symbol_info(dm_data.rows, dm_data.columns)?;
+        let codewords = super::placement::codewords(&dm_data, &info)?;
+
+        // Both data and error correction codewords are interleaved over the blocks
+        let blocks = info.blocks as usize;
+        let mut data_blocks = vec![vec![]; blocks];
+        let mut ec_blocks = vec![vec![]; blocks];
+
+        for (i, codeword) in codewords.iter().enumerate() {
+            if i < info.data as usize {
+                data_blocks[i % blocks].push(*codeword);
+            } else {
+                ec_blocks[(i - info.data as usize) % blocks].push(*codeword);
+            }
+        }
+
+        let mut corrected_blocks = vec![];
+
+        for (mut block, ec) in data_blocks.into_iter().zip(ec_blocks) {
+            let data_len = block.len();
+            block.extend(ec);
+
+            let (mut corrected, _) = super::correct::correct(block, info.ec_per_block as usize)?;
+            corrected.truncate(data_len);
+
+            corrected_blocks.push(corrected);
+        }
+
+        // Restore the original order of the data codewords
+        let mut all_blocks = vec![];
+
+        for i in 0..info.data as usize {
+            all_blocks.push(corrected_blocks[i % blocks][i / blocks]);
+        }
+
+        debug!("TOTAL LENGTH {}", all_blocks.len());
+
+        super::data::data(all_blocks)
+    }
+}
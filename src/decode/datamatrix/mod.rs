@@ -0,0 +1,87 @@
+use crate::util::datamatrix::DataMatrixError;
+
+pub mod correct;
+pub mod data;
+pub mod decoder;
+pub mod placement;
+
+#[derive(Debug, Clone)]
+pub struct SymbolInfo {
+    pub rows: u32,
+    pub columns: u32,
+    pub region_rows: u32,
+    pub region_columns: u32,
+    pub data: u32,
+    pub ec_per_block: u32,
+    pub blocks: u32,
+}
+
+impl SymbolInfo {
+    fn new(
+        (rows, columns): (u32, u32),
+        (region_rows, region_columns): (u32, u32),
+        data: u32,
+        ec_per_block: u32,
+        blocks: u32,
+    ) -> SymbolInfo {
+        SymbolInfo {
+            rows,
+            columns,
+            region_rows,
+            region_columns,
+            data,
+            ec_per_block,
+            blocks,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.data + self.ec_per_block * self.blocks
+    }
+}
+
+pub fn symbol_info(rows: u32, columns: u32) -> Result<SymbolInfo, DataMatrixError> {
+    let symbol_info = match (rows, columns) {
+        // Square symbols
+        (10, 10) => SymbolInfo::new((10, 10), (8, 8), 3, 5, 1),
+        (12, 12) => SymbolInfo::new((12, 12), (10, 10), 5, 7, 1),
+        (14, 14) => SymbolInfo::new((14, 14), (12, 12), 8, 10, 1),
+        (16, 16) => SymbolInfo::new((16, 16), (14, 14), 12, 12, 1),
+        (18, 18) => SymbolInfo::new((18, 18), (16, 16), 18, 14, 1),
+        (20, 20) => SymbolInfo::new((20, 20), (18, 18), 22, 18, 1),
+        (22, 22) => SymbolInfo::new((22, 22), (20, 20), 30, 20, 1),
+        (24, 24) => SymbolInfo::new((24, 24), (22, 22), 36, 24, 1),
+        (26, 26) => SymbolInfo::new((26, 26), (24, 24), 44, 28, 1),
+        (32, 32) => SymbolInfo::new((32, 32), (14, 14), 62, 36, 1),
+        (36, 36) => SymbolInfo::new((36, 36), (16, 16), 86, 42, 1),
+        (40, 40) => SymbolInfo::new((40, 40), (18, 18), 114, 48, 1),
+        (44, 44) => SymbolInfo::new((44, 44), (20, 20), 144, 56, 1),
+        (48, 48) => SymbolInfo::new((48, 48), (22, 22), 174, 68, 1),
+        (52, 52) => SymbolInfo::new((52, 52), (24, 24), 204, 42, 2),
+        (64, 64) => SymbolInfo::new((64, 64), (14, 14), 280, 56, 2),
+        (72, 72) => SymbolInfo::new((72, 72), (16, 16), 368, 36, 4),
+        (80, 80) => SymbolInfo::new((80, 80), (18, 18), 456, 48, 4),
+        (88, 88) => SymbolInfo::new((88, 88), (20, 20), 576, 56, 4),
+        (96, 96) => SymbolInfo::new((96, 96), (22, 22), 696, 68, 4),
+        (104, 104) => SymbolInfo::new((104, 104), (24, 24), 816, 56, 6),
+        (120, 120) => SymbolInfo::new((120, 120), (18, 18), 1050, 68, 6),
+        (132, 132) => SymbolInfo::new((132, 132), (20, 20), 1304, 62, 8),
+        (144, 144) => SymbolInfo::new((144, 144), (22, 22), 1558, 62, 10),
+
+        // Rectangular symbols
+        (8, 18) => SymbolInfo::new((8, 18), (6, 16), 5, 7, 1),
+        (8, 32) => SymbolInfo::new((8, 32), (6, 14), 10, 11, 1),
+        (12, 26) => SymbolInfo::new((12, 26), (10, 24), 16, 14, 1),
+        (12, 36) => SymbolInfo::new((12, 36), (10, 16), 22, 18, 1),
+        (16, 36) => SymbolInfo::new((16, 36), (14, 16), 32, 24, 1),
+        (16, 48) => SymbolInfo::new((16, 48), (14, 22), 49, 28, 1),
+
+        (rows, columns) => {
+            return Err(DataMatrixError {
+                msg: format!("Unknown Data Matrix size {}x{}", rows, columns),
+            })
+        }
+    };
+
+    Ok(symbol_info)
+}
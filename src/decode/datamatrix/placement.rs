@@ -0,0 +1,223 @@
+use super::SymbolInfo;
+
+use crate::util::datamatrix::{DataMatrixData, DataMatrixError};
+
+// The data regions without their finder and clock patterns, joined together into a single matrix
+struct Mapping {
+    bits: Vec<u8>,
+    read: Vec<bool>,
+    rows: i32,
+    columns: i32,
+}
+
+impl Mapping {
+    fn new(data: &DataMatrixData, info: &SymbolInfo) -> Mapping {
+        let rows = info.region_rows * (info.rows / (info.region_rows + 2));
+        let columns = info.region_columns * (info.columns / (info.region_columns + 2));
+
+        let mut bits = vec![];
+
+        for row in 0..rows {
+            let y = row / info.region_rows * (info.region_rows + 2) + 1 + row % info.region_rows;
+
+            for column in 0..columns {
+                let x = column / info.region_columns * (info.region_columns + 2)
+                    + 1
+                    + column % info.region_columns;
+
+                bits.push(data[[x, y]]);
+            }
+        }
+
+        Mapping {
+            bits,
+            read: vec![false; (rows * columns) as usize],
+            rows: rows as i32,
+            columns: columns as i32,
+        }
+    }
+
+    fn module(&mut self, mut row: i32, mut column: i32) -> u8 {
+        // Modules outside of the matrix wrap around to the other side
+        if row < 0 {
+            row += self.rows;
+            column += 4 - ((self.rows + 4) % 8);
+        }
+
+        if column < 0 {
+            column += self.columns;
+            row += 4 - ((self.columns + 4) % 8);
+        }
+
+        let index = (row * self.columns + column) as usize;
+        self.read[index] = true;
+        self.bits[index]
+    }
+
+    fn codeword(&mut self, modules: [(i32, i32); 8]) -> u8 {
+        modules.iter().fold(0, |codeword, (row, column)| {
+            codeword * 2 + self.module(*row, *column)
+        })
+    }
+
+    // The standard shape of a codeword, with the last bit at the provided position
+    fn utah(&mut self, row: i32, column: i32) -> u8 {
+        self.codeword([
+            (row - 2, column - 2),
+            (row - 2, column - 1),
+            (row - 1, column - 2),
+            (row - 1, column - 1),
+            (row - 1, column),
+            (row, column - 2),
+            (row, column - 1),
+            (row, column),
+        ])
+    }
+
+    fn corner1(&mut self) -> u8 {
+        let (r, c) = (self.rows, self.columns);
+        self.codeword([
+            (r - 1, 0),
+            (r - 1, 1),
+            (r - 1, 2),
+            (0, c - 2),
+            (0, c - 1),
+            (1, c - 1),
+            (2, c - 1),
+            (3, c - 1),
+        ])
+    }
+
+    fn corner2(&mut self) -> u8 {
+        let (r, c) = (self.rows, self.columns);
+        self.codeword([
+            (r - 3, 0),
+            (r - 2, 0),
+            (r - 1, 0),
+            (0, c - 4),
+            (0, c - 3),
+            (0, c - 2),
+            (0, c - 1),
+            (1, c - 1),
+        ])
+    }
+
+    fn corner3(&mut self) -> u8 {
+        let (r, c) = (self.rows, self.columns);
+        self.codeword([
+            (r - 1, 0),
+            (r - 1, c - 1),
+            (0, c - 3),
+            (0, c - 2),
+            (0, c - 1),
+            (1, c - 3),
+            (1, c - 2),
+            (1, c - 1),
+        ])
+    }
+
+    fn corner4(&mut self) -> u8 {
+        let (r, c) = (self.rows, self.columns);
+        self.codeword([
+            (r - 3, 0),
+            (r - 2, 0),
+            (r - 1, 0),
+            (0, c - 2),
+            (0, c - 1),
+            (1, c - 1),
+            (2, c - 1),
+            (3, c - 1),
+        ])
+    }
+
+    fn is_read(&self, row: i32, column: i32) -> bool {
+        self.read[(row * self.columns + column) as usize]
+    }
+}
+
+/// Read all codewords from the symbol, in the diagonal zigzag order of ECC 200
+pub fn codewords(data: &DataMatrixData, info: &SymbolInfo) -> Result<Vec<u8>, DataMatrixError> {
+    let mut mapping = Mapping::new(data, info);
+    let (rows, columns) = (mapping.rows, mapping.columns);
+
+    let mut codewords = vec![];
+    let mut corners = [false; 4];
+
+    let mut row = 4;
+    let mut column = 0;
+
+    loop {
+        // Some sizes have a special codeword shape in one of the corners
+        if row == rows && column == 0 && !corners[0] {
+            codewords.push(mapping.corner1());
+            corners[0] = true;
+            row -= 2;
+            column += 2;
+        } else if row == rows - 2 && column == 0 && columns % 4 != 0 && !corners[1] {
+            codewords.push(mapping.corner2());
+            corners[1] = true;
+            row -= 2;
+            column += 2;
+        } else if row == rows + 4 && column == 2 && columns % 8 == 0 && !corners[2] {
+            codewords.push(mapping.corner3());
+            corners[2] = true;
+            row -= 2;
+            column += 2;
+        } else if row == rows - 2 && column == 0 && columns % 8 == 4 && !corners[3] {
+            codewords.push(mapping.corner4());
+            corners[3] = true;
+            row -= 2;
+            column += 2;
+        } else {
+            // Sweep upwards to the right
+            loop {
+                if row < rows && column >= 0 && !mapping.is_read(row, column) {
+                    codewords.push(mapping.utah(row, column));
+                }
+
+                row -= 2;
+                column += 2;
+
+                if row < 0 || column >= columns {
+                    break;
+                }
+            }
+
+            row += 1;
+            column += 3;
+
+            // Sweep downwards to the left
+            loop {
+                if row >= 0 && column < columns && !mapping.is_read(row, column) {
+                    codewords.push(mapping.utah(row, column));
+                }
+
+                row += 2;
+                column -= 2;
+
+                if row >= rows || column < 0 {
+                    break;
+                }
+            }
+
+            row += 3;
+            column += 1;
+        }
+
+        if row >= rows && column >= columns {
+            break;
+        }
+    }
+
+    if codewords.len() != info.total() as usize {
+        return Err(DataMatrixError {
+            msg: format!(
+                "Expected {} codewords but found {}",
+                info.total(),
+                codewords.len()
+            ),
+        });
+    }
+
+    Ok(codewords)
+}
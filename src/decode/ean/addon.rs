@@ -0,0 +1,122 @@
+use super::digits::{digit, guard, to_string};
+
+use crate::util::ean::EANError;
+
+// Parity of a 5 digit add-on, one bit per digit with 1 for G, which encodes its check digit
+const FIVE_DIGIT_CHECK: [u8; 10] = [
+    0b1_1000, 0b1_0100, 0b1_0010, 0b1_0001, 0b0_1100, 0b0_0110, 0b0_0011, 0b0_1010, 0b0_1001,
+    0b0_0101,
+];
+
+const ADDON_GUARD: [u32; 3] = [1, 1, 2];
+const SEPARATOR: [u32; 2] = [1, 1];
+
+/// Decode a 2 or 5 digit add-on from its bars and spaces
+pub fn addon(runs: &[u32]) -> Result<String, EANError> {
+    let (count, modules) = match runs.len() {
+        13 => (2, 20),
+        31 => (5, 47),
+        _ => {
+            return Err(EANError {
+                msg: format!(
+                    "Unexpected number of bars and spaces {} in add-on",
+                    runs.len()
+                ),
+            })
+        }
+    };
+
+    let module = f64::from(runs.iter().sum::<u32>()) / f64::from(modules);
+
+    if !guard(&runs[..3], &ADDON_GUARD, module) {
+        return Err(EANError {
+            msg: String::from("Invalid add-on guard pattern"),
+        });
+    }
+
+    let mut digits = vec![];
+    let mut parity = 0;
+
+    for i in 0..count {
+        let start = 3 + i * 6;
+
+        if i > 0 && !guard(&runs[start - 2..start], &SEPARATOR, module) {
+            return Err(EANError {
+                msg: format!("Invalid add-on separator before digit {}", i),
+            });
+        }
+
+        let (d, g) = digit(&runs[start..start + 4], true).ok_or(EANError {
+            msg: format!("Invalid add-on digit pattern at {}", i),
+        })?;
+
+        digits.push(d);
+        parity = (parity << 1) | u8::from(g);
+    }
+
+    let expected = if count == 2 {
+        // The parity of a 2 digit add-on encodes its value modulo 4
+        (digits[0] * 10 + digits[1]) % 4
+    } else {
+        let sum: u32 = digits
+            .iter()
+            .enumerate()
+            .map(|(i, d)| {
+                u32::from(*d)
+                    * match i % 2 {
+                        0 => 3,
+                        _ => 9,
+                    }
+            })
+            .sum();
+
+        FIVE_DIGIT_CHECK[(sum % 10) as usize]
+    };
+
+    if parity != expected {
+        return Err(EANError {
+            msg: format!(
+                "Invalid parity {:b} for add-on {}",
+                parity,
+                to_string(&digits)
+            ),
+        });
+    }
+
+    Ok(to_string(&digits))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_addon() {
+        // "12", value modulo 4 is 0 so LL
+        let mut runs = ADDON_GUARD.to_vec();
+        runs.extend(&[2, 2, 2, 1]);
+        runs.extend(&SEPARATOR);
+        runs.extend(&[2, 1, 2, 2]);
+
+        assert_eq!(Ok(String::from("12")), addon(&runs));
+
+        // "13" needs LG
+        runs.truncate(9);
+        runs.extend(&[1, 4, 1, 1]);
+        assert!(addon(&runs).is_err());
+
+        // "52495", check digit 3 * (5 + 4 + 5) + 9 * (2 + 9) = 141 so GLGLL
+        let mut runs = ADDON_GUARD.to_vec();
+        runs.extend(&[1, 3, 2, 1]);
+        runs.extend(&SEPARATOR);
+        runs.extend(&[2, 1, 2, 2]);
+        runs.extend(&SEPARATOR);
+        runs.extend(&[2, 3, 1, 1]);
+        runs.extend(&SEPARATOR);
+        runs.extend(&[3, 1, 1, 2]);
+        runs.extend(&SEPARATOR);
+        runs.extend(&[1, 2, 3, 1]);
+
+        assert_eq!(Ok(String::from("52495")), addon(&runs));
+    }
+}
@@ -0,0 +1,88 @@
+use super::super::Decode;
+use super::addon::addon;
+use super::symbol::symbol;
+
+use crate::util::ean::{EANData, EANError, EANInfo};
+
+/// Decode an EAN-13, EAN-8, UPC-A or UPC-E barcode into a resulting String
+///
+/// The digits of an add-on are appended after a space
+///
+/// This decoder will, in order:
+/// * Determine the symbology from the number of bars and spaces
+/// * Match the digit patterns, reading the barcode from right to left if it is upside down
+/// * Determine any digits encoded in the parity of the digit patterns and validate the check digit
+/// * Decode the 2 or 5 digit add-on, if present
+pub struct EANDecoder {}
+
+impl EANDecoder {
+    /// Construct a new EANDecoder
+    pub fn new() -> EANDecoder {
+        EANDecoder {}
+    }
+}
+
+impl Decode<EANData, String, EANError> for EANDecoder {
+    fn decode(&self, data: Result<EANData, EANError>) -> Result<String, EANError> {
+        let info = decode_ean(&data?)?;
+
+        match info.addon {
+            Some(addon) => Ok(format!("{} {}", info.digits, addon)),
+            None => Ok(info.digits),
+        }
+    }
+}
+
+/// Decode an EAN-13, EAN-8, UPC-A or UPC-E barcode into a resulting String. It also includes some information about the decoded barcode.
+///
+/// Functions the same as EANDecoder, apart from also returning the symbology and the add-on separately
+pub struct EANDecoderWithInfo {}
+
+impl EANDecoderWithInfo {
+    /// Construct a new EANDecoderWithInfo
+    pub fn new() -> EANDecoderWithInfo {
+        EANDecoderWithInfo {}
+    }
+}
+
+impl Decode<EANData, (String, EANInfo), EANError> for EANDecoderWithInfo {
+    fn decode(&self, data: Result<EANData, EANError>) -> Result<(String, EANInfo), EANError> {
+        let info = decode_ean(&data?)?;
+
+        let text = match &info.addon {
+            Some(addon) => format!("{} {}", info.digits, addon),
+            None => info.digits.clone(),
+        };
+
+        Ok((text, info))
+    }
+}
+
+fn decode_ean(ean_data: &EANData) -> Result<EANInfo, EANError> {
+    // An upside down barcode is scanned from right to left
+    let (symbology, digits, reversed) = match symbol(&ean_data.runs) {
+        Ok((symbology, digits)) => (symbology, digits, false),
+        Err(e) => {
+            let reversed: Vec<u32> = ean_data.runs.iter().rev().copied().collect();
+
+            match symbol(&reversed) {
+                Ok((symbology, digits)) => (symbology, digits, true),
+                Err(_) => return Err(e),
+            }
+        }
+    };
+
+    debug!("{:?} {} REVERSED {}", symbology, digits, reversed);
+
+    let addon = match &ean_data.addon {
+        Some(runs) if reversed => Some(addon(&runs.iter().rev().copied().collect::<Vec<_>>())?),
+        Some(runs) => Some(addon(runs)?),
+        None => None,
+    };
+
+    Ok(EANInfo {
+        symbology,
+        digits,
+        addon,
+    })
+}
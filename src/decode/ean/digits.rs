@@ -0,0 +1,119 @@
+// Widths of the L patterns in modules, space first
+// The R patterns have the same widths but start with a bar, the G patterns are the R patterns reversed
+const L: [[u32; 4]; 10] = [
+    [3, 2, 1, 1],
+    [2, 2, 2, 1],
+    [2, 1, 2, 2],
+    [1, 4, 1, 1],
+    [1, 1, 3, 2],
+    [1, 2, 3, 1],
+    [1, 1, 1, 4],
+    [1, 3, 1, 2],
+    [1, 2, 1, 3],
+    [3, 1, 1, 2],
+];
+
+// Maximum deviation of a single bar or space from the pattern, in modules
+const MAX_INDIVIDUAL_VARIANCE: f64 = 0.7;
+
+// Maximum total deviation of all bars and spaces from the pattern, in modules
+const MAX_TOTAL_VARIANCE: f64 = 1.9;
+
+/// Match the widths of a single digit against the L and R patterns, and the G patterns if allowed
+///
+/// Returns the digit and whether it matched a G pattern
+pub fn digit(runs: &[u32], allow_g: bool) -> Option<(u8, bool)> {
+    let total: u32 = runs.iter().sum();
+
+    if runs.len() != 4 || total == 0 {
+        return None;
+    }
+
+    let scale = 7.0 / f64::from(total);
+    let mut best: Option<(u8, bool, f64)> = None;
+
+    for (d, pattern) in L.iter().enumerate() {
+        let mut options = vec![(false, *pattern)];
+
+        if allow_g {
+            let mut reversed = *pattern;
+            reversed.reverse();
+            options.push((true, reversed));
+        }
+
+        for (g, widths) in options {
+            let deviations: Vec<f64> = runs
+                .iter()
+                .zip(widths.iter())
+                .map(|(run, width)| (f64::from(*run) * scale - f64::from(*width)).abs())
+                .collect();
+
+            if deviations.iter().any(|d| *d > MAX_INDIVIDUAL_VARIANCE) {
+                continue;
+            }
+
+            let variance: f64 = deviations.iter().sum();
+
+            if variance <= MAX_TOTAL_VARIANCE && best.map(|b| variance < b.2).unwrap_or(true) {
+                best = Some((d as u8, g, variance));
+            }
+        }
+    }
+
+    best.map(|(d, g, _)| (d, g))
+}
+
+/// Check that the runs form a guard pattern with the provided widths in modules
+pub fn guard(runs: &[u32], widths: &[u32], module: f64) -> bool {
+    runs.len() == widths.len()
+        && runs.iter().zip(widths).all(|(run, width)| {
+            (f64::from(*run) - f64::from(*width) * module).abs() < MAX_INDIVIDUAL_VARIANCE * module
+        })
+}
+
+/// Check digit of the provided digits, using weights 3 and 1 starting from the right
+pub fn check_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| {
+            u32::from(*d)
+                * match i % 2 {
+                    0 => 3,
+                    _ => 1,
+                }
+        })
+        .sum();
+
+    ((10 - sum % 10) % 10) as u8
+}
+
+/// Convert digits into a String
+pub fn to_string(digits: &[u8]) -> String {
+    digits.iter().map(|d| (b'0' + d) as char).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_digit() {
+        assert_eq!(Some((0, false)), digit(&[6, 4, 2, 2], false));
+        assert_eq!(Some((0, true)), digit(&[2, 2, 4, 6], true));
+        assert_eq!(None, digit(&[2, 2, 4, 6], false));
+
+        // Bars slightly wider than the spaces
+        assert_eq!(Some((7, false)), digit(&[2, 10, 2, 7], false));
+
+        assert_eq!(None, digit(&[5, 5, 5, 5], true));
+    }
+
+    #[test]
+    pub fn test_check_digit() {
+        assert_eq!(1, check_digit(&[4, 0, 0, 6, 3, 8, 1, 3, 3, 3, 9, 3]));
+        assert_eq!(2, check_digit(&[0, 3, 6, 0, 0, 0, 2, 9, 1, 4, 5]));
+        assert_eq!(4, check_digit(&[9, 6, 3, 8, 5, 0, 7]));
+    }
+}
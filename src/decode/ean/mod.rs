@@ -0,0 +1,4 @@
+pub mod addon;
+pub mod decoder;
+pub mod digits;
+pub mod symbol;
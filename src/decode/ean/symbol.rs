@@ -0,0 +1,281 @@
+use super::digits::{check_digit, digit, guard, to_string};
+
+use crate::util::ean::{EANError, EANSymbology};
+
+// Parity of the left half of an EAN-13, one bit per digit with 1 for G, which encodes the first digit
+const FIRST_DIGIT: [u8; 10] = [
+    0b00_0000, 0b00_1011, 0b00_1101, 0b00_1110, 0b01_0011, 0b01_1001, 0b01_1100, 0b01_0101,
+    0b01_0110, 0b01_1010,
+];
+
+// Parity of a UPC-E with number system 0, one bit per digit with 1 for G, which encodes the check digit
+// Number system 1 uses the inverse parity
+const UPC_E_CHECK_DIGIT: [u8; 10] = [
+    0b11_1000, 0b11_0100, 0b11_0010, 0b11_0001, 0b10_1100, 0b10_0110, 0b10_0011, 0b10_1010,
+    0b10_1001, 0b10_0101,
+];
+
+const GUARD: [u32; 3] = [1, 1, 1];
+const MIDDLE_GUARD: [u32; 5] = [1, 1, 1, 1, 1];
+const UPC_E_END_GUARD: [u32; 6] = [1, 1, 1, 1, 1, 1];
+
+/// Decode the main symbol of an EAN or UPC barcode from its bars and spaces
+///
+/// The symbology is determined from the number of bars and spaces
+pub fn symbol(runs: &[u32]) -> Result<(EANSymbology, String), EANError> {
+    match runs.len() {
+        59 => ean13(runs),
+        43 => ean8(runs),
+        33 => upce(runs),
+        _ => Err(EANError {
+            msg: format!("Unexpected number of bars and spaces {}", runs.len()),
+        }),
+    }
+}
+
+fn ean13(runs: &[u32]) -> Result<(EANSymbology, String), EANError> {
+    let module = module_size(runs, 95);
+
+    check_guards(
+        runs,
+        module,
+        &[(0, &GUARD), (27, &MIDDLE_GUARD), (56, &GUARD)],
+    )?;
+
+    let (left, parity) = digits(&runs[3..27], true)?;
+    let (right, _) = digits(&runs[32..56], false)?;
+
+    let first = FIRST_DIGIT
+        .iter()
+        .position(|p| *p == parity)
+        .ok_or(EANError {
+            msg: format!("Invalid parity {:06b}", parity),
+        })?;
+
+    let mut all = vec![first as u8];
+    all.extend(left);
+    all.extend(right);
+
+    verify(&all, &all)?;
+
+    // A UPC-A is an EAN-13 starting with 0
+    if first == 0 {
+        Ok((EANSymbology::UPCA, to_string(&all[1..])))
+    } else {
+        Ok((EANSymbology::EAN13, to_string(&all)))
+    }
+}
+
+fn ean8(runs: &[u32]) -> Result<(EANSymbology, String), EANError> {
+    let module = module_size(runs, 67);
+
+    check_guards(
+        runs,
+        module,
+        &[(0, &GUARD), (19, &MIDDLE_GUARD), (40, &GUARD)],
+    )?;
+
+    let (mut all, _) = digits(&runs[3..19], false)?;
+    let (right, _) = digits(&runs[24..40], false)?;
+    all.extend(right);
+
+    verify(&all, &all)?;
+
+    Ok((EANSymbology::EAN8, to_string(&all)))
+}
+
+fn upce(runs: &[u32]) -> Result<(EANSymbology, String), EANError> {
+    let module = module_size(runs, 51);
+
+    check_guards(runs, module, &[(0, &GUARD), (27, &UPC_E_END_GUARD)])?;
+
+    let (middle, parity) = digits(&runs[3..27], true)?;
+
+    let (number_system, check) = UPC_E_CHECK_DIGIT
+        .iter()
+        .position(|p| *p == parity)
+        .map(|c| (0, c))
+        .or_else(|| {
+            UPC_E_CHECK_DIGIT
+                .iter()
+                .position(|p| *p == !parity & 0b11_1111)
+                .map(|c| (1, c))
+        })
+        .ok_or(EANError {
+            msg: format!("Invalid parity {:06b}", parity),
+        })?;
+
+    let mut all = vec![number_system];
+    all.extend(&middle);
+    all.push(check as u8);
+
+    // The check digit is calculated over the equivalent UPC-A
+    let mut upca = vec![number_system];
+    upca.extend(match middle[5] {
+        0..=2 => vec![
+            middle[0], middle[1], middle[5], 0, 0, 0, 0, middle[2], middle[3], middle[4],
+        ],
+        3 => vec![
+            middle[0], middle[1], middle[2], 0, 0, 0, 0, 0, middle[3], middle[4],
+        ],
+        4 => vec![
+            middle[0], middle[1], middle[2], middle[3], 0, 0, 0, 0, 0, middle[4],
+        ],
+        _ => vec![
+            middle[0], middle[1], middle[2], middle[3], middle[4], 0, 0, 0, 0, middle[5],
+        ],
+    });
+    upca.push(check as u8);
+
+    verify(&upca, &all)?;
+
+    Ok((EANSymbology::UPCE, to_string(&all)))
+}
+
+// Module size, estimated from the total width of the runs
+fn module_size(runs: &[u32], modules: u32) -> f64 {
+    f64::from(runs.iter().sum::<u32>()) / f64::from(modules)
+}
+
+fn check_guards(runs: &[u32], module: f64, guards: &[(usize, &[u32])]) -> Result<(), EANError> {
+    for (start, widths) in guards {
+        if !guard(&runs[*start..*start + widths.len()], widths, module) {
+            return Err(EANError {
+                msg: format!("Invalid guard pattern at {}", start),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// Decode consecutive digits of four runs each, returning the digits and their parity with 1 for G
+fn digits(runs: &[u32], allow_g: bool) -> Result<(Vec<u8>, u8), EANError> {
+    let mut digits = vec![];
+    let mut parity = 0;
+
+    for (i, chunk) in runs.chunks(4).enumerate() {
+        let (d, g) = digit(chunk, allow_g).ok_or(EANError {
+            msg: format!("Invalid digit pattern {:?} at {}", chunk, i),
+        })?;
+
+        digits.push(d);
+        parity = (parity << 1) | u8::from(g);
+    }
+
+    Ok((digits, parity))
+}
+
+// Verify the check digit, which is the last of the digits
+fn verify(digits: &[u8], shown: &[u8]) -> Result<(), EANError> {
+    let (check, rest) = digits.split_last().unwrap();
+
+    if check_digit(rest) != *check {
+        return Err(EANError {
+            msg: format!("Invalid check digit for {}", to_string(shown)),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const R: [[u32; 4]; 10] = [
+        [3, 2, 1, 1],
+        [2, 2, 2, 1],
+        [2, 1, 2, 2],
+        [1, 4, 1, 1],
+        [1, 1, 3, 2],
+        [1, 2, 3, 1],
+        [1, 1, 1, 4],
+        [1, 3, 1, 2],
+        [1, 2, 1, 3],
+        [3, 1, 1, 2],
+    ];
+
+    // Construct the runs of a digit, with a G pattern if the parity is set
+    fn digit_runs(d: &str, parity: &str) -> Vec<u32> {
+        d.bytes()
+            .zip(parity.bytes())
+            .flat_map(|(d, p)| {
+                let mut widths = R[(d - b'0') as usize];
+                if p == b'G' {
+                    widths.reverse();
+                }
+                widths.to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_ean13() {
+        let mut runs = GUARD.to_vec();
+        runs.extend(digit_runs("006381", "LGLLGG"));
+        runs.extend(&MIDDLE_GUARD);
+        runs.extend(digit_runs("333931", "LLLLLL"));
+        runs.extend(&GUARD);
+
+        let runs: Vec<u32> = runs.iter().map(|r| r * 3).collect();
+
+        assert_eq!(
+            Ok((EANSymbology::EAN13, String::from("4006381333931"))),
+            symbol(&runs)
+        );
+
+        // A 9 instead of the 3 following the middle guard
+        let mut wrong = runs.clone();
+        wrong[32..36].copy_from_slice(&[9, 3, 3, 6]);
+        assert!(symbol(&wrong).is_err());
+    }
+
+    #[test]
+    pub fn test_upca() {
+        let mut runs = GUARD.to_vec();
+        runs.extend(digit_runs("036000", "LLLLLL"));
+        runs.extend(&MIDDLE_GUARD);
+        runs.extend(digit_runs("291452", "LLLLLL"));
+        runs.extend(&GUARD);
+
+        assert_eq!(
+            Ok((EANSymbology::UPCA, String::from("036000291452"))),
+            symbol(&runs)
+        );
+    }
+
+    #[test]
+    pub fn test_ean8() {
+        let mut runs = GUARD.to_vec();
+        runs.extend(digit_runs("9638", "LLLL"));
+        runs.extend(&MIDDLE_GUARD);
+        runs.extend(digit_runs("5074", "LLLL"));
+        runs.extend(&GUARD);
+
+        assert_eq!(
+            Ok((EANSymbology::EAN8, String::from("96385074"))),
+            symbol(&runs)
+        );
+    }
+
+    #[test]
+    pub fn test_upce() {
+        // 0 123456 5, equivalent to UPC-A 012345000065
+        let mut runs = GUARD.to_vec();
+        runs.extend(digit_runs("123456", "GLLGGL"));
+        runs.extend(&UPC_E_END_GUARD);
+
+        assert_eq!(
+            Ok((EANSymbology::UPCE, String::from("01234565"))),
+            symbol(&runs)
+        );
+
+        // Number system 1 uses the inverse parity, which results in a different check digit
+        let mut runs = GUARD.to_vec();
+        runs.extend(digit_runs("123456", "LGGLLG"));
+        runs.extend(&UPC_E_END_GUARD);
+
+        assert!(symbol(&runs).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use super::super::ean::digits::{check_digit, to_string};
+use super::super::linear::{either_direction, narrow_wide};
+use super::super::Decode;
+
+use crate::util::linear::{LinearData, LinearError, LinearSymbology};
+
+// Narrow and wide runs of the digits, with 1 for wide
+const PATTERNS: [u32; 10] = [
+    0b00110, 0b10001, 0b01001, 0b11000, 0b00101, 0b10100, 0b01100, 0b00011, 0b10010, 0b01010,
+];
+
+/// Decode an ITF barcode into a resulting String of digits
+///
+/// This decoder will, in order:
+/// * Classify the bars and spaces of every pair of digits as narrow or wide, reading the barcode from right to left if it is upside down
+/// * Validate the number of digits against the allowed lengths
+/// * Validate the GS1 check digit, if enabled
+///
+/// ITF has no check character of its own, and a scan line that misses part of the barcode can still form a valid shorter barcode.
+/// Restricting the allowed lengths guards against such partial reads, so ITF-14 should be decoded with `ITFDecoder::new(&[14], true)`.
+pub struct ITFDecoder {
+    lengths: Vec<usize>,
+    check_digit: bool,
+}
+
+impl ITFDecoder {
+    /// Construct a new ITFDecoder
+    ///
+    /// * lengths: the allowed numbers of digits, or any number of at least 6 digits if empty
+    /// * check_digit: the last digit is a GS1 modulo 10 check digit, as in ITF-14
+    pub fn new(lengths: &[usize], check_digit: bool) -> ITFDecoder {
+        ITFDecoder {
+            lengths: lengths.to_vec(),
+            check_digit,
+        }
+    }
+}
+
+impl Decode<LinearData, String, LinearError> for ITFDecoder {
+    fn decode(&self, data: Result<LinearData, LinearError>) -> Result<String, LinearError> {
+        let linear_data = data?;
+
+        if linear_data.symbology != LinearSymbology::ITF {
+            return Err(LinearError {
+                msg: format!("Cannot decode {:?} as ITF", linear_data.symbology),
+            });
+        }
+
+        // An upside down barcode is scanned from right to left
+        let digits = either_direction(&linear_data.runs, digits)?;
+
+        debug!("ITF DIGITS {:?}", digits);
+
+        let valid = if self.lengths.is_empty() {
+            digits.len() >= 6
+        } else {
+            self.lengths.contains(&digits.len())
+        };
+
+        if !valid {
+            return Err(LinearError {
+                msg: format!("Unexpected number of digits {}", digits.len()),
+            });
+        }
+
+        if self.check_digit {
+            let (check, rest) = digits.split_last().unwrap();
+
+            if check_digit(rest) != *check {
+                return Err(LinearError {
+                    msg: format!(
+                        "Invalid check digit {}, expected {}",
+                        check,
+                        check_digit(rest)
+                    ),
+                });
+            }
+        }
+
+        Ok(to_string(&digits))
+    }
+}
+
+// Read the digits between the start and stop patterns
+fn digits(runs: &[u32]) -> Result<Vec<u8>, LinearError> {
+    // Start pattern of 4 runs, pairs of digits of 10 runs and stop pattern of 3 runs
+    let valid = match runs.len() % 10 {
+        7 => runs.len() >= 17,
+        _ => false,
+    };
+
+    if !valid {
+        return Err(LinearError {
+            msg: format!("Unexpected number of bars and spaces {}", runs.len()),
+        });
+    }
+
+    let (start, rest) = runs.split_at(4);
+    let (pairs, stop) = rest.split_at(rest.len() - 3);
+
+    // The runs of the start pattern are all narrow, but may differ a pixel due to rounding
+    let narrow = f64::from(start.iter().sum::<u32>()) / 4.0;
+    let is_start = start
+        .iter()
+        .all(|w| (f64::from(*w) - narrow).abs() < 0.5 * narrow);
+
+    if !is_start || narrow_wide(stop) != Some(0b100) {
+        return Err(LinearError {
+            msg: String::from("Missing start or stop pattern"),
+        });
+    }
+
+    let mut digits = vec![];
+
+    for (i, pair) in pairs.chunks(10).enumerate() {
+        // The bars encode the first digit and the spaces the second
+        let bars: Vec<u32> = pair.iter().step_by(2).copied().collect();
+        let spaces: Vec<u32> = pair.iter().skip(1).step_by(2).copied().collect();
+
+        for runs in [bars, spaces].iter() {
+            let digit = narrow_wide(runs)
+                .and_then(|pattern| PATTERNS.iter().position(|p| *p == pattern))
+                .ok_or(LinearError {
+                    msg: format!("Invalid digit pattern {:?} in pair {}", runs, i),
+                })?;
+
+            digits.push(digit as u8);
+        }
+    }
+
+    Ok(digits)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Widths of the runs for the digits, including the start and stop patterns
+    fn runs(digits: &str) -> Vec<u32> {
+        let digits: Vec<usize> = digits.bytes().map(|d| usize::from(d - b'0')).collect();
+
+        let mut runs = vec![2, 2, 2, 2];
+        for pair in digits.chunks(2) {
+            for i in (0..5).rev() {
+                for digit in pair {
+                    runs.push(match (PATTERNS[*digit] >> i) & 1 {
+                        1 => 5,
+                        _ => 2,
+                    });
+                }
+            }
+        }
+        runs.extend([5, 2, 2].iter());
+
+        runs
+    }
+
+    #[test]
+    pub fn test_decode() {
+        let decode = |runs, lengths: &[usize], check| {
+            ITFDecoder::new(lengths, check).decode(Ok(LinearData::new(LinearSymbology::ITF, runs)))
+        };
+
+        assert_eq!(
+            Ok(String::from("15400141288763")),
+            decode(runs("15400141288763"), &[14], true)
+        );
+        assert_eq!(
+            Ok(String::from("123456")),
+            decode(runs("123456"), &[], false)
+        );
+
+        // Invalid check digit
+        assert!(decode(runs("15400141288764"), &[14], true).is_err());
+
+        // Length not allowed
+        assert!(decode(runs("1234567890"), &[14], false).is_err());
+        assert!(decode(runs("1234"), &[], false).is_err());
+
+        // Upside down
+        let mut reversed = runs("15400141288763");
+        reversed.reverse();
+        assert_eq!(
+            Ok(String::from("15400141288763")),
+            decode(reversed, &[14], true)
+        );
+    }
+}
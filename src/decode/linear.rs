@@ -0,0 +1,89 @@
+use crate::util::linear::LinearError;
+
+/// Decode the runs as read along the scan line, or reversed if that fails because the barcode is upside down
+///
+/// Returns the error of the first attempt if both fail
+pub fn either_direction<T, F>(runs: &[u32], decode: F) -> Result<T, LinearError>
+where
+    F: Fn(&[u32]) -> Result<T, LinearError>,
+{
+    match decode(runs) {
+        Ok(result) => Ok(result),
+        Err(e) => {
+            let reversed: Vec<u32> = runs.iter().rev().copied().collect();
+            decode(&reversed).map_err(|_| e)
+        }
+    }
+}
+
+/// Classify the bars and spaces of a character as narrow or wide
+///
+/// Returns a bit pattern with 1 for wide, with the first run in the most significant bit.
+/// Runs wider than the mean of the narrowest and the widest are wide, which must be at least 1.5 times as wide as narrow runs.
+pub fn narrow_wide(runs: &[u32]) -> Option<u32> {
+    let min = *runs.iter().min()?;
+    let max = *runs.iter().max()?;
+
+    if 2 * max < 3 * min {
+        return None;
+    }
+
+    Some(
+        runs.iter()
+            .fold(0, |bits, run| (bits << 1) | u32::from(2 * run > min + max)),
+    )
+}
+
+/// Reverse the order of the lowest bits of a pattern
+pub fn reverse_bits(bits: u32, count: usize) -> u32 {
+    (0..count).fold(0, |reversed, i| (reversed << 1) | ((bits >> i) & 1))
+}
+
+/// Expand a character shifted by `$`, `%`, `/` or `+` in Full ASCII mode, as used by Code 39 and Code 93
+pub fn full_ascii(shift: char, c: char) -> Option<char> {
+    let value = match (shift, c) {
+        ('$', 'A'..='Z') => c as u8 - b'A' + 1,
+        ('%', 'A'..='E') => c as u8 - b'A' + 27,
+        ('%', 'F'..='J') => c as u8 - b'F' + b';',
+        ('%', 'K'..='O') => c as u8 - b'K' + b'[',
+        ('%', 'P'..='T') => c as u8 - b'P' + b'{',
+        ('%', 'U') => 0,
+        ('%', 'V') => b'@',
+        ('%', 'W') => b'`',
+        ('%', 'X'..='Z') => 127,
+        ('/', 'A'..='O') => c as u8 - b'A' + b'!',
+        ('/', 'Z') => b':',
+        ('+', 'A'..='Z') => c as u8 - b'A' + b'a',
+        _ => return None,
+    };
+
+    Some(char::from(value))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_narrow_wide() {
+        assert_eq!(
+            Some(0b010_010_100),
+            narrow_wide(&[2, 5, 2, 2, 6, 2, 5, 2, 2])
+        );
+        assert_eq!(None, narrow_wide(&[3, 3, 4, 3, 3]));
+
+        assert_eq!(0b001_010_010, reverse_bits(0b010_010_100, 9));
+    }
+
+    #[test]
+    pub fn test_full_ascii() {
+        assert_eq!(Some('\u{1}'), full_ascii('$', 'A'));
+        assert_eq!(Some('\u{1F}'), full_ascii('%', 'E'));
+        assert_eq!(Some('?'), full_ascii('%', 'J'));
+        assert_eq!(Some('_'), full_ascii('%', 'O'));
+        assert_eq!(Some('\u{7F}'), full_ascii('%', 'T'));
+        assert_eq!(Some('/'), full_ascii('/', 'O'));
+        assert_eq!(Some('z'), full_ascii('+', 'Z'));
+        assert_eq!(None, full_ascii('/', 'P'));
+    }
+}
@@ -1,8 +1,32 @@
 //! Decode data extracted from an image
 
-mod qr;
+pub(crate) mod aztec;
+mod codabar;
+mod code128;
+mod code39;
+mod code93;
+pub(crate) mod databar;
+mod datamatrix;
+mod ean;
+mod itf;
+pub(crate) mod linear;
+mod pdf417;
+pub(crate) mod qr;
 
-pub use self::qr::decoder::{QRDecoder, QRDecoderWithInfo};
+pub use self::aztec::decoder::AztecDecoder;
+pub use self::codabar::decoder::CodabarDecoder;
+pub use self::code128::decoder::{Code128Decoder, Code128DecoderWithInfo};
+pub use self::code39::decoder::Code39Decoder;
+pub use self::code93::decoder::Code93Decoder;
+pub use self::databar::decoder::{DataBarDecoder, DataBarDecoderWithInfo};
+pub use self::datamatrix::decoder::DataMatrixDecoder;
+pub use self::ean::decoder::{EANDecoder, EANDecoderWithInfo};
+pub use self::itf::decoder::ITFDecoder;
+pub use self::pdf417::decoder::{PDF417Decoder, PDF417DecoderWithInfo};
+pub use self::qr::decoder::{
+    MicroQRDecoder, QRDecoder, QRDecoderBytes, QRDecoderBytesWithInfo, QRDecoderSegments,
+    QRDecoderWithInfo, RMQRDecoder,
+};
 
 /// Decode extracted data into a resulting String
 ///
@@ -35,7 +59,6 @@ pub use self::qr::decoder::{QRDecoder, QRDecoderWithInfo};
 /// [`Extract`]: ../extract/trait.Extract.html
 /// [`here`]: ../extract/trait.Extract.html
 /// [`Decoder`]: ../struct.Decoder.html
-
 pub trait Decode<DATA, RESULT, ERROR> {
     /// Does the actual decoding
     fn decode(&self, data: Result<DATA, ERROR>) -> Result<RESULT, ERROR>;
@@ -0,0 +1,310 @@
+use crate::util::pdf417::PDF417Error;
+
+// GF(929) is a prime field, 3 is a primitive element
+const MODULUS: u32 = 929;
+const PRIMITIVE: u32 = 3;
+
+struct Field {
+    exp: Vec<u32>,
+    log: Vec<u32>,
+}
+
+impl Field {
+    fn new() -> Field {
+        let order = (MODULUS - 1) as usize;
+        let mut exp = vec![0; 2 * order];
+        let mut log = vec![0; MODULUS as usize];
+
+        let mut x = 1;
+        for (i, e) in exp.iter_mut().enumerate() {
+            *e = x;
+
+            if i < order {
+                log[x as usize] = i as u32;
+            }
+
+            x = (x * PRIMITIVE) % MODULUS;
+        }
+
+        Field { exp, log }
+    }
+
+    fn alpha(&self, power: usize) -> u32 {
+        self.exp[power % (MODULUS - 1) as usize]
+    }
+
+    fn add(&self, a: u32, b: u32) -> u32 {
+        (a + b) % MODULUS
+    }
+
+    fn sub(&self, a: u32, b: u32) -> u32 {
+        (MODULUS + a - b) % MODULUS
+    }
+
+    fn mul(&self, a: u32, b: u32) -> u32 {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+
+        self.exp[(self.log[a as usize] + self.log[b as usize]) as usize]
+    }
+
+    fn div(&self, a: u32, b: u32) -> u32 {
+        if a == 0 {
+            return 0;
+        }
+
+        self.exp[(self.log[a as usize] + MODULUS - 1 - self.log[b as usize]) as usize]
+    }
+
+    // Evaluate a polynomial with the lowest degree coefficient first
+    fn eval(&self, poly: &[u32], x: u32) -> u32 {
+        poly.iter()
+            .rev()
+            .fold(0, |acc, c| self.add(self.mul(acc, x), *c))
+    }
+
+    // Multiply two polynomials with the lowest degree coefficient first
+    fn poly_mul(&self, a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut product = vec![0; a.len() + b.len() - 1];
+
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                product[i + j] = self.add(product[i + j], self.mul(*x, *y));
+            }
+        }
+
+        product
+    }
+}
+
+/// Correct a block of codewords, the first codeword being the coefficient of the highest power
+///
+/// The erasures are the positions of codewords that could not be read, their value in the block is ignored.
+/// Two error correction codewords are reserved for error detection, so correction succeeds as long as
+/// `2 * errors + erasures <= ec - 2`.
+///
+/// Returns the corrected block and the number of corrected errors, not including erasures
+pub fn correct(
+    mut block: Vec<u32>,
+    erasures: &[usize],
+    ec: usize,
+) -> Result<(Vec<u32>, usize), PDF417Error> {
+    let field = Field::new();
+    let n = block.len();
+
+    if n > (MODULUS - 1) as usize || ec > n || erasures.iter().any(|e| *e >= n) {
+        return Err(PDF417Error {
+            msg: format!(
+                "Invalid block of {} codewords with {} for error correction",
+                n, ec
+            ),
+        });
+    }
+
+    for erasure in erasures {
+        block[*erasure] = 0;
+    }
+
+    // The generator polynomial has roots 3^1 to 3^ec
+    let syndromes: Vec<u32> = (1..=ec)
+        .map(|i| {
+            block
+                .iter()
+                .fold(0, |acc, c| field.add(field.mul(acc, field.alpha(i)), *c))
+        })
+        .collect();
+
+    if syndromes.iter().all(|s| *s == 0) {
+        debug!("ALL SYNDROMES WERE ZERO, NO CORRECTION NEEDED");
+        return Ok((block, 0));
+    }
+
+    // Error locations as powers of the primitive element
+    let location = |position: usize| field.alpha(n - 1 - position);
+
+    // The erasure locator has a root at the inverse of every erasure location
+    let erasure_locator = erasures.iter().fold(vec![1], |acc, e| {
+        field.poly_mul(&acc, &[1, field.sub(0, location(*e))])
+    });
+
+    // Berlekamp-Massey, starting from the erasure locator to find the combined errata locator
+    let mut sigma = erasure_locator.clone();
+    let mut previous = erasure_locator;
+    let mut degree = erasures.len();
+
+    for r in erasures.len()..ec {
+        let discrepancy = sigma.iter().enumerate().take(r + 1).fold(0, |acc, (j, s)| {
+            field.add(acc, field.mul(*s, syndromes[r - j]))
+        });
+
+        let mut shifted = vec![0];
+        shifted.extend(&previous);
+
+        if discrepancy == 0 {
+            previous = shifted;
+            continue;
+        }
+
+        let mut next = sigma.clone();
+        next.resize(next.len().max(shifted.len()), 0);
+
+        for (j, p) in shifted.iter().enumerate() {
+            next[j] = field.sub(next[j], field.mul(discrepancy, *p));
+        }
+
+        if 2 * degree <= r + erasures.len() {
+            previous = sigma.iter().map(|s| field.div(*s, discrepancy)).collect();
+            degree = r + 1 + erasures.len() - degree;
+        } else {
+            previous = shifted;
+        }
+
+        sigma = next;
+    }
+
+    sigma.resize(degree + 1, 0);
+
+    let errors = degree - erasures.len();
+
+    if 2 * errors + erasures.len() + 2 > ec {
+        return Err(PDF417Error {
+            msg: String::from("Too many errors to correct"),
+        });
+    }
+
+    // Errata evaluator polynomial, omega = syndromes * sigma mod x^ec
+    let mut omega = field.poly_mul(&syndromes, &sigma);
+    omega.truncate(ec);
+
+    // Formal derivative of sigma, in a prime field every power remains
+    let derivative: Vec<u32> = sigma
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, s)| field.mul(i as u32 % MODULUS, *s))
+        .collect();
+
+    // Chien search for the roots of sigma, which are the inverses of the errata locations
+    let mut corrected = 0;
+
+    for (position, codeword) in block.iter_mut().enumerate() {
+        let x_inv = field.div(1, location(position));
+
+        if field.eval(&sigma, x_inv) != 0 {
+            continue;
+        }
+
+        // Forney, the generator starts at 3^1 so no additional factor is needed
+        let magnitude = field.div(field.eval(&omega, x_inv), field.eval(&derivative, x_inv));
+
+        debug!(
+            "FIXING LOCATION {} FROM {} TO {}",
+            position,
+            codeword,
+            field.add(*codeword, magnitude)
+        );
+
+        *codeword = field.add(*codeword, magnitude);
+        corrected += 1;
+    }
+
+    if corrected != degree {
+        return Err(PDF417Error {
+            msg: String::from("Error correcting did not fix corrupted data"),
+        });
+    }
+
+    Ok((block, errors))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Systematic encoding, only used to construct test blocks
+    fn encode(data: &[u32], ec: usize) -> Vec<u32> {
+        let field = Field::new();
+
+        // Highest degree coefficient first
+        let mut generator = vec![1];
+        for i in 1..=ec {
+            let mut next = generator.clone();
+            next.push(0);
+            for (j, g) in generator.iter().enumerate() {
+                next[j + 1] = field.sub(next[j + 1], field.mul(*g, field.alpha(i)));
+            }
+            generator = next;
+        }
+
+        let mut remainder = data.to_vec();
+        remainder.resize(data.len() + ec, 0);
+
+        for i in 0..data.len() {
+            let c = remainder[i];
+            for (j, g) in generator.iter().enumerate().skip(1) {
+                remainder[i + j] = field.sub(remainder[i + j], field.mul(*g, c));
+            }
+        }
+
+        let mut block = data.to_vec();
+        block.extend(remainder[data.len()..].iter().map(|r| field.sub(0, *r)));
+        block
+    }
+
+    #[test]
+    pub fn test_encode() {
+        // Example from ISO/IEC 15438 Annex Q, "PDF417" at error correction level 1
+        assert_eq!(
+            vec![5, 453, 178, 121, 239, 452, 327, 657, 619],
+            encode(&[5, 453, 178, 121, 239], 4)
+        );
+    }
+
+    #[test]
+    pub fn test_correct() {
+        let data: Vec<u32> = (0..40).map(|i| (i * 37 + 11) % 929).collect();
+        let block = encode(&data, 16);
+
+        assert_eq!(Ok((block.clone(), 0)), correct(block.clone(), &[], 16));
+
+        // Errors only, 7 errors leave 2 codewords for detection
+        let mut corrupted = block.clone();
+        for i in 0..7 {
+            corrupted[i * 8] = (corrupted[i * 8] + 100 + i as u32) % 929;
+        }
+        assert_eq!(Ok((block.clone(), 7)), correct(corrupted.clone(), &[], 16));
+
+        corrupted[55] = (corrupted[55] + 1) % 929;
+        assert!(correct(corrupted, &[], 16).is_err());
+
+        // Erasures only
+        let erasures: Vec<usize> = (0..14).map(|i| i * 4).collect();
+        let mut corrupted = block.clone();
+        for e in &erasures {
+            corrupted[*e] = 928 - corrupted[*e];
+        }
+        assert_eq!(
+            Ok((block.clone(), 0)),
+            correct(corrupted.clone(), &erasures, 16)
+        );
+
+        // Errors and erasures combined
+        let erasures = vec![1, 10, 20, 30, 40, 50];
+        let mut corrupted = block.clone();
+        for e in &erasures {
+            corrupted[*e] = 0;
+        }
+        corrupted[3] = (corrupted[3] + 500) % 929;
+        corrupted[33] = (corrupted[33] + 1) % 929;
+        corrupted[55] = (corrupted[55] + 928) % 929;
+        corrupted[44] = (corrupted[44] + 2) % 929;
+        assert_eq!(
+            Ok((block.clone(), 4)),
+            correct(corrupted.clone(), &erasures, 16)
+        );
+
+        corrupted[5] = (corrupted[5] + 1) % 929;
+        assert!(correct(corrupted, &erasures, 16).is_err());
+    }
+}
@@ -0,0 +1,429 @@
+use super::super::qr::eci::Charset;
+
+use crate::util::pdf417::{MacroPDF417, PDF417Error};
+
+const TEXT_LATCH: u32 = 900;
+const BYTE_LATCH: u32 = 901;
+const NUMERIC_LATCH: u32 = 902;
+const BYTE_SHIFT: u32 = 913;
+const MACRO_TERMINATOR: u32 = 922;
+const MACRO_OPTIONAL_FIELD: u32 = 923;
+const BYTE_LATCH_6: u32 = 924;
+const ECI_USER_DEFINED: u32 = 925;
+const ECI_GENERAL_PURPOSE: u32 = 926;
+const ECI_CHARSET: u32 = 927;
+const MACRO_CONTROL_BLOCK: u32 = 928;
+
+const MIXED: &[u8] = b"0123456789&\r\t,:#-.$/+%*=^";
+const PUNCT: &[u8] = b";<>@[\\]_`~!\r\t,:\n-.$/\"|*()?{}'";
+
+pub struct Data {
+    pub text: Result<String, PDF417Error>,
+    pub eci: Vec<u32>,
+    pub macro_pdf417: Option<MacroPDF417>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum SubMode {
+    Alpha,
+    Lower,
+    Mixed,
+    Punct,
+}
+
+struct Reader {
+    codewords: Vec<u32>,
+    pos: usize,
+}
+
+impl Reader {
+    fn next(&mut self) -> Option<u32> {
+        let codeword = self.codewords.get(self.pos).copied();
+        self.pos += 1;
+        codeword
+    }
+
+    fn require(&mut self) -> Result<u32, PDF417Error> {
+        self.next().ok_or(PDF417Error {
+            msg: String::from("Unexpected end of data"),
+        })
+    }
+
+    // Codewords up to the next mode codeword, which are all compacted in the same mode
+    fn run(&mut self) -> Vec<u32> {
+        let start = self.pos.min(self.codewords.len());
+        let length = self.codewords[start..]
+            .iter()
+            .take_while(|c| **c < TEXT_LATCH)
+            .count();
+
+        self.pos = start + length;
+        self.codewords[start..start + length].to_vec()
+    }
+}
+
+// Decoded bytes, split up every time an ECI designator selects a different character set
+struct Output {
+    segments: Vec<(Option<Charset>, Vec<u8>)>,
+}
+
+impl Output {
+    fn new() -> Output {
+        Output {
+            segments: vec![(None, vec![])],
+        }
+    }
+
+    fn push(&mut self, byte: u8) {
+        self.segments.last_mut().unwrap().1.push(byte);
+    }
+
+    fn text(self) -> Result<String, PDF417Error> {
+        let mut text = String::new();
+
+        for (charset, bytes) in self.segments {
+            // The default character set for PDF417 is ISO 8859-1
+            let charset = charset.unwrap_or(Charset::Iso88591);
+            let segment = charset
+                .decode(&bytes)
+                .map_err(|e| PDF417Error { msg: e.msg })?;
+
+            text.push_str(&segment);
+        }
+
+        Ok(text)
+    }
+}
+
+/// Decode the data codewords of a PDF417 Code, without the symbol length descriptor
+pub fn data(codewords: Vec<u32>) -> Result<Data, PDF417Error> {
+    let mut reader = Reader { codewords, pos: 0 };
+    let mut output = Output::new();
+    let mut eci = vec![];
+    let mut macro_pdf417 = None;
+
+    // Every symbol starts in Text Compaction, the sub-mode is kept when the text is interrupted by a Byte Shift or ECI
+    let mut mode = TEXT_LATCH;
+    let mut sub_mode = SubMode::Alpha;
+
+    while let Some(codeword) = reader.next() {
+        trace!("PDF417 CODEWORD {}", codeword);
+
+        match codeword {
+            0..=899 => {
+                reader.pos -= 1;
+
+                match mode {
+                    TEXT_LATCH => text(&reader.run(), &mut output, &mut sub_mode),
+                    NUMERIC_LATCH => {
+                        for digit in numeric(&reader.run())?.bytes() {
+                            output.push(digit);
+                        }
+                    }
+                    _ => byte(&reader.run(), &mut output, mode == BYTE_LATCH_6)?,
+                }
+            }
+            TEXT_LATCH => {
+                mode = TEXT_LATCH;
+                sub_mode = SubMode::Alpha;
+            }
+            BYTE_LATCH | BYTE_LATCH_6 | NUMERIC_LATCH => mode = codeword,
+            BYTE_SHIFT => {
+                let byte = reader.require()?;
+
+                if byte > 255 {
+                    return Err(PDF417Error {
+                        msg: format!("Invalid byte {} after Byte Shift", byte),
+                    });
+                }
+
+                output.push(byte as u8);
+            }
+            ECI_USER_DEFINED | ECI_GENERAL_PURPOSE | ECI_CHARSET => {
+                let designator = match codeword {
+                    ECI_CHARSET => reader.require()?,
+                    ECI_GENERAL_PURPOSE => (reader.require()? + 1) * 900 + reader.require()?,
+                    _ => 810_900 + reader.require()?,
+                };
+
+                let charset =
+                    Charset::from_eci(designator).map_err(|e| PDF417Error { msg: e.msg })?;
+
+                eci.push(designator);
+                output.segments.push((Some(charset), vec![]));
+            }
+            MACRO_CONTROL_BLOCK => macro_pdf417 = Some(macro_block(&mut reader)?),
+            _ => {
+                return Err(PDF417Error {
+                    msg: format!("Unsupported codeword {}", codeword),
+                })
+            }
+        }
+    }
+
+    Ok(Data {
+        text: output.text(),
+        eci,
+        macro_pdf417,
+    })
+}
+
+// Text Compaction, every codeword holds two base 30 values
+fn text(codewords: &[u32], output: &mut Output, sub_mode: &mut SubMode) {
+    let mut shift = None;
+
+    for value in codewords.iter().flat_map(|c| vec![c / 30, c % 30]) {
+        let current = shift.take().unwrap_or(*sub_mode);
+
+        match (current, value) {
+            (SubMode::Alpha, 0..=25) => output.push(b'A' + value as u8),
+            (SubMode::Lower, 0..=25) => output.push(b'a' + value as u8),
+            (SubMode::Mixed, 0..=24) => output.push(MIXED[value as usize]),
+            (SubMode::Punct, 0..=28) => output.push(PUNCT[value as usize]),
+            (_, 26) => output.push(b' '),
+            (SubMode::Alpha, 27) | (SubMode::Mixed, 27) => *sub_mode = SubMode::Lower,
+            (SubMode::Alpha, 28) | (SubMode::Lower, 28) => *sub_mode = SubMode::Mixed,
+            (SubMode::Lower, 27) => shift = Some(SubMode::Alpha),
+            (SubMode::Mixed, 25) => *sub_mode = SubMode::Punct,
+            (SubMode::Mixed, 28) | (SubMode::Punct, 29) => *sub_mode = SubMode::Alpha,
+            // Punctuation shift, at the end of the data this is used as padding
+            (_, _) => shift = Some(SubMode::Punct),
+        }
+    }
+}
+
+// Byte Compaction, five codewords hold six bytes, any remaining codewords hold one byte each
+fn byte(codewords: &[u32], output: &mut Output, six: bool) -> Result<(), PDF417Error> {
+    // When latched with 901 the last group always holds one byte per codeword, even if it has five codewords
+    let groups = if six {
+        codewords.len() / 5
+    } else {
+        codewords.len().saturating_sub(1) / 5
+    };
+
+    for group in codewords.chunks(5).take(groups) {
+        let value = group.iter().fold(0u64, |acc, c| acc * 900 + u64::from(*c));
+
+        if value >> 48 != 0 {
+            return Err(PDF417Error {
+                msg: format!("Invalid Byte Compaction group {:?}", group),
+            });
+        }
+
+        for i in (0..6).rev() {
+            output.push((value >> (8 * i)) as u8);
+        }
+    }
+
+    for codeword in &codewords[groups * 5..] {
+        if *codeword > 255 {
+            return Err(PDF417Error {
+                msg: format!("Invalid byte {} in Byte Compaction", codeword),
+            });
+        }
+
+        output.push(*codeword as u8);
+    }
+
+    Ok(())
+}
+
+// Numeric Compaction, groups of up to fifteen codewords form a base 900 number with a leading 1
+fn numeric(codewords: &[u32]) -> Result<String, PDF417Error> {
+    let mut result = String::new();
+
+    for group in codewords.chunks(15) {
+        // Decimal digits, least significant first
+        let mut digits: Vec<u32> = vec![];
+
+        for codeword in group {
+            let mut carry = *codeword;
+
+            for digit in digits.iter_mut() {
+                let value = *digit * 900 + carry;
+                *digit = value % 10;
+                carry = value / 10;
+            }
+
+            while carry > 0 {
+                digits.push(carry % 10);
+                carry /= 10;
+            }
+        }
+
+        if digits.pop() != Some(1) {
+            return Err(PDF417Error {
+                msg: format!("Invalid Numeric Compaction group {:?}", group),
+            });
+        }
+
+        result.extend(digits.iter().rev().map(|d| (b'0' + *d as u8) as char));
+    }
+
+    Ok(result)
+}
+
+// Macro PDF417 control block, which runs until the end of the data
+fn macro_block(reader: &mut Reader) -> Result<MacroPDF417, PDF417Error> {
+    let segment_index = numeric(&[reader.require()?, reader.require()?])?;
+
+    let mut macro_pdf417 = MacroPDF417 {
+        segment_index: number(&segment_index)? as u32,
+        file_id: reader.run().iter().map(|c| format!("{:03}", c)).collect(),
+        ..Default::default()
+    };
+
+    while let Some(codeword) = reader.next() {
+        match codeword {
+            MACRO_TERMINATOR => macro_pdf417.last_segment = true,
+            MACRO_OPTIONAL_FIELD => {
+                let field = reader.require()?;
+                let codewords = reader.run();
+
+                match field {
+                    0 => macro_pdf417.file_name = Some(text_field(&codewords)?),
+                    1 => macro_pdf417.segment_count = Some(number(&numeric(&codewords)?)? as u32),
+                    2 => macro_pdf417.time_stamp = Some(number(&numeric(&codewords)?)?),
+                    3 => macro_pdf417.sender = Some(text_field(&codewords)?),
+                    4 => macro_pdf417.addressee = Some(text_field(&codewords)?),
+                    5 => macro_pdf417.file_size = Some(number(&numeric(&codewords)?)?),
+                    6 => macro_pdf417.checksum = Some(number(&numeric(&codewords)?)? as u32),
+                    _ => {
+                        return Err(PDF417Error {
+                            msg: format!("Invalid Macro PDF417 optional field {}", field),
+                        })
+                    }
+                }
+            }
+            _ => {
+                return Err(PDF417Error {
+                    msg: format!(
+                        "Unexpected codeword {} in Macro PDF417 control block",
+                        codeword
+                    ),
+                })
+            }
+        }
+    }
+
+    Ok(macro_pdf417)
+}
+
+fn text_field(codewords: &[u32]) -> Result<String, PDF417Error> {
+    let mut output = Output::new();
+    text(codewords, &mut output, &mut SubMode::Alpha);
+    output.text()
+}
+
+fn number(digits: &str) -> Result<u64, PDF417Error> {
+    digits.parse().map_err(|_| PDF417Error {
+        msg: format!("Invalid number {} in Macro PDF417 control block", digits),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn text_of(codewords: Vec<u32>) -> Result<String, PDF417Error> {
+        data(codewords)?.text
+    }
+
+    #[test]
+    pub fn test_text() {
+        // Example from ISO/IEC 15438 Annex Q
+        assert_eq!(
+            Ok(String::from("PDF417")),
+            text_of(vec![453, 178, 121, 239])
+        );
+
+        // "AbC1!", lower latch, alpha shift, mixed latch, punctuation shift, then padding
+        assert_eq!(
+            Ok(String::from("AbC1!")),
+            text_of(vec![27, 57, 88, 59, 329, 900, 900])
+        );
+
+        // Mixed and punctuation latch, then back to alpha
+        assert_eq!(
+            Ok(String::from("1@[Z")),
+            text_of(vec![28 * 30 + 1, 25 * 30 + 3, 4 * 30 + 29, 25 * 30 + 29])
+        );
+    }
+
+    #[test]
+    pub fn test_byte() {
+        // "alcool" as a group of six bytes, followed by "é" as a single byte
+        assert_eq!(
+            Ok(String::from("alcool\u{E9}")),
+            text_of(vec![901, 163, 238, 432, 766, 244, 233])
+        );
+
+        // A group of six with 924, then a single byte after a Byte Shift from Text Compaction
+        assert_eq!(
+            Ok(String::from("alcoolAB\u{E9}C")),
+            text_of(vec![
+                924,
+                163,
+                238,
+                432,
+                766,
+                244,
+                900,
+                1,
+                913,
+                233,
+                2 * 30 + 29
+            ])
+        );
+
+        // With 901 the last group of five codewords holds five bytes
+        assert_eq!(
+            Ok(String::from("hello")),
+            text_of(vec![901, 104, 101, 108, 108, 111])
+        );
+    }
+
+    #[test]
+    pub fn test_numeric() {
+        // "000213298174000"
+        assert_eq!(
+            Ok(String::from("000213298174000")),
+            text_of(vec![902, 1, 624, 434, 632, 282, 200])
+        );
+
+        // Leading 1 missing
+        assert!(data(vec![902, 5]).is_err());
+    }
+
+    #[test]
+    pub fn test_eci() {
+        // ECI 26 (UTF-8) followed by "é" as bytes
+        let result = data(vec![927, 26, 901, 0xC3, 0xA9]).unwrap();
+        assert_eq!(Ok(String::from("\u{E9}")), result.text);
+        assert_eq!(vec![26], result.eci);
+    }
+
+    #[test]
+    pub fn test_macro() {
+        // "AB" as segment 2 of 4 of file "017053" named "AB", last segment
+        let result = data(vec![
+            1, 928, 111, 102, 17, 53, 923, 0, 1, 923, 1, 1, 104, 922,
+        ])
+        .unwrap();
+
+        assert_eq!(Ok(String::from("AB")), result.text);
+        assert_eq!(
+            Some(MacroPDF417 {
+                segment_index: 2,
+                file_id: String::from("017053"),
+                last_segment: true,
+                file_name: Some(String::from("AB")),
+                segment_count: Some(4),
+                ..Default::default()
+            }),
+            result.macro_pdf417
+        );
+
+        assert!(data(vec![928, 111, 100, 923, 9]).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use super::super::Decode;
+use super::data::Data;
+
+use crate::util::pdf417::{PDF417Data, PDF417Error, PDF417Info};
+
+/// Decode a PDF417 Code into a resulting String
+///
+/// This decoder will, in order:
+/// * Perform error correction over GF(929), treating unreadable codewords as erasures
+/// * Check the symbol length descriptor
+/// * Decode the data codewords into a String, using Text, Byte and Numeric Compaction
+pub struct PDF417Decoder {}
+
+impl PDF417Decoder {
+    /// Construct a new PDF417Decoder
+    pub fn new() -> PDF417Decoder {
+        PDF417Decoder {}
+    }
+}
+
+impl Decode<PDF417Data, String, PDF417Error> for PDF417Decoder {
+    fn decode(&self, data: Result<PDF417Data, PDF417Error>) -> Result<String, PDF417Error> {
+        let (data, _, _) = correct_and_decode(&data?)?;
+
+        data.text
+    }
+}
+
+/// Decode a PDF417 Code into a resulting String. It also includes some information about the decoded PDF417 Code.
+///
+/// Functions the same as PDF417Decoder, apart from also returning some information about the decoded PDF417 Code,
+/// including the Macro PDF417 control block.
+pub struct PDF417DecoderWithInfo {}
+
+impl PDF417DecoderWithInfo {
+    /// Construct a new PDF417DecoderWithInfo
+    pub fn new() -> PDF417DecoderWithInfo {
+        PDF417DecoderWithInfo {}
+    }
+}
+
+impl Decode<PDF417Data, (String, PDF417Info), PDF417Error> for PDF417DecoderWithInfo {
+    fn decode(
+        &self,
+        data: Result<PDF417Data, PDF417Error>,
+    ) -> Result<(String, PDF417Info), PDF417Error> {
+        let pdf_data = data?;
+        let (data, errors, erasures) = correct_and_decode(&pdf_data)?;
+
+        Ok((
+            data.text?,
+            PDF417Info {
+                rows: pdf_data.rows,
+                columns: pdf_data.columns,
+                ec_level: pdf_data.ec_level,
+                errors,
+                erasures,
+                eci: data.eci,
+                macro_pdf417: data.macro_pdf417,
+            },
+        ))
+    }
+}
+
+fn correct_and_decode(pdf_data: &PDF417Data) -> Result<(Data, u32, u32), PDF417Error> {
+    let total = pdf_data.codewords.len();
+
+    if !(3..=90).contains(&pdf_data.rows)
+        || !(1..=30).contains(&pdf_data.columns)
+        || pdf_data.ec_level > 8
+        || total != (pdf_data.rows * pdf_data.columns) as usize
+    {
+        return Err(PDF417Error {
+            msg: format!(
+                "Invalid PDF417 Code of {} codewords, {} rows, {} columns and error correction level {}",
+                total, pdf_data.rows, pdf_data.columns, pdf_data.ec_level
+            ),
+        });
+    }
+
+    let ec = 2 << pdf_data.ec_level;
+    let erasures = pdf_data.erasures();
+
+    let block = pdf_data.codewords.iter().map(|c| c.unwrap_or(0)).collect();
+    let (corrected, errors) = super::correct::correct(block, &erasures, ec)?;
+
+    debug!(
+        "CORRECTED {} ERRORS AND {} ERASURES",
+        errors,
+        erasures.len()
+    );
+
+    // The symbol length descriptor counts the data codewords, including itself
+    let length = corrected[0] as usize;
+
+    if length == 0 || length > total - ec {
+        return Err(PDF417Error {
+            msg: format!("Invalid symbol length descriptor {}", length),
+        });
+    }
+
+    let data = super::data::data(corrected[1..length].to_vec())?;
+
+    Ok((data, errors as u32, erasures.len() as u32))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_decode() {
+        // Example from ISO/IEC 15438 Annex Q, "PDF417" in 3 rows of 3 columns at error correction level 1
+        let codewords = vec![5, 453, 178, 121, 239, 452, 327, 657, 619];
+        let data = PDF417Data::new(codewords.into_iter().map(Some).collect(), 3, 3, 1);
+
+        assert_eq!(
+            Ok(String::from("PDF417")),
+            PDF417Decoder::new().decode(Ok(data))
+        );
+
+        // Two erasures leave two codewords to detect errors
+        let data = PDF417Data::new(
+            vec![
+                Some(5),
+                None,
+                Some(178),
+                Some(121),
+                Some(239),
+                Some(452),
+                None,
+                Some(657),
+                Some(619),
+            ],
+            3,
+            3,
+            1,
+        );
+
+        let (text, info) = PDF417DecoderWithInfo::new().decode(Ok(data)).unwrap();
+
+        assert_eq!("PDF417", text);
+        assert_eq!((0, 2), (info.errors, info.erasures));
+        assert_eq!(None, info.macro_pdf417);
+    }
+}
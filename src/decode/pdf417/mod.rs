@@ -0,0 +1,3 @@
+pub mod correct;
+pub mod data;
+pub mod decoder;
@@ -3,22 +3,36 @@ use super::{BlockInfo, ECLevel, QRMask};
 
 use crate::util::qr::{QRData, QRError};
 
+use std::iter;
+
+/// Confidence in every codeword of every block
+pub type Confidence = Vec<Vec<u8>>;
+
+/// Read the blocks of codewords, along with the confidence in every codeword, which is that of its least confident module
 #[allow(clippy::borrowed_box)] // QRMask is a trait, unsure how to solve
-pub fn blocks(data: &QRData, level: &ECLevel, mask: &Box<QRMask>) -> Result<Vec<Vec<u8>>, QRError> {
+pub fn blocks_with_confidence(
+    data: &QRData,
+    level: &ECLevel,
+    mask: &Box<QRMask>,
+) -> Result<(Vec<Vec<u8>>, Confidence), QRError> {
     let bi = block_info(data.version, level)?;
     let mut codewords = Codewords::new(bi);
     let mut x = data.side - 1;
     let loc = alignment_location(data.version)?;
 
+    let confidence = |x: u32, y: u32| data.confidence[(y * data.side + x) as usize];
+
     loop {
         let y_range = y_range(x, data.side);
 
         for y in y_range {
             if is_data(data, &loc, x, y) {
+                codewords.add_confidence(confidence(x, y));
                 codewords.add_bit(mask(data, x, y));
             }
 
             if is_data(data, &loc, x - 1, y) {
+                codewords.add_confidence(confidence(x - 1, y));
                 codewords.add_bit(mask(data, x - 1, y));
             }
         }
@@ -35,7 +49,7 @@ pub fn blocks(data: &QRData, level: &ECLevel, mask: &Box<QRMask>) -> Result<Vec<
     }
 
     let bi = block_info(data.version, level)?;
-    let blocks = codewords.blocks();
+    let (blocks, confidence) = codewords.blocks_with_confidence();
 
     if blocks.len() != bi.len() {
         return Err(QRError {
@@ -44,7 +58,12 @@ pub fn blocks(data: &QRData, level: &ECLevel, mask: &Box<QRMask>) -> Result<Vec<
     }
 
     for (i, block) in blocks.iter().enumerate() {
-        debug!("BLOCK {}, CODEWORDS {}", i, block.len());
+        debug!(
+            "BLOCK {}, CODEWORDS {}, CONFIDENCE {:?}",
+            i,
+            block.len(),
+            confidence[i]
+        );
     }
 
     for i in 0..blocks.len() {
@@ -60,10 +79,10 @@ pub fn blocks(data: &QRData, level: &ECLevel, mask: &Box<QRMask>) -> Result<Vec<
         }
     }
 
-    Ok(blocks)
+    Ok((blocks, confidence))
 }
 
-fn y_range(x: u32, side: u32) -> Box<dyn Iterator<Item = u32>> {
+pub fn y_range(x: u32, side: u32) -> Box<dyn Iterator<Item = u32>> {
     let x = if x < 6 { x + 1 } else { x };
     if (i64::from(x) - i64::from(side) + 1) % 4 == 0 {
         Box::new((0..side).rev())
@@ -72,7 +91,7 @@ fn y_range(x: u32, side: u32) -> Box<dyn Iterator<Item = u32>> {
     }
 }
 
-fn is_data(data: &QRData, loc: &AlignmentLocation, x: u32, y: u32) -> bool {
+pub fn is_data(data: &QRData, loc: &AlignmentLocation, x: u32, y: u32) -> bool {
     // timing patterns
     if x == 6 || y == 6 {
         return false;
@@ -134,7 +153,7 @@ fn is_alignment_coord(loc: &AlignmentLocation, coord: u32) -> bool {
     false
 }
 
-fn alignment_location(version: u32) -> Result<AlignmentLocation, QRError> {
+pub fn alignment_location(version: u32) -> Result<AlignmentLocation, QRError> {
     match version {
         // no alignment patterns for version 1 but this saves some exception paths
         1 => Ok(AlignmentLocation::new(1000, 1000)),
@@ -187,10 +206,24 @@ fn alignment_location(version: u32) -> Result<AlignmentLocation, QRError> {
     }
 }
 
+/// Centers of the alignment patterns along either axis, including the ones that would overlap the finder patterns
+pub fn alignment_centers(version: u32) -> Result<Vec<u32>, QRError> {
+    if version == 1 {
+        return Ok(vec![]);
+    }
+
+    let loc = alignment_location(version)?;
+    let side = 4 * version + 17;
+
+    Ok(iter::once(6)
+        .chain((loc.start..side - 6).step_by(loc.step as usize))
+        .collect())
+}
+
 #[derive(Debug)]
-struct AlignmentLocation {
-    start: u32,
-    step: u32,
+pub struct AlignmentLocation {
+    pub start: u32,
+    pub step: u32,
 }
 
 impl AlignmentLocation {
@@ -202,6 +235,7 @@ impl AlignmentLocation {
 struct Blocks {
     block_info: Vec<BlockInfo>,
     blocks: Vec<Vec<u8>>,
+    confidence: Confidence,
 
     round: usize,
     max_data_round: usize,
@@ -223,6 +257,7 @@ impl Blocks {
         }
 
         Blocks {
+            confidence: vec![vec![]; blocks.len()],
             block_info,
             blocks,
             round: 0,
@@ -232,13 +267,14 @@ impl Blocks {
         }
     }
 
-    fn push(&mut self, byte: u8) {
+    fn push(&mut self, byte: u8, confidence: u8) {
         while self.data_blocks && self.round > self.block_info[self.block].data_per as usize - 1 {
             self.inc_count();
         }
 
         trace!("PUSHING {:08b} TO BLOCK {}", byte, self.block);
 
+        self.confidence[self.block].push(confidence);
         self.blocks[self.block].push(byte);
         self.inc_count();
     }
@@ -257,36 +293,48 @@ impl Blocks {
     }
 }
 
-struct Codewords {
+pub struct Codewords {
     current_byte: u8,
     bit_count: u8,
+    confidence: u8,
     blocks: Blocks,
 }
 
 impl Codewords {
-    fn new(block_info: Vec<BlockInfo>) -> Codewords {
+    pub fn new(block_info: Vec<BlockInfo>) -> Codewords {
         Codewords {
             current_byte: 0,
             bit_count: 0,
+            confidence: 255,
             blocks: Blocks::new(block_info),
         }
     }
 
-    fn add_bit(&mut self, bit: u8) {
+    pub fn add_bit(&mut self, bit: u8) {
         self.current_byte *= 2;
         self.current_byte += bit;
         self.bit_count += 1;
 
         if self.bit_count == 8 {
-            self.blocks.push(self.current_byte);
+            self.blocks.push(self.current_byte, self.confidence);
             self.current_byte = 0;
             self.bit_count = 0;
+            self.confidence = 255;
         }
     }
 
-    fn blocks(self) -> Vec<Vec<u8>> {
+    /// Lower the confidence in the codeword the next bit is added to, to that of the module it was read from
+    pub fn add_confidence(&mut self, confidence: u8) {
+        self.confidence = self.confidence.min(confidence);
+    }
+
+    pub fn blocks(self) -> Vec<Vec<u8>> {
         self.blocks.blocks
     }
+
+    pub fn blocks_with_confidence(self) -> (Vec<Vec<u8>>, Confidence) {
+        (self.blocks.blocks, self.blocks.confidence)
+    }
 }
 
 #[cfg(test)]
@@ -299,13 +347,13 @@ mod test {
         let side = 4 * 36 + 17;
 
         for x in 0..side {
-            if (x >= 4 && x <= 8)
-                || (x >= 22 && x <= 26)
-                || (x >= 48 && x <= 52)
-                || (x >= 74 && x <= 78)
-                || (x >= 100 && x <= 104)
-                || (x >= 126 && x <= 130)
-                || (x >= 152 && x <= 156)
+            if (4..=8).contains(&x)
+                || (22..=26).contains(&x)
+                || (48..=52).contains(&x)
+                || (74..=78).contains(&x)
+                || (100..=104).contains(&x)
+                || (126..=130).contains(&x)
+                || (152..=156).contains(&x)
             {
                 assert!(is_alignment_coord(&al, x));
             } else {
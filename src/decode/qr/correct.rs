@@ -3,46 +3,116 @@ use super::BlockInfo;
 
 use crate::util::qr::QRError;
 
-use std::ops::{Div, Mul, Sub};
-
 pub fn correct(block: Vec<u8>, block_info: &BlockInfo) -> Result<Vec<u8>, QRError> {
     correct_with_error_count(block, block_info).map(|r| r.0)
 }
 
+/// Correct a single block of codewords, data codewords first, followed by the error correction codewords
+///
+/// Returns the corrected block and the number of corrected bits
 pub fn correct_with_error_count(
+    block: Vec<u8>,
+    block_info: &BlockInfo,
+) -> Result<(Vec<u8>, u32), QRError> {
+    correct_with_erasures(block, &[], block_info)
+}
+
+/// Correct a single block of codewords, of which the codewords at the provided positions are known to be unreliable
+///
+/// The erasures are removed from the syndromes by calculating the Forney syndromes, from which Berlekamp-Massey finds
+/// the locator polynomial of the remaining errors. Together with the erasures this gives the errata locator, of which
+/// the roots are found with a Chien search and the values with Forney's algorithm. An erasure only takes up half as
+/// much of the error correction capacity as an error, so as long as `2 * errors + erasures` does not exceed twice the
+/// capacity the block is corrected. Blocks with more errata are rejected, the remaining error correction codewords
+/// then only serve to detect that.
+///
+/// Returns the corrected block and the number of corrected bits
+pub fn correct_with_erasures(
     mut block: Vec<u8>,
+    erasures: &[usize],
     block_info: &BlockInfo,
 ) -> Result<(Vec<u8>, u32), QRError> {
-    let (all_fine, syndromes) = calculate_syndromes(&block, block_info);
+    let n = block.len();
+    let ec = (block_info.total_per - block_info.data_per) as usize;
+    let capacity = 2 * block_info.ec_cap as usize;
 
-    if all_fine {
+    if erasures.len() > capacity || erasures.iter().any(|e| *e >= n) {
+        return Err(QRError {
+            msg: format!("Unable to correct {} erasures", erasures.len()),
+        });
+    }
+
+    // The generator polynomial has roots a^0 to a^(ec - 1)
+    let syndromes: Vec<GF8> = (0..ec).map(|i| syndrome(&block, EXP8[i])).collect();
+
+    if syndromes.iter().all(|s| *s == GF8(0)) {
         // all fine, nothing to do
         debug!("ALL SYNDROMES WERE ZERO, NO CORRECTION NEEDED");
         return Ok((block, 0));
     }
 
-    let locs = find_locs(block_info, &syndromes)?;
+    // The erasure locator has a root at the inverse of every erasure location
+    let gamma = erasures.iter().fold(vec![GF8(1)], |gamma, e| {
+        multiply(&gamma, &[GF8(1), EXP8[(n - 1 - e) % 255]])
+    });
+
+    // Forney syndromes, in which the erasures no longer appear
+    let forney: Vec<GF8> = multiply(&syndromes, &gamma)
+        .into_iter()
+        .take(ec)
+        .skip(erasures.len())
+        .collect();
+
+    let sigma = multiply(&locator(&forney), &gamma);
+
+    let errata = sigma.len() - 1;
+    let errors = errata - erasures.len();
+
+    debug!("SIGMA {:?}", sigma);
+
+    if errata == 0 || 2 * errors + erasures.len() > capacity {
+        return Err(QRError {
+            msg: String::from("Too many errors to correct"),
+        });
+    }
+
+    // Errata evaluator polynomial, omega = syndromes * sigma mod x^ec
+    let mut omega = multiply(&syndromes, &sigma);
+    omega.truncate(ec);
 
-    let distance = calculate_distances(&syndromes, &locs);
-    let distance = distance.ok_or(QRError {
-        msg: String::from("Could not calculate error distances"),
-    })?;
+    // Formal derivative of sigma, only the odd powers remain
+    let derivative: Vec<GF8> = (1..sigma.len())
+        .map(|i| if i % 2 == 1 { sigma[i] } else { GF8(0) })
+        .collect();
 
+    // Chien search for the roots of sigma, which are the inverses of the errata locations
+    let mut corrected = 0;
     let mut error_count = 0;
 
-    for i in 0..locs.len() {
+    for position in 0..n {
+        let x = EXP8[position % 255];
+        let x_inv = EXP8[(255 - position % 255) % 255];
+
+        if eval(&sigma, x_inv) != GF8(0) {
+            continue;
+        }
+
+        // Forney, the generator starts at a^0 so the error value is multiplied by the location once more
+        let magnitude = x * eval(&omega, x_inv) / eval(&derivative, x_inv);
+
         debug!(
             "FIXING LOCATION {} FROM {:08b} TO {:08b}",
-            block_info.total_per as usize - 1 - locs[i] as usize,
-            block[block_info.total_per as usize - 1 - locs[i] as usize],
-            block[block_info.total_per as usize - 1 - locs[i] as usize] ^ distance[i].0
+            n - 1 - position,
+            block[n - 1 - position],
+            block[n - 1 - position] ^ magnitude.0
         );
 
-        error_count += distance[i].0.count_ones();
-        block[block_info.total_per as usize - 1 - locs[i] as usize] ^= distance[i].0;
+        block[n - 1 - position] ^= magnitude.0;
+        error_count += magnitude.0.count_ones();
+        corrected += 1;
     }
 
-    if syndrome(&block, EXP8[0]) != GF8(0) {
+    if corrected != errata {
         return Err(QRError {
             msg: String::from("Error correcting did not fix corrupted data"),
         });
@@ -51,125 +121,202 @@ pub fn correct_with_error_count(
     Ok((block, error_count))
 }
 
-fn calculate_syndromes(block: &[u8], block_info: &BlockInfo) -> (bool, Vec<GF8>) {
-    let mut syndromes = vec![GF8(0); (block_info.ec_cap * 2) as usize];
+fn syndrome(block: &[u8], base: GF8) -> GF8 {
+    block
+        .iter()
+        .fold(GF8(0), |acc, codeword| acc * base + GF8(*codeword))
+}
 
-    let mut all_fine = true;
-    for i in 0..block_info.ec_cap * 2 {
-        syndromes[i as usize] = syndrome(block, EXP8[i as usize]);
-        if syndromes[i as usize] != GF8(0) {
-            all_fine = false;
+// Berlekamp-Massey, the shortest polynomial generating the syndromes, lowest degree coefficient first
+fn locator(syndromes: &[GF8]) -> Vec<GF8> {
+    let mut sigma = vec![GF8(1)];
+    let mut previous = vec![GF8(1)];
+    let mut errors = 0;
+    let mut shift = 1;
+    let mut b = GF8(1);
+
+    for i in 0..syndromes.len() {
+        let discrepancy = (1..=errors.min(sigma.len() - 1))
+            .fold(syndromes[i], |acc, j| acc + sigma[j] * syndromes[i - j]);
+
+        if discrepancy == GF8(0) {
+            shift += 1;
+            continue;
         }
-    }
 
-    (all_fine, syndromes)
-}
+        let factor = discrepancy / b;
+        let mut next = sigma.clone();
+        next.resize(next.len().max(previous.len() + shift), GF8(0));
 
-fn syndrome(block: &[u8], base: GF8) -> GF8 {
-    let mut synd = GF8(0);
-    let mut alpha = GF8(1);
+        for (j, p) in previous.iter().enumerate() {
+            next[j + shift] = next[j + shift] + factor * *p;
+        }
 
-    for codeword in block.iter().rev() {
-        synd = synd + (alpha * GF8(*codeword));
+        if 2 * errors <= i {
+            errors = i + 1 - errors;
+            previous = sigma;
+            b = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
 
-        alpha = alpha * base;
+        sigma = next;
     }
 
-    synd
+    sigma.truncate(errors + 1);
+    sigma
 }
 
-fn find_locs(block_info: &BlockInfo, syndromes: &[GF8]) -> Result<Vec<usize>, QRError> {
-    let z = block_info.ec_cap as usize;
-    let mut eq = vec![vec![GF8(0); z + 1]; z];
-    for i in 0..z {
-        eq[i][..=z].clone_from_slice(&syndromes[i..(z + 1 + i)]);
-    }
+// Multiply two polynomials with the lowest degree coefficient first
+fn multiply(a: &[GF8], b: &[GF8]) -> Vec<GF8> {
+    let mut product = vec![GF8(0); a.len() + b.len() - 1];
 
-    let sigma = solve(eq, GF8(0), GF8(1), false);
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i + j] = product[i + j] + *x * *y;
+        }
+    }
 
-    let sigma = sigma.ok_or(QRError {
-        msg: String::from("Could not calculate SIGMA"),
-    })?;
+    product
+}
 
-    let mut locs = vec![];
+// Evaluate a polynomial with the lowest degree coefficient first
+fn eval(poly: &[GF8], x: GF8) -> GF8 {
+    poly.iter().rev().fold(GF8(0), |acc, c| acc * x + *c)
+}
 
-    for (i, exp) in EXP8.iter().enumerate().take(block_info.total_per as usize) {
-        let mut x = *exp;
-        let mut check_value = sigma[0];
-        for s in sigma.iter().skip(1) {
-            check_value = check_value + x * *s;
-            x = x * *exp;
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Systematic encoding, only used to construct test blocks
+    fn encode(data: &[u8], ec: usize) -> Vec<u8> {
+        let mut generator = vec![GF8(1)];
+        for root in EXP8.iter().take(ec) {
+            let mut next = vec![GF8(0); generator.len() + 1];
+            for (j, g) in generator.iter().enumerate() {
+                next[j] = next[j] + *g;
+                next[j + 1] = next[j + 1] + *g * *root;
+            }
+            generator = next;
         }
-        check_value = check_value + x;
 
-        if check_value == GF8(0) {
-            debug!("LOC {:?} {} ", exp, i);
-            locs.push(i);
+        let mut remainder: Vec<GF8> = data.iter().map(|d| GF8(*d)).collect();
+        remainder.resize(data.len() + ec, GF8(0));
+
+        for i in 0..data.len() {
+            let c = remainder[i];
+            for (j, g) in generator.iter().enumerate().skip(1) {
+                remainder[i + j] = remainder[i + j] + *g * c;
+            }
         }
+
+        let mut block = data.to_vec();
+        block.extend(remainder[data.len()..].iter().map(|r| r.0));
+        block
     }
 
-    debug!("LOCS {:?}", locs);
+    #[test]
+    pub fn test_correct() {
+        // "01234567" in a version 1-M QR Code
+        let block = encode(
+            &[
+                0x10, 0x20, 0x0C, 0x56, 0x61, 0x80, 0xEC, 0x11, 0xEC, 0x11, 0xEC, 0x11, 0xEC, 0x11,
+                0xEC, 0x11,
+            ],
+            10,
+        );
+        assert_eq!(
+            vec![0xA5, 0x24, 0xD4, 0xC1, 0xED, 0x36, 0xC7, 0x87, 0x2C, 0x55],
+            block[16..].to_vec()
+        );
 
-    Ok(locs)
-}
+        let block_info = BlockInfo::new(1, 26, 16, 4);
+        assert_eq!(
+            Ok((block.clone(), 0)),
+            correct_with_error_count(block.clone(), &block_info)
+        );
 
-fn calculate_distances(syndromes: &[GF8], locs: &[usize]) -> Option<Vec<GF8>> {
-    let mut eq = vec![vec![GF8(0); locs.len() + 1]; locs.len()];
-    for i in 0..locs.len() {
-        for j in 0..locs.len() {
-            eq[i][j] = EXP8[(i * locs[j] as usize) % 255];
-        }
+        let mut corrupted = block.clone();
+        corrupted[0] ^= 0b1000_0001;
+        corrupted[7] = 0;
+        corrupted[15] ^= 0b0000_0100;
+        corrupted[25] ^= 0xFF;
+        assert_eq!(
+            Ok((block.clone(), 2 + 2 + 1 + 8)),
+            correct_with_error_count(corrupted, &block_info)
+        );
 
-        eq[i][locs.len()] = syndromes[i];
+        // One error more than the capacity
+        let mut corrupted = block.clone();
+        for c in corrupted.iter_mut().step_by(5).take(5) {
+            *c ^= 0x5A;
+        }
+        assert!(correct_with_error_count(corrupted, &block_info).is_err());
     }
 
-    solve(eq, GF8(0), GF8(1), false)
-}
+    #[test]
+    pub fn test_detection_only() {
+        // Version 1-L can correct 2 errors, the third error correction codeword is only used to detect more
+        let block = encode(&[0x40, 0x56, 0x86, 0x56, 0xC6, 0xC6, 0xF0, 0xEC, 0x11], 7);
+        let block_info = BlockInfo::new(1, 16, 9, 2);
+
+        let mut corrupted = block.clone();
+        corrupted[1] ^= 0x10;
+        corrupted[10] ^= 0x01;
+        assert_eq!(
+            Ok((block.clone(), 2)),
+            correct_with_error_count(corrupted, &block_info)
+        );
 
-fn solve<T>(mut eq: Vec<Vec<T>>, zero: T, one: T, fail_on_rank: bool) -> Option<Vec<T>>
-where
-    T: Div<Output = T> + Mul<Output = T> + Sub<Output = T> + Copy + PartialEq,
-{
-    let num_eq = eq.len() as usize;
-    if num_eq == 0 {
-        return None;
+        let mut corrupted = block.clone();
+        corrupted[1] ^= 0x10;
+        corrupted[10] ^= 0x01;
+        corrupted[14] ^= 0x01;
+        assert!(correct_with_error_count(corrupted, &block_info).is_err());
     }
 
-    let num_coeff = eq[0].len();
-    if num_coeff == 0 {
-        return None;
-    }
+    #[test]
+    pub fn test_erasures() {
+        let block = encode(
+            &[
+                0x10, 0x20, 0x0C, 0x56, 0x61, 0x80, 0xEC, 0x11, 0xEC, 0x11, 0xEC, 0x11, 0xEC, 0x11,
+                0xEC, 0x11,
+            ],
+            10,
+        );
+        let block_info = BlockInfo::new(1, 26, 16, 4);
 
-    for i in 0..num_eq {
-        // normalise equation
-        for j in (i..num_coeff).rev() {
-            // divide all coefficients by the first nonzero
-            // the first nonzero will now be GF8(1)
-            eq[i][j] = eq[i][j] / eq[i][i];
+        // Eight erasures, twice the number of errors that could be corrected
+        let erasures = [0, 3, 4, 9, 12, 17, 20, 25];
+        let mut corrupted = block.clone();
+        for e in erasures.iter() {
+            corrupted[*e] = 0;
         }
 
-        // subtract normalised equation from others, multiplied by first coefficient
-        // so the coefficients corresponding to the GF8(1) above will be GF8(0)
-        for j in i + 1..num_eq {
-            for k in (i..num_coeff).rev() {
-                eq[j][k] = eq[j][k] - (eq[j][i] * eq[i][k]);
-            }
-        }
+        assert!(correct_with_error_count(corrupted.clone(), &block_info).is_err());
 
-        // If the rank is too low, can't solve
-        if fail_on_rank && eq[i][num_coeff - 1] == one {
-            return None;
-        }
-    }
+        let errors: u32 = erasures.iter().map(|e| block[*e].count_ones()).sum();
+        assert_eq!(
+            Ok((block.clone(), errors)),
+            correct_with_erasures(corrupted, &erasures, &block_info)
+        );
 
-    let mut solution = vec![zero; num_eq];
+        // An erasure that turns out to be fine does not count
+        let mut corrupted = block.clone();
+        corrupted[5] ^= 0x42;
+        corrupted[8] ^= 0x01;
+        assert_eq!(
+            Ok((block.clone(), 3)),
+            correct_with_erasures(corrupted.clone(), &[5, 14, 21], &block_info)
+        );
 
-    for i in (0..num_eq).rev() {
-        solution[i] = eq[i][num_coeff - 1];
-        for j in i + 1..num_coeff - 1 {
-            solution[i] = solution[i] - (eq[i][j] * solution[j]);
-        }
+        // Two errors and five erasures are too many
+        corrupted[2] ^= 0x10;
+        assert!(
+            correct_with_erasures(corrupted.clone(), &[5, 14, 21, 22, 23], &block_info).is_err()
+        );
+        assert!(correct_with_erasures(corrupted, &[0; 9], &block_info).is_err());
     }
-
-    Some(solution)
 }
@@ -1,32 +1,327 @@
-use crate::util::qr::QRError;
+use super::eci::Charset;
+
+use crate::util::qr::{QRError, QRMode, QRSegment, StructuredAppend, FNC1};
 use crate::util::Chomp;
 
-pub fn data(input: Vec<u8>, version: u32) -> Result<String, QRError> {
+use encoding_rs::SHIFT_JIS;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Data {
+    pub text: Result<String, QRError>,
+    pub bytes: Vec<u8>,
+    pub segments: Vec<QRSegment>,
+    pub eci: Vec<u32>,
+    pub structured_append: Option<StructuredAppend>,
+    pub fnc1: Option<FNC1>,
+}
+
+pub fn data(input: Vec<u8>, version: u32) -> Result<Data, QRError> {
     let mut chomp = Chomp::new(input);
-    let mut result = String::new();
+    let mut result = Text::new();
+    let mut bytes = vec![];
+    let mut segments = vec![];
+    let mut eci = vec![];
+    let mut charset = Ok(None);
+    let mut sa = None;
+    let mut fnc1 = None;
 
     while let Some(mode) = chomp.chomp(4) {
-        match mode {
-            0b0001 => result.push_str(numeric(&mut chomp, version)?.as_str()),
-            0b0010 => result.push_str(alphanumeric(&mut chomp, version)?.as_str()),
-            0b0100 => result.push_str(eight_bit(&mut chomp, version)?.as_str()),
+        let offset = chomp.position() - 4;
+
+        let (segment_mode, count, raw, text) = match mode {
+            0b0001 => {
+                let numeric = numeric(&mut chomp, length_bits(QRMode::NUMERIC, version)?)?;
+                let raw = numeric.clone().into_bytes();
+                (QRMode::NUMERIC, numeric.len(), raw, Ok(numeric))
+            }
+            0b0010 => {
+                let alphanumeric =
+                    alphanumeric(&mut chomp, length_bits(QRMode::ALPHANUMERIC, version)?)?;
+                let count = alphanumeric.len();
+                let alphanumeric = if fnc1.is_some() {
+                    gs1(&alphanumeric)
+                } else {
+                    alphanumeric
+                };
+                let raw = alphanumeric.clone().into_bytes();
+                (QRMode::ALPHANUMERIC, count, raw, Ok(alphanumeric))
+            }
+            0b0100 => {
+                let raw = eight_bit(&mut chomp, length_bits(QRMode::BYTE, version)?)?;
+                let text = charset
+                    .clone()
+                    .and_then(|charset| eight_bit_text(&raw, charset));
+                (QRMode::BYTE, raw.len(), raw, text)
+            }
+            0b1000 => {
+                let sjis = kanji(&mut chomp, length_bits(QRMode::KANJI, version)?)?;
+                let text = kanji_text(&sjis);
+                (QRMode::KANJI, sjis.len() / 2, sjis, text)
+            }
+            0b0111 => {
+                let designator = eci_designator(&mut chomp)?;
+                result.flush();
+                // An unknown character set only fails converting the byte segments to text, the raw bytes are still there
+                charset = Charset::from_eci(designator).map(Some);
+                eci.push(designator);
+                continue;
+            }
+            0b0011 => {
+                sa = Some(structured_append(&mut chomp)?);
+                continue;
+            }
+            0b0101 => {
+                fnc1 = Some(FNC1::First);
+                continue;
+            }
+            0b1001 => {
+                let application_indicator = read_bits(&mut chomp, 8)?;
+                debug!("FNC1 APPLICATION INDICATOR {}", application_indicator);
+
+                fnc1 = Some(FNC1::Second(application_indicator));
+                continue;
+            }
             0b0000 => break,
             _ => {
                 return Err(QRError {
                     msg: format!("Mode {:04b} not yet implemented.", mode),
                 })
             }
+        };
+
+        segments.push(QRSegment {
+            mode: segment_mode,
+            count: count as u32,
+            offset,
+            bytes: raw.clone(),
+            value: text.clone(),
+        });
+
+        if segment_mode == QRMode::BYTE {
+            result.push_bytes(&raw, charset.clone());
+        } else {
+            result.push(text);
         }
+
+        bytes.extend(raw);
     }
 
-    Ok(result)
+    let structured_append = sa.map(|sa| StructuredAppend {
+        part_parity: bytes.iter().fold(0, |parity, b| parity ^ b),
+        ..sa
+    });
+
+    Ok(Data {
+        text: result.finish(),
+        bytes,
+        segments,
+        eci,
+        structured_append,
+        fnc1,
+    })
+}
+
+pub fn micro_data(input: Vec<u8>, version: u32) -> Result<Data, QRError> {
+    // M1 has no mode indicator at all, only numeric data is supported
+    let mode_bits = (version - 1) as u8;
+
+    // The terminator is a numeric mode indicator with a character count of 0
+    let terminator_bits = mode_bits as usize + micro_length_bits(QRMode::NUMERIC, version)? as usize;
+
+    let total_bits = input.len() * 8;
+    let mut chomp = Chomp::new(input);
+    let mut result = Text::new();
+    let mut bytes = vec![];
+    let mut segments = vec![];
+
+    while total_bits - chomp.position() >= terminator_bits {
+        let offset = chomp.position();
+
+        let mode = if mode_bits == 0 {
+            0
+        } else {
+            read_bits(&mut chomp, mode_bits)?
+        };
+
+        let (segment_mode, count, raw, text) = match mode {
+            0b000 => {
+                let numeric = numeric(
+                    &mut chomp,
+                    micro_length_bits(QRMode::NUMERIC, version)?,
+                )?;
+
+                if numeric.is_empty() {
+                    break;
+                }
+
+                let raw = numeric.clone().into_bytes();
+                (QRMode::NUMERIC, numeric.len(), raw, Ok(numeric))
+            }
+            0b001 => {
+                let alphanumeric = alphanumeric(
+                    &mut chomp,
+                    micro_length_bits(QRMode::ALPHANUMERIC, version)?,
+                )?;
+                let raw = alphanumeric.clone().into_bytes();
+                (QRMode::ALPHANUMERIC, alphanumeric.len(), raw, Ok(alphanumeric))
+            }
+            0b010 => {
+                let raw = eight_bit(&mut chomp, micro_length_bits(QRMode::BYTE, version)?)?;
+                let text = eight_bit_text(&raw, None);
+                (QRMode::BYTE, raw.len(), raw, text)
+            }
+            0b011 => {
+                let sjis = kanji(&mut chomp, micro_length_bits(QRMode::KANJI, version)?)?;
+                let text = kanji_text(&sjis);
+                (QRMode::KANJI, sjis.len() / 2, sjis, text)
+            }
+            _ => {
+                return Err(QRError {
+                    msg: format!("Mode {:03b} not supported by Micro QR", mode),
+                })
+            }
+        };
+
+        segments.push(QRSegment {
+            mode: segment_mode,
+            count: count as u32,
+            offset,
+            bytes: raw.clone(),
+            value: text.clone(),
+        });
+
+        if segment_mode == QRMode::BYTE {
+            result.push_bytes(&raw, Ok(None));
+        } else {
+            result.push(text);
+        }
+
+        bytes.extend(raw);
+    }
+
+    Ok(Data {
+        text: result.finish(),
+        bytes,
+        segments,
+        eci: vec![],
+        structured_append: None,
+        fnc1: None,
+    })
+}
+
+pub fn rmqr_data(input: Vec<u8>, version: u32) -> Result<Data, QRError> {
+    let mut chomp = Chomp::new(input);
+    let mut result = Text::new();
+    let mut bytes = vec![];
+    let mut segments = vec![];
+    let mut eci = vec![];
+    let mut charset = Ok(None);
+    let mut fnc1 = None;
+
+    // rMQR uses 3 bit mode indicators and has no structured append
+    while let Some(mode) = chomp.chomp(3) {
+        let offset = chomp.position() - 3;
+
+        let (segment_mode, count, raw, text) = match mode {
+            0b001 => {
+                let numeric = numeric(&mut chomp, rmqr_length_bits(QRMode::NUMERIC, version)?)?;
+                let raw = numeric.clone().into_bytes();
+                (QRMode::NUMERIC, numeric.len(), raw, Ok(numeric))
+            }
+            0b010 => {
+                let alphanumeric = alphanumeric(
+                    &mut chomp,
+                    rmqr_length_bits(QRMode::ALPHANUMERIC, version)?,
+                )?;
+                let count = alphanumeric.len();
+                let alphanumeric = if fnc1.is_some() {
+                    gs1(&alphanumeric)
+                } else {
+                    alphanumeric
+                };
+                let raw = alphanumeric.clone().into_bytes();
+                (QRMode::ALPHANUMERIC, count, raw, Ok(alphanumeric))
+            }
+            0b011 => {
+                let raw = eight_bit(&mut chomp, rmqr_length_bits(QRMode::BYTE, version)?)?;
+                let text = charset
+                    .clone()
+                    .and_then(|charset| eight_bit_text(&raw, charset));
+                (QRMode::BYTE, raw.len(), raw, text)
+            }
+            0b100 => {
+                let sjis = kanji(&mut chomp, rmqr_length_bits(QRMode::KANJI, version)?)?;
+                let text = kanji_text(&sjis);
+                (QRMode::KANJI, sjis.len() / 2, sjis, text)
+            }
+            0b111 => {
+                let designator = eci_designator(&mut chomp)?;
+                result.flush();
+                // An unknown character set only fails converting the byte segments to text, the raw bytes are still there
+                charset = Charset::from_eci(designator).map(Some);
+                eci.push(designator);
+                continue;
+            }
+            0b101 => {
+                fnc1 = Some(FNC1::First);
+                continue;
+            }
+            0b110 => {
+                let application_indicator = read_bits(&mut chomp, 8)?;
+                debug!("FNC1 APPLICATION INDICATOR {}", application_indicator);
+
+                fnc1 = Some(FNC1::Second(application_indicator));
+                continue;
+            }
+            _ => break,
+        };
+
+        segments.push(QRSegment {
+            mode: segment_mode,
+            count: count as u32,
+            offset,
+            bytes: raw.clone(),
+            value: text.clone(),
+        });
+
+        if segment_mode == QRMode::BYTE {
+            result.push_bytes(&raw, charset.clone());
+        } else {
+            result.push(text);
+        }
+
+        bytes.extend(raw);
+    }
+
+    Ok(Data {
+        text: result.finish(),
+        bytes,
+        segments,
+        eci,
+        structured_append: None,
+        fnc1,
+    })
 }
 
-fn numeric(chomp: &mut Chomp, version: u32) -> Result<String, QRError> {
-    let length_bits = match version {
-        1..=9 => 10,
-        10..=26 => 12,
-        27..=40 => 14,
+// Number of bits in the character count indicator, depending on mode and version
+pub fn length_bits(mode: QRMode, version: u32) -> Result<u8, QRError> {
+    let length_bits = match (mode, version) {
+        (QRMode::NUMERIC, 1..=9) => 10,
+        (QRMode::NUMERIC, 10..=26) => 12,
+        (QRMode::NUMERIC, 27..=40) => 14,
+
+        (QRMode::ALPHANUMERIC, 1..=9) => 9,
+        (QRMode::ALPHANUMERIC, 10..=26) => 11,
+        (QRMode::ALPHANUMERIC, 27..=40) => 13,
+
+        (QRMode::BYTE, 1..=9) => 8,
+        (QRMode::BYTE, 10..=26) => 16,
+        (QRMode::BYTE, 27..=40) => 16,
+
+        (QRMode::KANJI, 1..=9) => 8,
+        (QRMode::KANJI, 10..=26) => 10,
+        (QRMode::KANJI, 27..=40) => 12,
+
         _ => {
             return Err(QRError {
                 msg: format!("Unknown version {}", version),
@@ -34,6 +329,159 @@ fn numeric(chomp: &mut Chomp, version: u32) -> Result<String, QRError> {
         }
     };
 
+    Ok(length_bits)
+}
+
+// Number of bits in the character count indicator for Micro QR, depending on mode and version
+fn micro_length_bits(mode: QRMode, version: u32) -> Result<u8, QRError> {
+    let length_bits = match (mode, version) {
+        (QRMode::NUMERIC, 1..=4) => version + 2,
+        (QRMode::ALPHANUMERIC, 2..=4) => version + 1,
+        (QRMode::BYTE, 3..=4) => version + 1,
+        (QRMode::KANJI, 3..=4) => version,
+        _ => {
+            return Err(QRError {
+                msg: format!("Mode {:?} not supported by Micro QR version M{}", mode, version),
+            });
+        }
+    };
+
+    Ok(length_bits as u8)
+}
+
+// Number of bits in the character count indicator for rMQR, depending on mode and version
+fn rmqr_length_bits(mode: QRMode, version: u32) -> Result<u8, QRError> {
+    const NUMERIC: [u8; 32] = [
+        4, 5, 6, 7, 7, 5, 6, 7, 7, 8, 4, 6, 7, 7, 8, 8, 5, 6, 7, 7, 8, 8, 7, 7, 8, 8, 9, 7, 8, 8,
+        8, 9,
+    ];
+    const ALPHANUMERIC: [u8; 32] = [
+        3, 5, 5, 6, 6, 5, 5, 6, 6, 7, 4, 5, 6, 6, 7, 7, 5, 6, 6, 7, 7, 8, 6, 7, 7, 7, 8, 6, 7, 7,
+        8, 8,
+    ];
+    const BYTE: [u8; 32] = [
+        3, 4, 5, 5, 6, 4, 5, 5, 6, 6, 3, 5, 5, 6, 6, 7, 4, 5, 6, 6, 7, 7, 6, 6, 7, 7, 7, 6, 6, 7,
+        7, 8,
+    ];
+    const KANJI: [u8; 32] = [
+        2, 3, 4, 5, 5, 3, 4, 5, 5, 6, 2, 4, 5, 5, 6, 6, 3, 5, 5, 6, 6, 7, 5, 5, 6, 6, 7, 5, 6, 6,
+        6, 7,
+    ];
+
+    let table = match mode {
+        QRMode::NUMERIC => &NUMERIC,
+        QRMode::ALPHANUMERIC => &ALPHANUMERIC,
+        QRMode::BYTE => &BYTE,
+        QRMode::KANJI => &KANJI,
+    };
+
+    table.get(version as usize).copied().ok_or(QRError {
+        msg: format!("Unknown rMQR version {}", version),
+    })
+}
+
+// Text of the segments decoded so far. Consecutive byte segments under the same character set are decoded together,
+// as a multibyte character may be split across them
+struct Text {
+    text: Result<String, QRError>,
+    pending: Vec<u8>,
+    charset: Result<Option<Charset>, QRError>,
+}
+
+impl Text {
+    fn new() -> Text {
+        Text {
+            text: Ok(String::new()),
+            pending: vec![],
+            charset: Ok(None),
+        }
+    }
+
+    fn push(&mut self, segment: Result<String, QRError>) {
+        self.flush();
+        append(&mut self.text, segment);
+    }
+
+    fn push_bytes(&mut self, raw: &[u8], charset: Result<Option<Charset>, QRError>) {
+        self.pending.extend(raw);
+        self.charset = charset;
+    }
+
+    // Decode the pending bytes, before anything that is not a byte segment under the same character set
+    fn flush(&mut self) {
+        if !self.pending.is_empty() {
+            let text = self
+                .charset
+                .clone()
+                .and_then(|charset| eight_bit_text(&self.pending, charset));
+
+            append(&mut self.text, text);
+            self.pending.clear();
+        }
+    }
+
+    fn finish(mut self) -> Result<String, QRError> {
+        self.flush();
+        self.text
+    }
+}
+
+// Add a decoded segment to the text, unless converting any of the previous segments to text failed.
+// The raw bytes are still available in that case
+fn append(text: &mut Result<String, QRError>, segment: Result<String, QRError>) {
+    match (text.as_mut(), segment) {
+        (Ok(text), Ok(segment)) => text.push_str(&segment),
+        (Ok(_), Err(e)) => *text = Err(e),
+        (Err(_), _) => {}
+    }
+}
+
+fn structured_append(chomp: &mut Chomp) -> Result<StructuredAppend, QRError> {
+    let index = read_bits(chomp, 4)?;
+    let total = read_bits(chomp, 4)? + 1;
+    let parity = read_bits(chomp, 8)?;
+
+    debug!("STRUCTURED APPEND {} OF {}, PARITY {:08b}", index + 1, total, parity);
+
+    if index >= total {
+        return Err(QRError {
+            msg: format!("Structured append index {} exceeds total {}", index, total),
+        });
+    }
+
+    // The part parity is only known once all data has been read
+    Ok(StructuredAppend {
+        index,
+        total,
+        parity,
+        part_parity: 0,
+    })
+}
+
+fn eci_designator(chomp: &mut Chomp) -> Result<u32, QRError> {
+    let first = read_bits(chomp, 8)?;
+
+    // The number of leading 1's determines the length of the designator
+    let designator = if first & 0b1000_0000 == 0 {
+        u32::from(first)
+    } else if first & 0b1100_0000 == 0b1000_0000 {
+        let second = read_bits(chomp, 8)?;
+        u32::from(first & 0b0011_1111) << 8 | u32::from(second)
+    } else if first & 0b1110_0000 == 0b1100_0000 {
+        let rest = read_bits_u16(chomp, 16)?;
+        u32::from(first & 0b0001_1111) << 16 | u32::from(rest)
+    } else {
+        return Err(QRError {
+            msg: format!("Invalid ECI designator {:08b}", first),
+        });
+    };
+
+    debug!("ECI {:06}", designator);
+
+    Ok(designator)
+}
+
+fn numeric(chomp: &mut Chomp, length_bits: u8) -> Result<String, QRError> {
     let mut length = chomp.chomp_or_u16(
         length_bits,
         QRError {
@@ -78,18 +526,7 @@ const ALPHANUMERIC: [char; 45] = [
     '%', '*', '+', '-', '.', '/', ':',
 ];
 
-fn alphanumeric(chomp: &mut Chomp, version: u32) -> Result<String, QRError> {
-    let length_bits = match version {
-        1..=9 => 9,
-        10..=26 => 11,
-        27..=40 => 13,
-        _ => {
-            return Err(QRError {
-                msg: format!("Unknown version {}", version),
-            });
-        }
-    };
-
+fn alphanumeric(chomp: &mut Chomp, length_bits: u8) -> Result<String, QRError> {
     let mut length = chomp.chomp_or_u16(
         length_bits,
         QRError {
@@ -134,18 +571,28 @@ fn alphanumeric(chomp: &mut Chomp, version: u32) -> Result<String, QRError> {
     Ok(result)
 }
 
-fn eight_bit(chomp: &mut Chomp, version: u32) -> Result<String, QRError> {
-    let length_bits = match version {
-        1..=9 => 8,
-        10..=26 => 16,
-        27..=40 => 16,
-        _ => {
-            return Err(QRError {
-                msg: format!("Unknown version {}", version),
-            });
+// In FNC1 mode a single % represents the GS separator and a literal % is escaped as %%
+fn gs1(alphanumeric: &str) -> String {
+    let mut gs1 = String::new();
+    let mut chars = alphanumeric.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            gs1.push(c);
+        } else if chars.peek() == Some(&'%') {
+            chars.next();
+            gs1.push('%');
+        } else {
+            gs1.push('\u{1D}');
         }
-    };
+    }
+
+    debug!("ALPHANUMERIC AS GS1 {:?}", gs1);
 
+    gs1
+}
+
+fn eight_bit(chomp: &mut Chomp, length_bits: u8) -> Result<Vec<u8>, QRError> {
     let length = chomp.chomp_or_u16(
         length_bits,
         QRError {
@@ -164,9 +611,20 @@ fn eight_bit(chomp: &mut Chomp, version: u32) -> Result<String, QRError> {
 
     debug!("EIGHT BIT RAW {:?}", result);
 
+    Ok(result)
+}
+
+fn eight_bit_text(raw: &[u8], charset: Option<Charset>) -> Result<String, QRError> {
+    if let Some(charset) = charset {
+        let decoded = charset.decode(raw)?;
+        debug!("EIGHT BIT AS {:?} {:?}", charset, decoded);
+
+        return Ok(decoded);
+    }
+
     let mut may_be_utf8 = false;
 
-    for r in &result {
+    for r in raw {
         if *r == 0xC3 {
             may_be_utf8 = true;
             break;
@@ -174,13 +632,13 @@ fn eight_bit(chomp: &mut Chomp, version: u32) -> Result<String, QRError> {
     }
 
     let final_result = if may_be_utf8 {
-        let utf8 = String::from_utf8(result)?;
+        let utf8 = String::from_utf8(raw.to_vec())?;
         debug!("EIGHT BIT AS UTF-8 {:?}", utf8);
         utf8
     } else {
         let mut iso88591 = String::new();
-        for r in result {
-            iso88591.push(r as char);
+        for r in raw {
+            iso88591.push(*r as char);
         }
         debug!("EIGHT BIT AS ISO 8859-1 {:?}", iso88591);
         iso88591
@@ -189,6 +647,50 @@ fn eight_bit(chomp: &mut Chomp, version: u32) -> Result<String, QRError> {
     Ok(final_result)
 }
 
+fn kanji(chomp: &mut Chomp, length_bits: u8) -> Result<Vec<u8>, QRError> {
+    let length = chomp.chomp_or_u16(
+        length_bits,
+        QRError {
+            msg: format!("Could not read {} bits for kanji length", length_bits),
+        },
+    )?;
+
+    let mut sjis = vec![];
+
+    for _ in 0..length {
+        // Each character is packed into 13 bits, reverse the packing to get back the Shift JIS double byte
+        let packed = read_bits_u16(chomp, 13)?;
+        let mut value = (packed / 0xC0) << 8 | (packed % 0xC0);
+
+        if value < 0x1F00 {
+            value += 0x8140;
+        } else {
+            value += 0xC140;
+        }
+
+        sjis.push((value >> 8) as u8);
+        sjis.push((value & 0xFF) as u8);
+    }
+
+    debug!("KANJI RAW {:?}", sjis);
+
+    Ok(sjis)
+}
+
+fn kanji_text(sjis: &[u8]) -> Result<String, QRError> {
+    let (kanji, had_errors) = SHIFT_JIS.decode_without_bom_handling(sjis);
+
+    if had_errors {
+        return Err(QRError {
+            msg: format!("Invalid Shift JIS in kanji data, raw bytes: {:?}", sjis),
+        });
+    }
+
+    debug!("KANJI {:?}", kanji);
+
+    Ok(kanji.into_owned())
+}
+
 fn read_bits(chomp: &mut Chomp, bits: u8) -> Result<u8, QRError> {
     chomp.chomp_or(
         bits,
@@ -206,3 +708,295 @@ fn read_bits_u16(chomp: &mut Chomp, bits: u8) -> Result<u16, QRError> {
         },
     )
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // pack a string of 0's and 1's into bytes, padding the last byte with 0's
+    fn bits(input: &str) -> Vec<u8> {
+        let mut bytes = vec![];
+
+        for (count, c) in input.chars().filter(|c| *c == '0' || *c == '1').enumerate() {
+            if count % 8 == 0 {
+                bytes.push(0);
+            }
+
+            if c == '1' {
+                *bytes.last_mut().unwrap() |= 0x80 >> (count % 8);
+            }
+        }
+
+        bytes
+    }
+
+    #[test]
+    pub fn test_kanji_small() {
+        // mode, 8 bit length, 点 and 茗
+        let input = bits("1000 00000010 0110110011111 1101010101010");
+
+        assert_eq!(String::from("点茗"), data(input, 1).unwrap().text.unwrap());
+    }
+
+    #[test]
+    pub fn test_kanji_medium() {
+        // mode, 10 bit length, 点 and 茗
+        let input = bits("1000 0000000010 0110110011111 1101010101010");
+
+        assert_eq!(String::from("点茗"), data(input, 10).unwrap().text.unwrap());
+    }
+
+    #[test]
+    pub fn test_kanji_large() {
+        // mode, 12 bit length, 点 and 茗
+        let input = bits("1000 000000000010 0110110011111 1101010101010");
+
+        assert_eq!(String::from("点茗"), data(input, 40).unwrap().text.unwrap());
+    }
+
+    #[test]
+    pub fn test_kanji_mixed() {
+        // numeric 12, kanji 点
+        let input = bits("0001 0000000010 0001100 1000 00000001 0110110011111 0000");
+
+        assert_eq!(String::from("12点"), data(input, 1).unwrap().text.unwrap());
+    }
+
+    #[test]
+    pub fn test_eci_one_byte() {
+        // ECI 000009 (ISO 8859-7), byte 'α'
+        let input = bits("0111 00001001 0100 00000001 11100001");
+
+        let data = data(input, 1).unwrap();
+        assert_eq!(String::from("α"), data.text.unwrap());
+        assert_eq!(vec![9], data.eci);
+    }
+
+    #[test]
+    pub fn test_eci_two_bytes() {
+        // ECI 000130 (unsupported), the bytes are still returned
+        let input = bits("0111 10000000 10000010 0100 00000001 11100001");
+
+        let data_130 = data(input, 1).unwrap();
+        assert!(data_130.text.is_err());
+        assert_eq!(vec![0xE1], data_130.bytes);
+        assert_eq!(vec![130], data_130.eci);
+
+        // ECI 000026 (UTF-8) encoded with the two byte designator, bytes 'é'
+        let input = bits("0111 10000000 00011010 0100 00000010 11000011 10101001");
+
+        let data = data(input, 1).unwrap();
+        assert_eq!(String::from("é"), data.text.unwrap());
+        assert_eq!(vec![26], data.eci);
+    }
+
+    #[test]
+    pub fn test_eci_unknown() {
+        // ECI 000899 (not assigned), byte 0xFF, numeric '1'
+        let input = bits("0111 10000011 10000011 0100 00000001 11111111 0001 0000000001 0001");
+
+        let data = data(input, 1).unwrap();
+        assert!(data.text.is_err());
+        assert_eq!(vec![0xFF, b'1'], data.bytes);
+        assert_eq!(vec![899], data.eci);
+
+        // Only the byte segment fails, the numeric segment is still there
+        assert!(data.segments[0].value.is_err());
+        assert_eq!(vec![0xFF], data.segments[0].bytes);
+        assert_eq!(Ok(String::from("1")), data.segments[1].value);
+    }
+
+    #[test]
+    pub fn test_eci_three_bytes() {
+        // ECI 000020 (Shift JIS) encoded with the three byte designator, bytes '点'
+        let input = bits("0111 11000000 00000000 00010100 0100 00000010 10010011 01011111");
+
+        let data = data(input, 1).unwrap();
+        assert_eq!(String::from("点"), data.text.unwrap());
+        assert_eq!(vec![20], data.eci);
+    }
+
+    #[test]
+    pub fn test_eci_switch() {
+        // ECI 000022 (windows-1251) 'Ж', ECI 000003 (ISO 8859-1) 'Æ'
+        let input = bits("0111 00010110 0100 00000001 11000110 0111 00000011 0100 00000001 11000110");
+
+        let data = data(input, 1).unwrap();
+        assert_eq!(String::from("ЖÆ"), data.text.unwrap());
+        assert_eq!(vec![22, 3], data.eci);
+    }
+
+    #[test]
+    pub fn test_eci_split_character() {
+        // ECI 000026 (UTF-8), 'é' split across two byte segments, followed by numeric '1'
+        let input = bits("0111 00011010 0100 00000001 11000011 0100 00000001 10101001 0001 0000000001 0001");
+
+        assert_eq!(String::from("é1"), data(input, 1).unwrap().text.unwrap());
+    }
+
+    #[test]
+    pub fn test_eci_cp437() {
+        // ECI 000000 'Ç', ECI 000002 '░', both code page 437
+        let input = bits("0111 00000000 0100 00000001 10000000 0111 00000010 0100 00000001 10110000");
+
+        let data = data(input, 1).unwrap();
+        assert_eq!(String::from("Ç░"), data.text.unwrap());
+        assert_eq!(vec![0, 2], data.eci);
+    }
+
+    #[test]
+    pub fn test_eci_utf16() {
+        // ECI 000025 (UTF-16BE), bytes '€'
+        let input = bits("0111 00011001 0100 00000010 00100000 10101100");
+
+        assert_eq!(String::from("€"), data(input, 1).unwrap().text.unwrap());
+    }
+
+    #[test]
+    pub fn test_structured_append() {
+        // symbol 2 of 3, parity 0x5A, alphanumeric 'AB'
+        let input = bits("0011 0001 0010 01011010 0010 000000010 00111001101");
+
+        let data = data(input, 1).unwrap();
+        assert_eq!(String::from("AB"), data.text.unwrap());
+        assert_eq!(
+            Some(StructuredAppend {
+                index: 1,
+                total: 3,
+                parity: 0x5A,
+                part_parity: b'A' ^ b'B',
+            }),
+            data.structured_append
+        );
+    }
+
+    #[test]
+    pub fn test_fnc1_first() {
+        // FNC1 first position, alphanumeric '01%%%1'
+        let input = bits("0101 0010 000000110 00000000001 11011010100 11010101111");
+
+        let data = data(input, 1).unwrap();
+        assert_eq!(String::from("01%\u{1D}1"), data.text.unwrap());
+        assert_eq!(Some(FNC1::First), data.fnc1);
+    }
+
+    #[test]
+    pub fn test_fnc1_second() {
+        // FNC1 second position with application indicator 37, numeric '12'
+        let input = bits("1001 00100101 0001 0000000010 0001100");
+
+        let data = data(input, 1).unwrap();
+        assert_eq!(String::from("12"), data.text.unwrap());
+        assert_eq!(Some(FNC1::Second(37)), data.fnc1);
+    }
+
+    #[test]
+    pub fn test_percent_without_fnc1() {
+        // alphanumeric '%%' outside of FNC1 mode is left alone
+        let input = bits("0010 000000010 11011010100");
+
+        assert_eq!(String::from("%%"), data(input, 1).unwrap().text.unwrap());
+    }
+
+    #[test]
+    pub fn test_bytes() {
+        // numeric '12', invalid UTF-8 bytes 0xC3 0x28
+        let input = bits("0001 0000000010 0001100 0100 00000010 11000011 00101000");
+
+        let data = data(input, 1).unwrap();
+        assert_eq!(vec![b'1', b'2', 0xC3, 0x28], data.bytes);
+        assert!(data.text.is_err());
+    }
+
+    #[test]
+    pub fn test_segments() {
+        // numeric '12', FNC1, alphanumeric 'AB%', byte 'é' in ISO 8859-1
+        let input = bits(
+            "0001 0000000010 0001100 0101 0010 000000011 00111001101 100110 0100 00000001 11101001",
+        );
+
+        let data = data(input, 1).unwrap();
+        assert_eq!(String::from("12AB\u{1D}é"), data.text.unwrap());
+        assert_eq!(
+            vec![
+                QRSegment {
+                    mode: QRMode::NUMERIC,
+                    count: 2,
+                    offset: 0,
+                    bytes: b"12".to_vec(),
+                    value: Ok(String::from("12")),
+                },
+                QRSegment {
+                    mode: QRMode::ALPHANUMERIC,
+                    count: 3,
+                    offset: 25,
+                    bytes: b"AB\x1D".to_vec(),
+                    value: Ok(String::from("AB\u{1D}")),
+                },
+                QRSegment {
+                    mode: QRMode::BYTE,
+                    count: 1,
+                    offset: 55,
+                    bytes: vec![0xE9],
+                    value: Ok(String::from("é")),
+                },
+            ],
+            data.segments
+        );
+    }
+
+    #[test]
+    pub fn test_micro_m1() {
+        // numeric '12345', no mode indicator and a 3 bit length
+        let input = bits("101 0001111011 0101101 000");
+
+        assert_eq!(String::from("12345"), micro_data(input, 1).unwrap().text.unwrap());
+    }
+
+    #[test]
+    pub fn test_micro_m2() {
+        // alphanumeric 'AB', numeric '1'
+        let input = bits("1 010 00111001101 0 0001 0001 00000");
+
+        assert_eq!(String::from("AB1"), micro_data(input, 2).unwrap().text.unwrap());
+    }
+
+    #[test]
+    pub fn test_micro_m4() {
+        // byte 'a', kanji '点'
+        let input = bits("010 00001 01100001 011 0001 0110110011111 000000000");
+
+        let data = micro_data(input, 4).unwrap();
+        assert_eq!(String::from("a点"), data.text.unwrap());
+        assert_eq!(2, data.segments.len());
+        assert_eq!(16, data.segments[1].offset);
+    }
+
+    #[test]
+    pub fn test_rmqr_numeric() {
+        // R7x43, numeric '123' with a 4 bit length
+        let input = bits("001 0011 0001111011 000");
+
+        assert_eq!(String::from("123"), rmqr_data(input, 0).unwrap().text.unwrap());
+    }
+
+    #[test]
+    pub fn test_rmqr_eci() {
+        // R9x43, ECI 26 (UTF-8), byte 'é'
+        let input = bits("111 00011010 011 0010 11000011 10101001 000");
+
+        let data = rmqr_data(input, 5).unwrap();
+        assert_eq!(String::from("é"), data.text.unwrap());
+        assert_eq!(vec![26], data.eci);
+    }
+
+    #[test]
+    pub fn test_rmqr_fnc1() {
+        // R9x43, FNC1 first position, alphanumeric 'AB%'
+        let input = bits("101 010 00011 00111001101 100110 000");
+
+        let data = rmqr_data(input, 5).unwrap();
+        assert_eq!(String::from("AB\u{1D}"), data.text.unwrap());
+        assert_eq!(Some(FNC1::First), data.fnc1);
+    }
+}
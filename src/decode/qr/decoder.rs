@@ -1,6 +1,8 @@
 use super::super::Decode;
+use super::data::Data;
+use super::BlockInfo;
 
-use crate::util::qr::{QRData, QRError, QRInfo};
+use crate::util::qr::{ECLevel, QRData, QRError, QRInfo, QRSegment, RMQRData};
 
 /// Decode a QR code into a resulting String
 ///
@@ -10,6 +12,9 @@ use crate::util::qr::{QRData, QRError, QRInfo};
 /// * Perform error correction
 /// * Decode the blocks into a String
 ///
+/// If that fails, and the format information has fewer errors with the rows and columns of the QR Code swapped,
+/// it tries again on the swapped QR Code, as the QR Code was mirrored.
+///
 /// # Optimisation
 /// The error correction process can be relatively expensive. This decoder has a fast detection of the existence of errors,
 /// allowing to bypass the correction altogether if none exist. Users of this library are encouraged to provide high quality fault-free images,
@@ -25,26 +30,51 @@ impl QRDecoder {
 
 impl Decode<QRData, String, QRError> for QRDecoder {
     fn decode(&self, data: Result<QRData, QRError>) -> Result<String, QRError> {
-        let qr_data = data?;
+        let ((data, _, _, _), _) = unmirror(data?, decode_data)?;
+        data.text
+    }
+}
 
-        let format = super::format::format(&qr_data)?;
-        let blocks = super::blocks::blocks(&qr_data, &format.0, &format.1)?;
-        let block_info = super::block_info(qr_data.version, &format.0)?;
+/// Decode a QR code into the raw bytes it contains
+///
+/// Functions the same as QRDecoder, but skips converting the decoded data to a String. Byte segments are returned exactly as they
+/// were encoded, without guessing a character set, which makes this decoder suitable for binary payloads.
+/// Numeric and alphanumeric segments are returned as their ASCII bytes, kanji segments as their Shift JIS bytes.
+/// The bytes are returned even if an ECI designator switches to a character set that is not supported.
+pub struct QRDecoderBytes {}
 
-        let mut all_blocks = vec![];
+impl QRDecoderBytes {
+    /// Construct a new QRDecoderBytes
+    pub fn new() -> QRDecoderBytes {
+        QRDecoderBytes {}
+    }
+}
 
-        for (block, bi) in blocks.into_iter().zip(block_info) {
-            let corrected = super::correct::correct(block, &bi)?;
+impl Decode<QRData, Vec<u8>, QRError> for QRDecoderBytes {
+    fn decode(&self, data: Result<QRData, QRError>) -> Result<Vec<u8>, QRError> {
+        let ((data, _, _, _), _) = unmirror(data?, decode_data)?;
+        Ok(data.bytes)
+    }
+}
 
-            for corr in corrected.iter().take(bi.data_per as usize) {
-                all_blocks.push(*corr);
-            }
-        }
+/// Decode a QR code into the list of segments it consists of
+///
+/// Functions the same as QRDecoder, but instead of joining the segments into a single String it returns every segment
+/// with its mode, character count and location in the data codewords. This shows exactly how the encoder chose to split up the data.
+/// Segments that could not be converted to a String are returned as well, with their raw bytes and the error.
+pub struct QRDecoderSegments {}
 
-        debug!("TOTAL LENGTH {}", all_blocks.len());
+impl QRDecoderSegments {
+    /// Construct a new QRDecoderSegments
+    pub fn new() -> QRDecoderSegments {
+        QRDecoderSegments {}
+    }
+}
 
-        let data = super::data::data(all_blocks, qr_data.version)?;
-        Ok(data)
+impl Decode<QRData, Vec<QRSegment>, QRError> for QRDecoderSegments {
+    fn decode(&self, data: Result<QRData, QRError>) -> Result<Vec<QRSegment>, QRError> {
+        let ((data, _, _, _), _) = unmirror(data?, decode_data)?;
+        Ok(data.segments)
     }
 }
 
@@ -63,36 +93,305 @@ impl QRDecoderWithInfo {
 impl Decode<QRData, (String, QRInfo), QRError> for QRDecoderWithInfo {
     fn decode(&self, data: Result<QRData, QRError>) -> Result<(String, QRInfo), QRError> {
         let qr_data = data?;
+        let (version, estimated_version) = (qr_data.version, qr_data.estimated_version);
+
+        let ((data, ec_level, errors, total_data), mirrored) = unmirror(qr_data, decode_data)?;
+
+        Ok((
+            data.text?,
+            QRInfo {
+                version,
+                estimated_version,
+                ec_level,
+                total_data,
+                errors,
+                eci: data.eci,
+                structured_append: data.structured_append,
+                fnc1: data.fnc1,
+                mirrored,
+            },
+        ))
+    }
+}
+
+/// Decode a QR code into the raw bytes it contains. It also includes some information about the decoded QR Code.
+///
+/// Functions the same as QRDecoderBytes, apart from also returning some information about the decoded QR Code,
+/// which allows binary structured append series to be joined with the [`Reassembler`].
+///
+/// [`Reassembler`]: ../util/struct.Reassembler.html
+pub struct QRDecoderBytesWithInfo {}
+
+impl QRDecoderBytesWithInfo {
+    /// Construct a new QRDecoderBytesWithInfo
+    pub fn new() -> QRDecoderBytesWithInfo {
+        QRDecoderBytesWithInfo {}
+    }
+}
+
+impl Decode<QRData, (Vec<u8>, QRInfo), QRError> for QRDecoderBytesWithInfo {
+    fn decode(&self, data: Result<QRData, QRError>) -> Result<(Vec<u8>, QRInfo), QRError> {
+        let qr_data = data?;
+        let (version, estimated_version) = (qr_data.version, qr_data.estimated_version);
+
+        let ((data, ec_level, errors, total_data), mirrored) = unmirror(qr_data, decode_data)?;
+
+        Ok((
+            data.bytes,
+            QRInfo {
+                version,
+                estimated_version,
+                ec_level,
+                total_data,
+                errors,
+                eci: data.eci,
+                structured_append: data.structured_append,
+                fnc1: data.fnc1,
+                mirrored,
+            },
+        ))
+    }
+}
+
+/// Decode a Micro QR code into a resulting String
+///
+/// This decoder will, in order:
+/// * Determine Micro QR Format information, which includes the version, error correction level and mask
+/// * Extract the single block of codewords
+/// * Perform error correction, or only error detection for M1 symbols
+/// * Decode the block into a String
+pub struct MicroQRDecoder {}
+
+impl MicroQRDecoder {
+    /// Construct a new MicroQRDecoder
+    pub fn new() -> MicroQRDecoder {
+        MicroQRDecoder {}
+    }
+}
+
+impl Decode<QRData, String, QRError> for MicroQRDecoder {
+    fn decode(&self, data: Result<QRData, QRError>) -> Result<String, QRError> {
+        let qr_data = data?;
+
+        let (version, level, mask) = super::micro::format(&qr_data)?;
+        let bi = super::micro::block_info(version, &level)?;
+        let block = super::micro::blocks(&qr_data, &bi, &mask)?;
+
+        let (corrected, error_count) = super::correct::correct_with_error_count(block, &bi)?;
+
+        if version == 1 && error_count > 0 {
+            return Err(QRError {
+                msg: String::from("Errors detected in M1 symbol, which does not support error correction"),
+            });
+        }
+
+        let all_blocks = corrected[..bi.data_per as usize].to_vec();
+
+        debug!("TOTAL LENGTH {}", all_blocks.len());
+
+        let data = super::data::micro_data(all_blocks, version)?;
+        data.text
+    }
+}
+
+/// Decode an rMQR code into a resulting String
+///
+/// This decoder will, in order:
+/// * Determine rMQR Format information, which includes the version and error correction level
+/// * Extract the interleaved blocks of codewords
+/// * Perform error correction
+/// * Decode the blocks into a String
+pub struct RMQRDecoder {}
+
+impl RMQRDecoder {
+    /// Construct a new RMQRDecoder
+    pub fn new() -> RMQRDecoder {
+        RMQRDecoder {}
+    }
+}
+
+impl Decode<RMQRData, String, QRError> for RMQRDecoder {
+    fn decode(&self, data: Result<RMQRData, QRError>) -> Result<String, QRError> {
+        let rmqr_data = data?;
 
-        let format = super::format::format(&qr_data)?;
-        let blocks = super::blocks::blocks(&qr_data, &format.0, &format.1)?;
-        let block_info = super::block_info(qr_data.version, &format.0)?;
+        let level = super::rmqr::format(&rmqr_data)?;
+        let blocks = super::rmqr::blocks(&rmqr_data, &level)?;
+        let block_info = super::rmqr::block_info(rmqr_data.version, &level)?;
 
         let mut all_blocks = vec![];
-        let mut total_errors = 0;
 
         for (block, bi) in blocks.into_iter().zip(block_info) {
-            let (corrected, error_count) = super::correct::correct_with_error_count(block, &bi)?;
+            let corrected = super::correct::correct(block, &bi)?;
 
             for corr in corrected.iter().take(bi.data_per as usize) {
                 all_blocks.push(*corr);
             }
-
-            total_errors += error_count;
         }
 
         debug!("TOTAL LENGTH {}", all_blocks.len());
-        let total_data = (all_blocks.len() as u32) * 8;
 
-        let data = super::data::data(all_blocks, qr_data.version)?;
-        Ok((
-            data,
-            QRInfo {
-                version: qr_data.version,
-                ec_level: format.0,
-                total_data,
-                errors: total_errors,
-            },
-        ))
+        let data = super::data::rmqr_data(all_blocks, rmqr_data.version)?;
+        data.text
+    }
+}
+
+// Determine the format, extract and correct the blocks and decode the data of a QR Code, returning the decoded data,
+// the error correction level, the number of bits that were corrected and the total number of bits of data
+fn decode_data(qr_data: &QRData) -> Result<(Data, ECLevel, u32, u32), QRError> {
+    let format = super::format::format(qr_data)?;
+    let (blocks, confidence) =
+        super::blocks::blocks_with_confidence(qr_data, &format.0, &format.1)?;
+    let block_info = super::block_info(qr_data.version, &format.0)?;
+
+    let mut all_blocks = vec![];
+    let mut total_errors = 0;
+
+    for ((block, confidence), bi) in blocks.into_iter().zip(confidence).zip(block_info) {
+        let (corrected, error_count) = correct(block, &confidence, &bi)?;
+
+        for corr in corrected.iter().take(bi.data_per as usize) {
+            all_blocks.push(*corr);
+        }
+
+        total_errors += error_count;
+    }
+
+    debug!("TOTAL LENGTH {}", all_blocks.len());
+    let total_data = (all_blocks.len() as u32) * 8;
+
+    let data = super::data::data(all_blocks, qr_data.version)?;
+
+    Ok((data, format.0, total_errors, total_data))
+}
+
+// Decode the QR Code, or else its transpose in case the QR Code was mirrored, returning whether it was
+//
+// A mirrored QR Code is recognised by its format information, which fails to decode on the grid as read but does
+// decode on the transposed grid. The error correction of the format information may well turn the reversed bits into
+// some other valid format though, so failing is measured as having more bit errors than the transposed grid has.
+// Only then is the transposed QR Code decoded, so a QR Code that is merely damaged is not reported as mirrored.
+fn unmirror<T, F>(qr_data: QRData, decode: F) -> Result<(T, bool), QRError>
+where
+    F: Fn(&QRData) -> Result<T, QRError>,
+{
+    let error = match decode(&qr_data) {
+        Ok(result) => return Ok((result, false)),
+        Err(e) => e,
+    };
+
+    let transposed = qr_data.transpose();
+
+    let errors = super::format::errors(&qr_data);
+    let transposed_errors = super::format::errors(&transposed);
+
+    debug!("FORMAT ERRORS {}, TRANSPOSED {}", errors, transposed_errors);
+
+    if transposed_errors >= errors {
+        return Err(error);
+    }
+
+    match decode(&transposed) {
+        Ok(result) => {
+            debug!("QR CODE IS MIRRORED");
+            Ok((result, true))
+        }
+        Err(_) => Err(error),
+    }
+}
+
+// Modules sampled with less confidence make the codeword they are part of an erasure
+const MIN_CONFIDENCE: u8 = 128;
+
+// Correct a block of a QR Code, trying in order:
+// * the codewords of low confidence as erasures
+// * only correcting errors
+// * the least confident codewords as erasures, as many as the error correction capacity allows
+fn correct(block: Vec<u8>, confidence: &[u8], bi: &BlockInfo) -> Result<(Vec<u8>, u32), QRError> {
+    let erasures: Vec<usize> = (0..block.len())
+        .filter(|i| confidence[*i] < MIN_CONFIDENCE)
+        .collect();
+
+    if !erasures.is_empty() {
+        match super::correct::correct_with_erasures(block.clone(), &erasures, bi) {
+            Ok(corrected) => return Ok(corrected),
+            Err(e) => debug!("CORRECTING WITH ERASURES {:?} FAILED: {}", erasures, e.msg),
+        }
+    }
+
+    let error = match super::correct::correct_with_error_count(block.clone(), bi) {
+        Ok(corrected) => return Ok(corrected),
+        Err(e) => e,
+    };
+
+    let mut least_confident: Vec<usize> =
+        (0..block.len()).filter(|i| confidence[*i] < 255).collect();
+    least_confident.sort_by_key(|i| confidence[*i]);
+    least_confident.truncate(bi.ec_cap as usize);
+    least_confident.sort_unstable();
+
+    if least_confident.is_empty() || least_confident == erasures {
+        return Err(error);
+    }
+
+    debug!(
+        "RETRYING WITH LEAST CONFIDENT CODEWORDS {:?}",
+        least_confident
+    );
+
+    super::correct::correct_with_erasures(block, &least_confident, bi).map_err(|_| error)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::cell::Cell;
+
+    use crate::encode::QREncoder;
+
+    #[test]
+    pub fn test_mirrored() {
+        let data = QREncoder::new(ECLevel::QUARTILE).encode("MIRROR").unwrap();
+
+        let (text, info) = QRDecoderWithInfo::new()
+            .decode(Ok(data.transpose()))
+            .unwrap();
+        assert_eq!("MIRROR", text);
+        assert!(info.mirrored);
+
+        let (text, info) = QRDecoderWithInfo::new().decode(Ok(data)).unwrap();
+        assert_eq!("MIRROR", text);
+        assert!(!info.mirrored);
+    }
+
+    #[test]
+    pub fn test_bytes_with_info() {
+        let data = QREncoder::new(ECLevel::QUARTILE).encode("MIRROR").unwrap();
+
+        let (bytes, info) = QRDecoderBytesWithInfo::new().decode(Ok(data)).unwrap();
+        assert_eq!(b"MIRROR".to_vec(), bytes);
+        assert_eq!(1, info.version);
+        assert_eq!(ECLevel::QUARTILE, info.ec_level);
+    }
+
+    #[test]
+    pub fn test_not_mirrored() {
+        // Failing to decode a QR Code that is not mirrored does not try the transposed grid, which may decode by accident
+        let data = QREncoder::new(ECLevel::QUARTILE).encode("MIRROR").unwrap();
+        let calls = Cell::new(0);
+
+        let result = unmirror(data, |_| {
+            calls.set(calls.get() + 1);
+
+            match calls.get() {
+                1 => Err(QRError {
+                    msg: String::from("Undecodable"),
+                }),
+                _ => Ok(()),
+            }
+        });
+
+        assert!(result.is_err());
+        assert_eq!(1, calls.get());
     }
 }
@@ -0,0 +1,114 @@
+use crate::util::qr::QRError;
+
+use encoding_rs::{
+    Encoding, BIG5, EUC_KR, GB18030, GBK, ISO_8859_10, ISO_8859_13, ISO_8859_14, ISO_8859_15,
+    ISO_8859_16, ISO_8859_2, ISO_8859_3, ISO_8859_4, ISO_8859_5, ISO_8859_6, ISO_8859_7,
+    ISO_8859_8, SHIFT_JIS, UTF_16BE, UTF_8, WINDOWS_1250, WINDOWS_1251, WINDOWS_1252,
+    WINDOWS_1254, WINDOWS_1256, WINDOWS_874,
+};
+
+// Characters 0x80 to 0xFF of code page 437, the lower half is plain ASCII
+const CP437: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', 'É', 'æ', 'Æ',
+    'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', 'á', 'í', 'ó', 'ú', 'ñ', 'Ñ',
+    'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕',
+    '╣', '║', '╗', '╝', '╜', '╛', '┐', '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦',
+    '╠', '═', '╬', '╧', '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐',
+    '▀', 'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', '≡', '±',
+    '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{A0}',
+];
+
+/// Character set selected by an ECI designator
+#[derive(Debug, Clone, Copy)]
+pub enum Charset {
+    /// ISO 8859-1 maps every byte straight onto the first 256 unicode code points
+    Iso88591,
+
+    /// US-ASCII, bytes above 0x7F are invalid
+    Ascii,
+
+    /// Code page 437, the original character set of the IBM PC
+    Cp437,
+
+    /// Any other character set is handled by `encoding_rs`
+    Encoded(&'static Encoding),
+}
+
+impl Charset {
+    /// Look up the character set for the provided ECI assignment number
+    pub fn from_eci(eci: u32) -> Result<Charset, QRError> {
+        let charset = match eci {
+            0 | 2 => Charset::Cp437,
+            1 | 3 => Charset::Iso88591,
+            4 => Charset::Encoded(ISO_8859_2),
+            5 => Charset::Encoded(ISO_8859_3),
+            6 => Charset::Encoded(ISO_8859_4),
+            7 => Charset::Encoded(ISO_8859_5),
+            8 => Charset::Encoded(ISO_8859_6),
+            9 => Charset::Encoded(ISO_8859_7),
+            10 => Charset::Encoded(ISO_8859_8),
+            // encoding_rs treats ISO 8859-9 as its superset windows-1254
+            11 => Charset::Encoded(WINDOWS_1254),
+            12 => Charset::Encoded(ISO_8859_10),
+            // encoding_rs treats ISO 8859-11 as its superset windows-874
+            13 => Charset::Encoded(WINDOWS_874),
+            15 => Charset::Encoded(ISO_8859_13),
+            16 => Charset::Encoded(ISO_8859_14),
+            17 => Charset::Encoded(ISO_8859_15),
+            18 => Charset::Encoded(ISO_8859_16),
+            20 => Charset::Encoded(SHIFT_JIS),
+            21 => Charset::Encoded(WINDOWS_1250),
+            22 => Charset::Encoded(WINDOWS_1251),
+            23 => Charset::Encoded(WINDOWS_1252),
+            24 => Charset::Encoded(WINDOWS_1256),
+            25 => Charset::Encoded(UTF_16BE),
+            26 => Charset::Encoded(UTF_8),
+            27 | 170 => Charset::Ascii,
+            28 => Charset::Encoded(BIG5),
+            29 => Charset::Encoded(GBK),
+            30 => Charset::Encoded(EUC_KR),
+            32 => Charset::Encoded(GB18030),
+            _ => {
+                return Err(QRError {
+                    msg: format!("Unsupported ECI {:06}", eci),
+                })
+            }
+        };
+
+        Ok(charset)
+    }
+
+    /// Decode the provided bytes into a String using this character set
+    pub fn decode(&self, bytes: &[u8]) -> Result<String, QRError> {
+        match self {
+            Charset::Iso88591 => Ok(bytes.iter().map(|b| *b as char).collect()),
+            Charset::Ascii => {
+                if bytes.iter().any(|b| *b > 0x7F) {
+                    return Err(QRError {
+                        msg: format!("Invalid US-ASCII, raw bytes: {:?}", bytes),
+                    });
+                }
+
+                Ok(bytes.iter().map(|b| *b as char).collect())
+            }
+            Charset::Cp437 => Ok(bytes
+                .iter()
+                .map(|b| match b {
+                    0x00..=0x7F => *b as char,
+                    _ => CP437[*b as usize - 0x80],
+                })
+                .collect()),
+            Charset::Encoded(encoding) => {
+                let (decoded, had_errors) = encoding.decode_without_bom_handling(bytes);
+
+                if had_errors {
+                    return Err(QRError {
+                        msg: format!("Invalid {}, raw bytes: {:?}", encoding.name(), bytes),
+                    });
+                }
+
+                Ok(decoded.into_owned())
+            }
+        }
+    }
+}
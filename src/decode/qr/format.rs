@@ -3,13 +3,16 @@ use super::{ECLevel, QRMask};
 
 use crate::util::qr::{QRData, QRError};
 
-const MASK: [u8; 15] = [1, 0, 1, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 1, 0];
+pub const MASK: [u8; 15] = [1, 0, 1, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 1, 0];
+
+// Generator polynomial of the BCH code protecting the format information
+const FORMAT_GENERATOR: u32 = 0b101_0011_0111;
 
 pub fn format(data: &QRData) -> Result<(ECLevel, Box<QRMask>), QRError> {
-    let mut format = format1(data);
+    let mut format = correct(format1(data));
 
     if format.is_err() {
-        format = format2(data);
+        format = correct(format2(data));
     }
 
     let format = format?;
@@ -20,7 +23,41 @@ pub fn format(data: &QRData) -> Result<(ECLevel, Box<QRMask>), QRError> {
     Ok((correction, mask))
 }
 
-fn format1(data: &QRData) -> Result<Vec<u8>, QRError> {
+/// Format information of 5 bits, the error correction level followed by the mask, with the 10 error correction bits appended
+pub fn format_information(format: u32) -> u32 {
+    let mut remainder = format << 10;
+
+    for i in (10..15).rev() {
+        if (remainder >> i) & 1 == 1 {
+            remainder ^= FORMAT_GENERATOR << (i - 10);
+        }
+    }
+
+    format << 10 | remainder
+}
+
+/// Number of bit errors in the format information, as the distance to the closest valid format of the best of both copies
+///
+/// This tells how well the modules line up with where the format information is expected, without correcting it.
+pub fn errors(data: &QRData) -> u32 {
+    [format1(data), format2(data)]
+        .iter()
+        .map(|bits| {
+            let bits = bits
+                .iter()
+                .fold(0, |value, bit| value << 1 | u32::from(*bit));
+
+            (0..32)
+                .map(|format| (format_information(format) ^ bits).count_ones())
+                .min()
+                .unwrap_or(15)
+        })
+        .min()
+        .unwrap_or(15)
+}
+
+// Read the format information next to the top left finder pattern, and remove the mask
+fn format1(data: &QRData) -> Vec<u8> {
     let mut format1 = vec![];
 
     for x in 0..9 {
@@ -43,10 +80,11 @@ fn format1(data: &QRData) -> Result<Vec<u8>, QRError> {
         format1[i] ^= MASK[i];
     }
 
-    correct(format1)
+    format1
 }
 
-fn format2(data: &QRData) -> Result<Vec<u8>, QRError> {
+// Read the format information next to the other two finder patterns, and remove the mask
+fn format2(data: &QRData) -> Vec<u8> {
     let mut format2 = vec![];
 
     for y in (data.side - 7..data.side).rev() {
@@ -61,10 +99,10 @@ fn format2(data: &QRData) -> Result<Vec<u8>, QRError> {
         format2[i] ^= MASK[i];
     }
 
-    correct(format2)
+    format2
 }
 
-fn correct(mut format: Vec<u8>) -> Result<Vec<u8>, QRError> {
+pub fn correct(mut format: Vec<u8>) -> Result<Vec<u8>, QRError> {
     let mut s1 = GF4(0);
 
     for i in 0..format.len() {
@@ -134,27 +172,32 @@ fn error_correction(bytes: u8) -> Option<ECLevel> {
     }
 }
 
-fn mask(bytes: u8) -> Option<Box<QRMask>> {
+pub fn mask(bytes: u8) -> Option<Box<QRMask>> {
     debug!("MASK {:03b}", bytes);
-    match bytes {
-        0b000 => qrmask(Box::new(|j, i| (i + j) % 2 == 0)),
-        0b001 => qrmask(Box::new(|_, i| i % 2 == 0)),
-        0b010 => qrmask(Box::new(|j, _| j % 3 == 0)),
-        0b011 => qrmask(Box::new(|j, i| (i + j) % 3 == 0)),
-        0b100 => qrmask(Box::new(|j, i| (i / 2 + j / 3) % 2 == 0)),
-        0b101 => qrmask(Box::new(|j, i| (i * j) % 2 + (i * j) % 3 == 0)),
-        0b110 => qrmask(Box::new(|j, i| ((i * j) % 2 + (i * j) % 3) % 2 == 0)),
-        0b111 => qrmask(Box::new(|j, i| ((i * j) % 3 + (i + j) % 2) % 2 == 0)),
-        _ => None,
-    }
+    condition(bytes).map(qrmask)
 }
 
-type Mask = dyn Fn(u32, u32) -> bool;
+// Condition of a mask pattern, true for the modules at (x, y) that are inverted
+pub type Condition = dyn Fn(u32, u32) -> bool;
+
+pub fn condition(bytes: u8) -> Option<Box<Condition>> {
+    let condition: Box<Condition> = match bytes {
+        0b000 => Box::new(|j, i| (i + j) % 2 == 0),
+        0b001 => Box::new(|_, i| i % 2 == 0),
+        0b010 => Box::new(|j, _| j % 3 == 0),
+        0b011 => Box::new(|j, i| (i + j) % 3 == 0),
+        0b100 => Box::new(|j, i| (i / 2 + j / 3) % 2 == 0),
+        0b101 => Box::new(|j, i| (i * j) % 2 + (i * j) % 3 == 0),
+        0b110 => Box::new(|j, i| ((i * j) % 2 + (i * j) % 3) % 2 == 0),
+        0b111 => Box::new(|j, i| ((i * j) % 3 + (i + j) % 2) % 2 == 0),
+        _ => return None,
+    };
+
+    Some(condition)
+}
 
-fn qrmask(mask: Box<Mask>) -> Option<Box<QRMask>> {
-    Some(Box::new(move |q: &QRData, i: u32, j: u32| {
-        q[[i, j]] ^ u8::from(mask(i, j))
-    }))
+fn qrmask(condition: Box<Condition>) -> Box<QRMask> {
+    Box::new(move |q: &QRData, i: u32, j: u32| q[[i, j]] ^ u8::from(condition(i, j)))
 }
 
 #[cfg(test)]
@@ -185,6 +228,15 @@ mod test {
         assert_eq!(input_orig, output.unwrap());
     }
 
+    #[test]
+    pub fn test_format_information() {
+        let bits = CORRECT
+            .iter()
+            .fold(0, |value, bit| value << 1 | u32::from(*bit));
+
+        assert_eq!(bits, format_information(0b00011));
+    }
+
     #[test]
     pub fn test_corrupt() {
         let input_orig = CORRECT.to_vec();
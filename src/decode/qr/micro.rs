@@ -0,0 +1,161 @@
+use super::format::{correct, mask};
+use super::{BlockInfo, ECLevel, QRMask};
+
+use crate::util::qr::{QRData, QRError};
+
+const MASK: [u8; 15] = [1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 1];
+
+pub fn format(data: &QRData) -> Result<(u32, ECLevel, Box<QRMask>), QRError> {
+    let mut format = vec![];
+
+    // Micro QR only has a single copy of the format information, next to the finder
+    for x in 1..9 {
+        format.push(data[[x, 8]]);
+    }
+
+    for y in (1..8).rev() {
+        format.push(data[[8, y]]);
+    }
+
+    for i in 0..format.len() {
+        format[i] ^= MASK[i];
+    }
+
+    let format = correct(format)?;
+
+    let (version, level) = symbol(4 * format[0] + 2 * format[1] + format[2]);
+
+    // The four Micro QR masks are a subset of the regular QR masks
+    let mask = match 2 * format[3] + format[4] {
+        0b00 => mask(0b001),
+        0b01 => mask(0b100),
+        0b10 => mask(0b110),
+        _ => mask(0b111),
+    }
+    .unwrap();
+
+    debug!("MICRO QR M{} {:?}", version, level);
+
+    if version != data.version {
+        return Err(QRError {
+            msg: format!(
+                "Format information indicates version M{} but symbol is version M{}",
+                version, data.version
+            ),
+        });
+    }
+
+    Ok((version, level, mask))
+}
+
+// M1 only supports error detection, LOW is used as a stand in
+fn symbol(number: u8) -> (u32, ECLevel) {
+    match number {
+        0 => (1, ECLevel::LOW),
+        1 => (2, ECLevel::LOW),
+        2 => (2, ECLevel::MEDIUM),
+        3 => (3, ECLevel::LOW),
+        4 => (3, ECLevel::MEDIUM),
+        5 => (4, ECLevel::LOW),
+        6 => (4, ECLevel::MEDIUM),
+        _ => (4, ECLevel::QUARTILE),
+    }
+}
+
+pub fn block_info(version: u32, level: &ECLevel) -> Result<BlockInfo, QRError> {
+    match (version, level) {
+        // M1 can only detect errors, the capacity of 1 is only used to calculate the syndromes
+        (1, ECLevel::LOW) => Ok(BlockInfo::new(1, 5, 3, 1)),
+        (2, ECLevel::LOW) => Ok(BlockInfo::new(1, 10, 5, 1)),
+        (2, ECLevel::MEDIUM) => Ok(BlockInfo::new(1, 10, 4, 2)),
+        (3, ECLevel::LOW) => Ok(BlockInfo::new(1, 17, 11, 2)),
+        (3, ECLevel::MEDIUM) => Ok(BlockInfo::new(1, 17, 9, 4)),
+        (4, ECLevel::LOW) => Ok(BlockInfo::new(1, 24, 16, 3)),
+        (4, ECLevel::MEDIUM) => Ok(BlockInfo::new(1, 24, 14, 5)),
+        (4, ECLevel::QUARTILE) => Ok(BlockInfo::new(1, 24, 10, 7)),
+        (version, level) => Err(QRError {
+            msg: format!(
+                "Unknown combination of version M{} and level {:?}",
+                version, level
+            ),
+        }),
+    }
+}
+
+#[allow(clippy::borrowed_box)] // QRMask is a trait, unsure how to solve
+pub fn blocks(data: &QRData, bi: &BlockInfo, mask: &Box<QRMask>) -> Result<Vec<u8>, QRError> {
+    let mut bits = vec![];
+    let mut x = data.side - 1;
+    let mut upwards = true;
+
+    loop {
+        let y_range: Box<dyn Iterator<Item = u32>> = if upwards {
+            Box::new((0..data.side).rev())
+        } else {
+            Box::new(0..data.side)
+        };
+
+        for y in y_range {
+            if is_data(x, y) {
+                bits.push(mask(data, x, y));
+            }
+
+            if is_data(x - 1, y) {
+                bits.push(mask(data, x - 1, y));
+            }
+        }
+
+        // The timing pattern is in the leftmost column so there is no need to skip it
+        if x == 2 {
+            break;
+        }
+
+        x -= 2;
+        upwards = !upwards;
+    }
+
+    // M1 and M3 have a final data codeword that is only 4 bits long
+    let half_codeword = data.version == 1 || data.version == 3;
+
+    let mut bits = bits.into_iter();
+    let mut block = vec![];
+
+    for i in 0..bi.total_per {
+        let codeword_bits = if half_codeword && i == bi.data_per - 1 {
+            4
+        } else {
+            8
+        };
+
+        let mut codeword = 0;
+        for _ in 0..codeword_bits {
+            let bit = bits.next().ok_or(QRError {
+                msg: format!("Not enough data modules for {} codewords", bi.total_per),
+            })?;
+
+            codeword = codeword * 2 + bit;
+        }
+
+        if codeword_bits == 4 {
+            codeword <<= 4;
+        }
+
+        block.push(codeword);
+    }
+
+    Ok(block)
+}
+
+fn is_data(x: u32, y: u32) -> bool {
+    // timing patterns
+    if x == 0 || y == 0 {
+        return false;
+    }
+
+    // locator pattern, separator and format information
+    if x < 9 && y < 9 {
+        return false;
+    }
+
+    true
+}
@@ -4,8 +4,12 @@ pub mod blocks;
 pub mod correct;
 pub mod data;
 pub mod decoder;
+pub mod eci;
 pub mod format;
 pub mod galois;
+pub mod micro;
+pub mod rmqr;
+pub mod version;
 
 pub type QRMask = dyn Fn(&QRData, u32, u32) -> u8;
 
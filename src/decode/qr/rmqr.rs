@@ -0,0 +1,421 @@
+use super::blocks::Codewords;
+use super::{BlockInfo, ECLevel};
+
+use crate::util::qr::{QRError, RMQRData};
+
+const FORMAT_MASK: u32 = 0b01_1111_1010_1011_0010;
+const FORMAT_MASK_SUB: u32 = 0b10_0000_1010_0111_1011;
+
+// x^12 + x^11 + x^10 + x^9 + x^8 + x^5 + x^2 + 1
+const FORMAT_GENERATOR: u32 = 0b1_1111_0010_0101;
+
+pub fn format(data: &RMQRData) -> Result<ECLevel, QRError> {
+    let (width, height) = (data.width, data.height);
+
+    // The first copy is next to the finder pattern, the second copy next to the sub finder pattern
+    let mut format1 = 0;
+
+    for y in (1..4).rev() {
+        format1 = format1 * 2 + u32::from(data[[11, y]]);
+    }
+
+    for x in (8..11).rev() {
+        for y in (1..6).rev() {
+            format1 = format1 * 2 + u32::from(data[[x, y]]);
+        }
+    }
+
+    let mut format2 = 0;
+
+    for x in 3..6 {
+        format2 = format2 * 2 + u32::from(data[[width - x, height - 6]]);
+    }
+
+    for x in 6..9 {
+        for y in 2..7 {
+            format2 = format2 * 2 + u32::from(data[[width - x, height - y]]);
+        }
+    }
+
+    let format = correct(format1 ^ FORMAT_MASK, format2 ^ FORMAT_MASK_SUB)?;
+
+    let level = if format & 0b10_0000 == 0 {
+        ECLevel::MEDIUM
+    } else {
+        ECLevel::HIGH
+    };
+    let version = format & 0b1_1111;
+
+    debug!("RMQR VERSION {} {:?}", version, level);
+
+    if version != data.version {
+        return Err(QRError {
+            msg: format!(
+                "Format information indicates version {} but symbol is version {}",
+                version, data.version
+            ),
+        });
+    }
+
+    Ok(level)
+}
+
+// The (18, 6) BCH code only has 64 valid code words, so simply find the closest one to either copy
+fn correct(format1: u32, format2: u32) -> Result<u32, QRError> {
+    let mut best = (u32::MAX, 0);
+
+    for candidate in 0..64 {
+        let code = bch(candidate);
+        let distance = (code ^ format1)
+            .count_ones()
+            .min((code ^ format2).count_ones());
+
+        if distance < best.0 {
+            best = (distance, candidate);
+        }
+    }
+
+    debug!("RMQR FORMAT {:06b}, DISTANCE {}", best.1, best.0);
+
+    // The code has a minimum distance of 8, so up to 3 errors can be corrected
+    if best.0 > 3 {
+        return Err(QRError {
+            msg: String::from("Unable to correct rMQR format information"),
+        });
+    }
+
+    Ok(best.1)
+}
+
+fn bch(data: u32) -> u32 {
+    let mut remainder = data << 12;
+
+    for bit in (12..18).rev() {
+        if remainder & (1 << bit) != 0 {
+            remainder ^= FORMAT_GENERATOR << (bit - 12);
+        }
+    }
+
+    data << 12 | remainder
+}
+
+pub fn block_info(version: u32, level: &ECLevel) -> Result<Vec<BlockInfo>, QRError> {
+    let block_info = match (version, level) {
+        // R7x43
+        (0, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 13, 6, 3)],
+        (0, ECLevel::HIGH) => vec![BlockInfo::new(1, 13, 3, 5)],
+
+        // R7x59
+        (1, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 21, 12, 4)],
+        (1, ECLevel::HIGH) => vec![BlockInfo::new(1, 21, 7, 7)],
+
+        // R7x77
+        (2, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 32, 20, 6)],
+        (2, ECLevel::HIGH) => vec![BlockInfo::new(1, 32, 10, 11)],
+
+        // R7x99
+        (3, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 44, 28, 8)],
+        (3, ECLevel::HIGH) => vec![BlockInfo::new(1, 44, 14, 15)],
+
+        // R7x139
+        (4, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 68, 44, 12)],
+        (4, ECLevel::HIGH) => vec![BlockInfo::new(2, 34, 12, 11)],
+
+        // R9x43
+        (5, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 21, 12, 4)],
+        (5, ECLevel::HIGH) => vec![BlockInfo::new(1, 21, 7, 7)],
+
+        // R9x59
+        (6, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 33, 21, 6)],
+        (6, ECLevel::HIGH) => vec![BlockInfo::new(1, 33, 11, 11)],
+
+        // R9x77
+        (7, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 49, 31, 9)],
+        (7, ECLevel::HIGH) => vec![BlockInfo::new(1, 24, 8, 8), BlockInfo::new(1, 25, 9, 8)],
+
+        // R9x99
+        (8, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 66, 42, 12)],
+        (8, ECLevel::HIGH) => vec![BlockInfo::new(2, 33, 11, 11)],
+
+        // R9x139
+        (9, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 49, 31, 9), BlockInfo::new(1, 50, 32, 9)],
+        (9, ECLevel::HIGH) => vec![BlockInfo::new(3, 33, 11, 11)],
+
+        // R11x27
+        (10, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 15, 7, 4)],
+        (10, ECLevel::HIGH) => vec![BlockInfo::new(1, 15, 5, 5)],
+
+        // R11x43
+        (11, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 31, 19, 6)],
+        (11, ECLevel::HIGH) => vec![BlockInfo::new(1, 31, 11, 10)],
+
+        // R11x59
+        (12, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 47, 31, 8)],
+        (12, ECLevel::HIGH) => vec![BlockInfo::new(1, 23, 7, 8), BlockInfo::new(1, 24, 8, 8)],
+
+        // R11x77
+        (13, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 67, 43, 12)],
+        (13, ECLevel::HIGH) => vec![BlockInfo::new(1, 33, 11, 11), BlockInfo::new(1, 34, 12, 11)],
+
+        // R11x99
+        (14, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 44, 28, 8), BlockInfo::new(1, 45, 29, 8)],
+        (14, ECLevel::HIGH) => vec![BlockInfo::new(1, 44, 14, 15), BlockInfo::new(1, 45, 15, 15)],
+
+        // R11x139
+        (15, ECLevel::MEDIUM) => vec![BlockInfo::new(2, 66, 42, 12)],
+        (15, ECLevel::HIGH) => vec![BlockInfo::new(3, 44, 14, 15)],
+
+        // R13x27
+        (16, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 21, 12, 4)],
+        (16, ECLevel::HIGH) => vec![BlockInfo::new(1, 21, 7, 7)],
+
+        // R13x43
+        (17, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 41, 27, 7)],
+        (17, ECLevel::HIGH) => vec![BlockInfo::new(1, 41, 13, 14)],
+
+        // R13x59
+        (18, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 60, 38, 11)],
+        (18, ECLevel::HIGH) => vec![BlockInfo::new(2, 30, 10, 10)],
+
+        // R13x77
+        (19, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 42, 26, 8), BlockInfo::new(1, 43, 27, 8)],
+        (19, ECLevel::HIGH) => vec![BlockInfo::new(1, 42, 14, 14), BlockInfo::new(1, 43, 15, 14)],
+
+        // R13x99
+        (20, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 56, 36, 10), BlockInfo::new(1, 57, 37, 10)],
+        (20, ECLevel::HIGH) => vec![BlockInfo::new(1, 37, 11, 13), BlockInfo::new(2, 38, 12, 13)],
+
+        // R13x139
+        (21, ECLevel::MEDIUM) => vec![BlockInfo::new(2, 55, 35, 10), BlockInfo::new(1, 56, 36, 10)],
+        (21, ECLevel::HIGH) => vec![BlockInfo::new(2, 41, 15, 13), BlockInfo::new(2, 42, 16, 13)],
+
+        // R15x43
+        (22, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 51, 33, 9)],
+        (22, ECLevel::HIGH) => vec![BlockInfo::new(1, 25, 7, 9), BlockInfo::new(1, 26, 8, 9)],
+
+        // R15x59
+        (23, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 74, 48, 13)],
+        (23, ECLevel::HIGH) => vec![BlockInfo::new(2, 37, 13, 12)],
+
+        // R15x77
+        (24, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 51, 33, 9), BlockInfo::new(1, 52, 34, 9)],
+        (24, ECLevel::HIGH) => vec![BlockInfo::new(2, 34, 10, 12), BlockInfo::new(1, 35, 11, 12)],
+
+        // R15x99
+        (25, ECLevel::MEDIUM) => vec![BlockInfo::new(2, 68, 44, 12)],
+        (25, ECLevel::HIGH) => vec![BlockInfo::new(4, 34, 12, 11)],
+
+        // R15x139
+        (26, ECLevel::MEDIUM) => vec![BlockInfo::new(2, 66, 42, 12), BlockInfo::new(1, 67, 43, 12)],
+        (26, ECLevel::HIGH) => vec![BlockInfo::new(1, 39, 13, 13), BlockInfo::new(4, 40, 14, 13)],
+
+        // R17x43
+        (27, ECLevel::MEDIUM) => vec![BlockInfo::new(1, 61, 39, 11)],
+        (27, ECLevel::HIGH) => vec![BlockInfo::new(1, 30, 10, 10), BlockInfo::new(1, 31, 11, 10)],
+
+        // R17x59
+        (28, ECLevel::MEDIUM) => vec![BlockInfo::new(2, 44, 28, 8)],
+        (28, ECLevel::HIGH) => vec![BlockInfo::new(2, 44, 14, 15)],
+
+        // R17x77
+        (29, ECLevel::MEDIUM) => vec![BlockInfo::new(2, 61, 39, 11)],
+        (29, ECLevel::HIGH) => vec![BlockInfo::new(1, 40, 12, 14), BlockInfo::new(2, 41, 13, 14)],
+
+        // R17x99
+        (30, ECLevel::MEDIUM) => vec![BlockInfo::new(2, 53, 33, 10), BlockInfo::new(1, 54, 34, 10)],
+        (30, ECLevel::HIGH) => vec![BlockInfo::new(4, 40, 14, 13)],
+
+        // R17x139
+        (31, ECLevel::MEDIUM) => vec![BlockInfo::new(4, 58, 38, 10)],
+        (31, ECLevel::HIGH) => vec![BlockInfo::new(2, 38, 12, 13), BlockInfo::new(4, 39, 13, 13)],
+
+        (version, level) => {
+            return Err(QRError {
+                msg: format!(
+                    "Unknown combination of rMQR version {} and level {:?}",
+                    version, level
+                ),
+            })
+        }
+    };
+
+    let mut bi_unwound = vec![];
+
+    for bi in &block_info {
+        for _ in 0..bi.block_count {
+            bi_unwound.push(bi.clone());
+        }
+    }
+
+    Ok(bi_unwound)
+}
+
+pub fn blocks(data: &RMQRData, level: &ECLevel) -> Result<Vec<Vec<u8>>, QRError> {
+    let bi = block_info(data.version, level)?;
+    let mut codewords = Codewords::new(bi.clone());
+    let alignment = alignment_columns(data.width)?;
+
+    // The rightmost column is an edge, columns are read in pairs from there towards the left edge
+    let mut x = data.width - 2;
+    let mut upwards = true;
+
+    loop {
+        let y_range: Box<dyn Iterator<Item = u32>> = if upwards {
+            Box::new((0..data.height).rev())
+        } else {
+            Box::new(0..data.height)
+        };
+
+        for y in y_range {
+            if is_data(data, alignment, x, y) {
+                codewords.add_bit(mask(data, x, y));
+            }
+
+            if is_data(data, alignment, x - 1, y) {
+                codewords.add_bit(mask(data, x - 1, y));
+            }
+        }
+
+        if x == 1 {
+            break;
+        }
+
+        x -= 2;
+        upwards = !upwards;
+    }
+
+    let blocks = codewords.blocks();
+
+    for (i, (block, bi)) in blocks.iter().zip(&bi).enumerate() {
+        if bi.total_per as usize != block.len() {
+            return Err(QRError {
+                msg: format!(
+                    "Expected {} codewords in block {} but found {}",
+                    bi.total_per,
+                    i,
+                    block.len()
+                ),
+            });
+        }
+    }
+
+    Ok(blocks)
+}
+
+// rMQR only has a single mask
+fn mask(data: &RMQRData, x: u32, y: u32) -> u8 {
+    match (y / 2 + x / 3) % 2 {
+        0 => 1 - data[[x, y]],
+        _ => data[[x, y]],
+    }
+}
+
+fn is_data(data: &RMQRData, alignment: &[u32], x: u32, y: u32) -> bool {
+    let (width, height) = (data.width, data.height);
+
+    // edges, the timing patterns and corner patterns run along all of them
+    if x == 0 || y == 0 || x == width - 1 || y == height - 1 {
+        return false;
+    }
+
+    // finder pattern, separator and format information
+    if x < 8 && y < 8 {
+        return false;
+    }
+
+    if (8..11).contains(&x) && y < 6 || x == 11 && y < 4 {
+        return false;
+    }
+
+    // sub finder pattern and format information
+    if x >= width - 5 && y >= height - 5 {
+        return false;
+    }
+
+    if (width - 8..width - 5).contains(&x) && y >= height - 6
+        || (width - 5..width - 2).contains(&x) && y == height - 6
+    {
+        return false;
+    }
+
+    // corner patterns
+    if x == width - 2 && y == 1 || height > 9 && x == 1 && y == height - 2 {
+        return false;
+    }
+
+    // alignment patterns and the vertical timing patterns between them
+    for column in alignment {
+        if x + 1 >= *column && x <= column + 1 && (y < 3 || y >= height - 3) {
+            return false;
+        }
+
+        if x == *column {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn alignment_columns(width: u32) -> Result<&'static [u32], QRError> {
+    match width {
+        27 => Ok(&[]),
+        43 => Ok(&[21]),
+        59 => Ok(&[19, 39]),
+        77 => Ok(&[25, 51]),
+        99 => Ok(&[23, 49, 75]),
+        139 => Ok(&[27, 55, 83, 111]),
+        _ => Err(QRError {
+            msg: format!("Unknown rMQR width {}", width),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::util::qr::RMQR_SIZES;
+
+    #[test]
+    pub fn test_correct() {
+        // Same code as the QR version information, this is the one for version 7
+        assert_eq!(0b00_0111_1100_1001_0100, bch(7));
+
+        let code = bch(0b10_0110);
+
+        assert_eq!(Ok(0b10_0110), correct(code, code));
+        assert_eq!(
+            Ok(0b10_0110),
+            correct(code ^ 0b1_0000_0100_0000_0001, code ^ 0b1111)
+        );
+        assert!(correct(code ^ 0b1111, code ^ 0b1111_0000).is_err());
+    }
+
+    #[test]
+    pub fn test_data_modules() {
+        for (version, (width, height)) in RMQR_SIZES.iter().enumerate() {
+            let data = RMQRData::new(vec![255; (width * height) as usize], version as u32);
+            let alignment = alignment_columns(*width).unwrap();
+
+            let mut modules = 0;
+            for y in 0..*height {
+                for x in 0..*width {
+                    if is_data(&data, alignment, x, y) {
+                        modules += 1;
+                    }
+                }
+            }
+
+            for level in &[ECLevel::MEDIUM, ECLevel::HIGH] {
+                let total: u32 = block_info(version as u32, level)
+                    .unwrap()
+                    .iter()
+                    .map(|bi| u32::from(bi.total_per))
+                    .sum();
+
+                assert_eq!(total, modules / 8, "version {}", version);
+            }
+        }
+    }
+}
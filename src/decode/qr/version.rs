@@ -0,0 +1,57 @@
+// Generator polynomial of the (18,6) Golay code protecting the version information
+const VERSION_GENERATOR: u32 = 0b1_1111_0010_0101;
+
+// Maximum number of bit errors the version information can correct
+const MAX_ERRORS: u32 = 3;
+
+/// Version information of a QR Code of version 7 and up, the version in the top 6 bits followed by 12 error correction bits
+pub fn version_information(version: u32) -> u32 {
+    let mut remainder = version << 12;
+
+    for i in (12..18).rev() {
+        if (remainder >> i) & 1 == 1 {
+            remainder ^= VERSION_GENERATOR << (i - 12);
+        }
+    }
+
+    version << 12 | remainder
+}
+
+/// Correct 18 bits of version information read from a QR Code
+///
+/// Since there are only 34 valid versions, the closest one is looked up directly. The code has a minimum distance of 8,
+/// so up to 3 bit errors can be corrected.
+///
+/// Returns the version and the number of bit errors, or None if the version information is corrupted
+pub fn correct(bits: u32) -> Option<(u32, u32)> {
+    (7..=40)
+        .map(|version| (version, (version_information(version) ^ bits).count_ones()))
+        .min_by_key(|(_, errors)| *errors)
+        .filter(|(_, errors)| *errors <= MAX_ERRORS)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_version_information() {
+        assert_eq!(0x07C94, version_information(7));
+        assert_eq!(0x0A4D3, version_information(10));
+        assert_eq!(0x28C69, version_information(40));
+    }
+
+    #[test]
+    pub fn test_correct() {
+        assert_eq!(Some((7, 0)), correct(0x07C94));
+        assert_eq!(
+            Some((21, 2)),
+            correct(version_information(21) ^ 0b10_0000_0000_0100)
+        );
+        assert_eq!(
+            Some((40, 3)),
+            correct(version_information(40) ^ 0b1_0000_1000_0001)
+        );
+        assert_eq!(None, correct(version_information(12) ^ 0b1111));
+    }
+}
@@ -1,18 +1,41 @@
 use anyhow::Error;
 use image::{GenericImageView, GrayImage, Rgba};
 
-use crate::decode::{Decode, QRDecoder, QRDecoderWithInfo};
+use crate::decode::{Decode, QRDecoder, QRDecoderBytes, QRDecoderWithInfo};
 use crate::detect::{Detect, LineScan, Location};
 use crate::extract::{Extract, QRExtractor};
 use crate::prepare::{BlockedMean, Prepare};
 
-use crate::util::qr::{QRData, QRError, QRInfo, QRLocation};
+use crate::util::aztec::{AztecData, AztecError, AztecLocation};
+use crate::util::datamatrix::{DataMatrixData, DataMatrixError, DataMatrixLocation};
+use crate::util::ean::{EANData, EANError, EANLocation};
+use crate::util::linear::{LinearData, LinearError, LinearLocation, LinearSymbology};
+use crate::util::pdf417::{PDF417Data, PDF417Error, PDF417Location};
+use crate::util::qr::{
+    MicroQRLocation, QRData, QRError, QRInfo, QRLocation, RMQRData, RMQRLocation,
+};
 
 /// Struct to hold logic to do the entire decoding
 pub struct Decoder<IMG, PREPD, RESULT> {
     prepare: Box<dyn Prepare<IMG, PREPD>>,
     detect: Box<dyn Detect<PREPD>>,
     qr: ExtractDecode<PREPD, QRLocation, QRData, RESULT, QRError>,
+    micro_qr: Option<ExtractDecode<PREPD, MicroQRLocation, QRData, RESULT, QRError>>,
+    rmqr: Option<ExtractDecode<PREPD, RMQRLocation, RMQRData, RESULT, QRError>>,
+    datamatrix: Option<
+        DetectExtractDecode<PREPD, DataMatrixLocation, DataMatrixData, RESULT, DataMatrixError>,
+    >,
+    aztec: Option<DetectExtractDecode<PREPD, AztecLocation, AztecData, RESULT, AztecError>>,
+    ean: Option<DetectExtractDecode<PREPD, EANLocation, EANData, RESULT, EANError>>,
+    code128: Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    code39: Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    code93: Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    codabar: Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    itf: Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    databar: Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    databar_expanded:
+        Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    pdf417: Option<DetectExtractDecode<PREPD, PDF417Location, PDF417Data, RESULT, PDF417Error>>,
 }
 
 impl<IMG, PREPD, RESULT> Decoder<IMG, PREPD, RESULT> {
@@ -20,11 +43,47 @@ impl<IMG, PREPD, RESULT> Decoder<IMG, PREPD, RESULT> {
     ///
     /// Logic is run in the following order:
     /// * prepare
-    /// * detect
+    /// * detect, followed by the detect implementations of any optional symbologies
     /// * per detected code the associated extract and decode functions
+    ///
+    /// If neither Micro QR nor rMQR decoding is set, the `detect_qr` function is called instead of `detect`.
     pub fn decode(&self, source: &IMG) -> Vec<Result<RESULT, Error>> {
         let prepared = self.prepare.prepare(source);
-        let locations = self.detect.detect(&prepared);
+        let mut locations = match (&self.micro_qr, &self.rmqr) {
+            (None, None) => self.detect.detect_qr(&prepared),
+            _ => self.detect.detect(&prepared),
+        };
+
+        if let Some(datamatrix) = &self.datamatrix {
+            locations.extend(datamatrix.detect.detect(&prepared));
+        }
+
+        if let Some(aztec) = &self.aztec {
+            locations.extend(aztec.detect.detect(&prepared));
+        }
+
+        if let Some(ean) = &self.ean {
+            locations.extend(ean.detect.detect(&prepared));
+        }
+
+        for linear in [
+            &self.code128,
+            &self.code39,
+            &self.code93,
+            &self.codabar,
+            &self.itf,
+            &self.databar,
+            &self.databar_expanded,
+        ]
+        .iter()
+        .filter_map(|linear| linear.as_ref())
+        {
+            locations.extend(linear.detect.detect(&prepared));
+        }
+
+        if let Some(pdf417) = &self.pdf417 {
+            locations.extend(pdf417.detect.detect(&prepared));
+        }
 
         if locations.is_empty() {
             return vec![];
@@ -40,6 +99,79 @@ impl<IMG, PREPD, RESULT> Decoder<IMG, PREPD, RESULT> {
 
                     all_decoded.push(decoded.map_err(Error::from));
                 }
+                Location::MicroQR(microqrloc) => {
+                    // Micro QR is optional, skip if no extract and decode implementations are set
+                    if let Some(micro_qr) = &self.micro_qr {
+                        let extracted = micro_qr.extract.extract(&prepared, microqrloc);
+                        let decoded = micro_qr.decode.decode(extracted);
+
+                        all_decoded.push(decoded.map_err(Error::from));
+                    }
+                }
+                Location::RMQR(rmqrloc) => {
+                    // rMQR is optional, skip if no extract and decode implementations are set
+                    if let Some(rmqr) = &self.rmqr {
+                        let extracted = rmqr.extract.extract(&prepared, rmqrloc);
+                        let decoded = rmqr.decode.decode(extracted);
+
+                        all_decoded.push(decoded.map_err(Error::from));
+                    }
+                }
+                Location::DataMatrix(dmloc) => {
+                    // Data Matrix is optional, skip if no extract and decode implementations are set
+                    if let Some(datamatrix) = &self.datamatrix {
+                        let extracted = datamatrix.extract.extract(&prepared, dmloc);
+                        let decoded = datamatrix.decode.decode(extracted);
+
+                        all_decoded.push(decoded.map_err(Error::from));
+                    }
+                }
+                Location::Aztec(aztecloc) => {
+                    // Aztec is optional, skip if no extract and decode implementations are set
+                    if let Some(aztec) = &self.aztec {
+                        let extracted = aztec.extract.extract(&prepared, aztecloc);
+                        let decoded = aztec.decode.decode(extracted);
+
+                        all_decoded.push(decoded.map_err(Error::from));
+                    }
+                }
+                Location::EAN(eanloc) => {
+                    // EAN is optional, skip if no extract and decode implementations are set
+                    if let Some(ean) = &self.ean {
+                        let extracted = ean.extract.extract(&prepared, eanloc);
+                        let decoded = ean.decode.decode(extracted);
+
+                        all_decoded.push(decoded.map_err(Error::from));
+                    }
+                }
+                Location::Linear(linearloc) => {
+                    let linear = match linearloc.symbology {
+                        LinearSymbology::Code128 => &self.code128,
+                        LinearSymbology::Code39 => &self.code39,
+                        LinearSymbology::Code93 => &self.code93,
+                        LinearSymbology::Codabar => &self.codabar,
+                        LinearSymbology::ITF => &self.itf,
+                        LinearSymbology::DataBar => &self.databar,
+                        LinearSymbology::DataBarExpanded => &self.databar_expanded,
+                    };
+
+                    // Linear barcodes are optional, skip if no extract and decode implementations are set for the symbology
+                    if let Some(linear) = linear {
+                        let extracted = linear.extract.extract(&prepared, linearloc);
+                        let decoded = linear.decode.decode(extracted);
+
+                        all_decoded.push(decoded.map_err(Error::from));
+                    }
+                }
+                Location::PDF417(pdf417loc) => {
+                    // PDF417 is optional, skip if no extract and decode implementations are set
+                    if let Some(pdf417) = &self.pdf417 {
+                        let extracted = pdf417.extract.extract(&prepared, pdf417loc);
+                        let decoded = pdf417.decode.decode(extracted);
+
+                        all_decoded.push(decoded.map_err(Error::from));
+                    }
+                }
             }
         }
 
@@ -75,6 +207,20 @@ pub fn default_decoder_with_info<D>() -> Decoder<D, GrayImage, (String, QRInfo)>
     default_builder_with_info().build()
 }
 
+/// Create a default Decoder that returns the raw bytes contained in the QR Code
+///
+/// It will use the following components:
+///
+/// * prepare: BlockedMean
+/// * detect: LineScan
+/// * extract: QRExtractor
+/// * decode: QRDecoderBytes
+///
+/// This is meant to provide a good balance between speed and accuracy
+pub fn default_decoder_bytes<D>() -> Decoder<D, GrayImage, Vec<u8>> where D: GenericImageView<Pixel = Rgba<u8>> {
+    default_builder_bytes().build()
+}
+
 /// Builder struct to create a Decoder
 ///
 /// Required elements are:
@@ -83,10 +229,53 @@ pub fn default_decoder_with_info<D>() -> Decoder<D, GrayImage, (String, QRInfo)>
 /// * Detect
 /// * Extract
 /// * Decode
+///
+/// Optional elements are:
+///
+/// * Extract and Decode for Micro QR codes, see [`micro_qr`]
+/// * Extract and Decode for rMQR codes, see [`rmqr`]
+/// * Detect, Extract and Decode for Data Matrix codes, see [`datamatrix`]
+/// * Detect, Extract and Decode for Aztec codes, see [`aztec`]
+/// * Detect, Extract and Decode for EAN and UPC barcodes, see [`ean`]
+/// * Detect, Extract and Decode for Code 128 barcodes, see [`code128`]
+/// * Detect, Extract and Decode for Code 39, Code 93 and Codabar barcodes, see [`code39`], [`code93`] and [`codabar`]
+/// * Detect, Extract and Decode for ITF barcodes, see [`itf`]
+/// * Detect, Extract and Decode for DataBar and DataBar Expanded barcodes, see [`databar`] and [`databar_expanded`]
+/// * Detect, Extract and Decode for PDF417 Codes, see [`pdf417`]
+///
+/// [`micro_qr`]: #method.micro_qr
+/// [`rmqr`]: #method.rmqr
+/// [`datamatrix`]: #method.datamatrix
+/// [`aztec`]: #method.aztec
+/// [`ean`]: #method.ean
+/// [`code128`]: #method.code128
+/// [`code39`]: #method.code39
+/// [`code93`]: #method.code93
+/// [`codabar`]: #method.codabar
+/// [`itf`]: #method.itf
+/// [`databar`]: #method.databar
+/// [`databar_expanded`]: #method.databar_expanded
+/// [`pdf417`]: #method.pdf417
 pub struct DecoderBuilder<IMG, PREPD, RESULT> {
     prepare: Option<Box<dyn Prepare<IMG, PREPD>>>,
     detect: Option<Box<dyn Detect<PREPD>>>,
     qr: Option<ExtractDecode<PREPD, QRLocation, QRData, RESULT, QRError>>,
+    micro_qr: Option<ExtractDecode<PREPD, MicroQRLocation, QRData, RESULT, QRError>>,
+    rmqr: Option<ExtractDecode<PREPD, RMQRLocation, RMQRData, RESULT, QRError>>,
+    datamatrix: Option<
+        DetectExtractDecode<PREPD, DataMatrixLocation, DataMatrixData, RESULT, DataMatrixError>,
+    >,
+    aztec: Option<DetectExtractDecode<PREPD, AztecLocation, AztecData, RESULT, AztecError>>,
+    ean: Option<DetectExtractDecode<PREPD, EANLocation, EANData, RESULT, EANError>>,
+    code128: Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    code39: Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    code93: Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    codabar: Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    itf: Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    databar: Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    databar_expanded:
+        Option<DetectExtractDecode<PREPD, LinearLocation, LinearData, RESULT, LinearError>>,
+    pdf417: Option<DetectExtractDecode<PREPD, PDF417Location, PDF417Data, RESULT, PDF417Error>>,
 }
 
 impl<IMG, PREPD, RESULT> DecoderBuilder<IMG, PREPD, RESULT> {
@@ -96,6 +285,19 @@ impl<IMG, PREPD, RESULT> DecoderBuilder<IMG, PREPD, RESULT> {
             prepare: None,
             detect: None,
             qr: None,
+            micro_qr: None,
+            rmqr: None,
+            datamatrix: None,
+            aztec: None,
+            ean: None,
+            code128: None,
+            code39: None,
+            code93: None,
+            codabar: None,
+            itf: None,
+            databar: None,
+            databar_expanded: None,
+            pdf417: None,
         }
     }
 
@@ -127,6 +329,221 @@ impl<IMG, PREPD, RESULT> DecoderBuilder<IMG, PREPD, RESULT> {
         self
     }
 
+    /// Set the extact and decode implementations for this Decoder for Micro QR codes
+    ///
+    /// Micro QR codes are only decoded if these are set, otherwise any detected Micro QR codes are ignored
+    pub fn micro_qr(
+        &mut self,
+        extract: Box<dyn Extract<PREPD, MicroQRLocation, QRData, QRError>>,
+        decode: Box<dyn Decode<QRData, RESULT, QRError>>,
+    ) -> &mut DecoderBuilder<IMG, PREPD, RESULT> {
+        self.micro_qr = Some(ExtractDecode { extract, decode });
+        self
+    }
+
+    /// Set the extact and decode implementations for this Decoder for rMQR codes
+    ///
+    /// rMQR codes are only decoded if these are set, otherwise any detected rMQR codes are ignored
+    pub fn rmqr(
+        &mut self,
+        extract: Box<dyn Extract<PREPD, RMQRLocation, RMQRData, QRError>>,
+        decode: Box<dyn Decode<RMQRData, RESULT, QRError>>,
+    ) -> &mut DecoderBuilder<IMG, PREPD, RESULT> {
+        self.rmqr = Some(ExtractDecode { extract, decode });
+        self
+    }
+
+    /// Set the detect, extract and decode implementations for this Decoder for Data Matrix codes
+    ///
+    /// Data Matrix codes have a different finder pattern than QR codes, so they need their own detect implementation.
+    /// Data Matrix codes are only detected and decoded if these are set
+    pub fn datamatrix(
+        &mut self,
+        detect: Box<dyn Detect<PREPD>>,
+        extract: Box<dyn Extract<PREPD, DataMatrixLocation, DataMatrixData, DataMatrixError>>,
+        decode: Box<dyn Decode<DataMatrixData, RESULT, DataMatrixError>>,
+    ) -> &mut DecoderBuilder<IMG, PREPD, RESULT> {
+        self.datamatrix = Some(DetectExtractDecode {
+            detect,
+            extract,
+            decode,
+        });
+        self
+    }
+
+    /// Set the detect, extract and decode implementations for this Decoder for Aztec codes
+    ///
+    /// Aztec codes are found by their bullseye rather than the QR finder patterns, so they need their own detect implementation.
+    /// Aztec codes are only detected and decoded if these are set
+    pub fn aztec(
+        &mut self,
+        detect: Box<dyn Detect<PREPD>>,
+        extract: Box<dyn Extract<PREPD, AztecLocation, AztecData, AztecError>>,
+        decode: Box<dyn Decode<AztecData, RESULT, AztecError>>,
+    ) -> &mut DecoderBuilder<IMG, PREPD, RESULT> {
+        self.aztec = Some(DetectExtractDecode {
+            detect,
+            extract,
+            decode,
+        });
+        self
+    }
+
+    /// Set the detect, extract and decode implementations for this Decoder for EAN and UPC barcodes
+    ///
+    /// Linear barcodes are read along a scan line rather than as a grid of modules, so they need their own detect implementation.
+    /// EAN and UPC barcodes are only detected and decoded if these are set
+    pub fn ean(
+        &mut self,
+        detect: Box<dyn Detect<PREPD>>,
+        extract: Box<dyn Extract<PREPD, EANLocation, EANData, EANError>>,
+        decode: Box<dyn Decode<EANData, RESULT, EANError>>,
+    ) -> &mut DecoderBuilder<IMG, PREPD, RESULT> {
+        self.ean = Some(DetectExtractDecode {
+            detect,
+            extract,
+            decode,
+        });
+        self
+    }
+
+    /// Set the detect, extract and decode implementations for this Decoder for Code 128 barcodes
+    ///
+    /// Code 128 barcodes are only detected and decoded if these are set
+    pub fn code128(
+        &mut self,
+        detect: Box<dyn Detect<PREPD>>,
+        extract: Box<dyn Extract<PREPD, LinearLocation, LinearData, LinearError>>,
+        decode: Box<dyn Decode<LinearData, RESULT, LinearError>>,
+    ) -> &mut DecoderBuilder<IMG, PREPD, RESULT> {
+        self.code128 = Some(DetectExtractDecode {
+            detect,
+            extract,
+            decode,
+        });
+        self
+    }
+
+    /// Set the detect, extract and decode implementations for this Decoder for Code 39 barcodes
+    ///
+    /// Code 39 barcodes are only detected and decoded if these are set
+    pub fn code39(
+        &mut self,
+        detect: Box<dyn Detect<PREPD>>,
+        extract: Box<dyn Extract<PREPD, LinearLocation, LinearData, LinearError>>,
+        decode: Box<dyn Decode<LinearData, RESULT, LinearError>>,
+    ) -> &mut DecoderBuilder<IMG, PREPD, RESULT> {
+        self.code39 = Some(DetectExtractDecode {
+            detect,
+            extract,
+            decode,
+        });
+        self
+    }
+
+    /// Set the detect, extract and decode implementations for this Decoder for Code 93 barcodes
+    ///
+    /// Code 93 barcodes are only detected and decoded if these are set
+    pub fn code93(
+        &mut self,
+        detect: Box<dyn Detect<PREPD>>,
+        extract: Box<dyn Extract<PREPD, LinearLocation, LinearData, LinearError>>,
+        decode: Box<dyn Decode<LinearData, RESULT, LinearError>>,
+    ) -> &mut DecoderBuilder<IMG, PREPD, RESULT> {
+        self.code93 = Some(DetectExtractDecode {
+            detect,
+            extract,
+            decode,
+        });
+        self
+    }
+
+    /// Set the detect, extract and decode implementations for this Decoder for Codabar barcodes
+    ///
+    /// Codabar barcodes are only detected and decoded if these are set
+    pub fn codabar(
+        &mut self,
+        detect: Box<dyn Detect<PREPD>>,
+        extract: Box<dyn Extract<PREPD, LinearLocation, LinearData, LinearError>>,
+        decode: Box<dyn Decode<LinearData, RESULT, LinearError>>,
+    ) -> &mut DecoderBuilder<IMG, PREPD, RESULT> {
+        self.codabar = Some(DetectExtractDecode {
+            detect,
+            extract,
+            decode,
+        });
+        self
+    }
+
+    /// Set the detect, extract and decode implementations for this Decoder for ITF barcodes, including ITF-14
+    ///
+    /// ITF barcodes are only detected and decoded if these are set
+    pub fn itf(
+        &mut self,
+        detect: Box<dyn Detect<PREPD>>,
+        extract: Box<dyn Extract<PREPD, LinearLocation, LinearData, LinearError>>,
+        decode: Box<dyn Decode<LinearData, RESULT, LinearError>>,
+    ) -> &mut DecoderBuilder<IMG, PREPD, RESULT> {
+        self.itf = Some(DetectExtractDecode {
+            detect,
+            extract,
+            decode,
+        });
+        self
+    }
+
+    /// Set the detect, extract and decode implementations for this Decoder for GS1 DataBar Omnidirectional barcodes, including the stacked variants
+    ///
+    /// GS1 DataBar Omnidirectional barcodes are only detected and decoded if these are set
+    pub fn databar(
+        &mut self,
+        detect: Box<dyn Detect<PREPD>>,
+        extract: Box<dyn Extract<PREPD, LinearLocation, LinearData, LinearError>>,
+        decode: Box<dyn Decode<LinearData, RESULT, LinearError>>,
+    ) -> &mut DecoderBuilder<IMG, PREPD, RESULT> {
+        self.databar = Some(DetectExtractDecode {
+            detect,
+            extract,
+            decode,
+        });
+        self
+    }
+
+    /// Set the detect, extract and decode implementations for this Decoder for GS1 DataBar Expanded barcodes, including Expanded Stacked
+    ///
+    /// GS1 DataBar Expanded barcodes are only detected and decoded if these are set
+    pub fn databar_expanded(
+        &mut self,
+        detect: Box<dyn Detect<PREPD>>,
+        extract: Box<dyn Extract<PREPD, LinearLocation, LinearData, LinearError>>,
+        decode: Box<dyn Decode<LinearData, RESULT, LinearError>>,
+    ) -> &mut DecoderBuilder<IMG, PREPD, RESULT> {
+        self.databar_expanded = Some(DetectExtractDecode {
+            detect,
+            extract,
+            decode,
+        });
+        self
+    }
+
+    /// Set the detect, extract and decode implementations for this Decoder for PDF417 Codes
+    ///
+    /// PDF417 Codes are stacked rows of symbol characters between start and stop patterns, so they need their own detect implementation.
+    /// PDF417 Codes are only detected and decoded if these are set
+    pub fn pdf417(
+        &mut self,
+        detect: Box<dyn Detect<PREPD>>,
+        extract: Box<dyn Extract<PREPD, PDF417Location, PDF417Data, PDF417Error>>,
+        decode: Box<dyn Decode<PDF417Data, RESULT, PDF417Error>>,
+    ) -> &mut DecoderBuilder<IMG, PREPD, RESULT> {
+        self.pdf417 = Some(DetectExtractDecode {
+            detect,
+            extract,
+            decode,
+        });
+        self
+    }
+
     /// Build actual Decoder
     ///
     /// # Panics
@@ -145,6 +562,19 @@ impl<IMG, PREPD, RESULT> DecoderBuilder<IMG, PREPD, RESULT> {
             prepare: self.prepare.unwrap(),
             detect: self.detect.unwrap(),
             qr: self.qr.unwrap(),
+            micro_qr: self.micro_qr,
+            rmqr: self.rmqr,
+            datamatrix: self.datamatrix,
+            aztec: self.aztec,
+            ean: self.ean,
+            code128: self.code128,
+            code39: self.code39,
+            code93: self.code93,
+            codabar: self.codabar,
+            itf: self.itf,
+            databar: self.databar,
+            databar_expanded: self.databar_expanded,
+            pdf417: self.pdf417,
         }
     }
 }
@@ -192,7 +622,33 @@ pub fn default_builder_with_info<D>() -> DecoderBuilder<D, GrayImage, (String, Q
     db
 }
 
+/// Create a default DecoderBuilder that returns the raw bytes contained in the QR Code
+///
+/// It will use the following components:
+///
+/// * prepare: BlockedMean
+/// * locate: LineScan
+/// * extract: QRExtractor
+/// * decode: QRDecoderBytes
+///
+/// The builder can then be customised before creating the Decoder
+pub fn default_builder_bytes<D>() -> DecoderBuilder<D, GrayImage, Vec<u8>> where D: GenericImageView<Pixel = Rgba<u8>> {
+    let mut db = DecoderBuilder::new();
+
+    db.prepare(Box::new(BlockedMean::new(5, 7)));
+    db.detect(Box::new(LineScan::new()));
+    db.qr(Box::new(QRExtractor::new()), Box::new(QRDecoderBytes::new()));
+
+    db
+}
+
 struct ExtractDecode<PREPD, LOC, DATA, RESULT, ERROR> {
     extract: Box<dyn Extract<PREPD, LOC, DATA, ERROR>>,
     decode: Box<dyn Decode<DATA, RESULT, ERROR>>,
 }
+
+struct DetectExtractDecode<PREPD, LOC, DATA, RESULT, ERROR> {
+    detect: Box<dyn Detect<PREPD>>,
+    extract: Box<dyn Extract<PREPD, LOC, DATA, ERROR>>,
+    decode: Box<dyn Decode<DATA, RESULT, ERROR>>,
+}
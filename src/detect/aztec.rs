@@ -0,0 +1,230 @@
+use super::{runs, Detect, Location};
+
+use crate::decode::aztec::mode::mode_message;
+use crate::util::aztec::AztecLocation;
+use crate::util::{Delta, Point};
+
+use image::GrayImage;
+
+// Orientation marks around the mode message, starting at the top left corner going clockwise
+// Each corner has three modules, the one before the corner, the corner and the one after the corner
+// XXX .XX X.. ...
+const ORIENTATION: [u32; 4] = [0xEE0, 0x1DC, 0x83B, 0x707];
+
+/// Scan a prepared image for Aztec Codes
+///
+/// The general idea of this method is as follows:
+/// 1. Scan all rows for seven runs of alternating dark and light pixels of the same length, with a dark run in the middle and dark runs on either side. These are candidates for the bullseye
+/// 2. Confirm the candidate by scanning the column through its center for the same pattern
+/// 3. Determine whether it is a compact or full-range Aztec Code from the rings around the candidate
+/// 4. Read the ring around the bullseye, determine the orientation from the orientation marks and decode the mode message
+///
+/// Aztec Codes are only detected when they are aligned with the image axes, though they may be rotated by multiples of 90 degrees.
+pub struct AztecScan {}
+
+impl AztecScan {
+    /// Construct a new AztecScan
+    pub fn new() -> AztecScan {
+        AztecScan {}
+    }
+}
+
+impl Detect<GrayImage> for AztecScan {
+    fn detect(&self, prepared: &GrayImage) -> Vec<Location> {
+        let mut locations: Vec<AztecLocation> = vec![];
+
+        for y in 0..prepared.height() {
+            // Step 1
+            // Find the bullseye in the row
+            let row = runs((0..prepared.width()).map(|x| is_dark(prepared, x, y)));
+
+            for (cx, module_x) in bullseye(&row) {
+                // Step 2
+                // Confirm in the column
+                let column = runs((0..prepared.height()).map(|y| is_dark(prepared, cx, y)));
+
+                let (cy, module_y) = match bullseye(&column)
+                    .into_iter()
+                    .find(|(cy, module_y)| close(*cy, y, *module_y))
+                {
+                    Some(found) => found,
+                    None => continue,
+                };
+
+                let center = Point {
+                    x: f64::from(cx),
+                    y: f64::from(cy),
+                };
+
+                let duplicate = locations.iter().any(|other| {
+                    let d = other.center - center;
+                    (d.dx * d.dx + d.dy * d.dy).sqrt() < 2.0 * other.module_size
+                });
+
+                if duplicate {
+                    continue;
+                }
+
+                // Steps 3 and 4
+                // Check the rings and the mode message
+                if let Some(loc) = find_aztec(prepared, center, module_x, module_y) {
+                    locations.push(loc);
+                }
+            }
+        }
+
+        locations.into_iter().map(Location::Aztec).collect()
+    }
+}
+
+// Centers and module sizes of seven runs of the same length with a dark run in the middle
+//
+// The outermost dark ring of the bullseye of a compact Aztec Code can merge with the mode message next to it,
+// so that ring only needs to be at least half a module wide
+fn bullseye(runs: &[(bool, u32, u32)]) -> Vec<(u32, f64)> {
+    let mut found = vec![];
+
+    if runs.len() < 9 {
+        return found;
+    }
+
+    for i in 4..runs.len() - 4 {
+        if !runs[i].0 {
+            continue;
+        }
+
+        let pattern = &runs[i - 3..=i + 3];
+        let module = f64::from(pattern.iter().map(|r| r.2).sum::<u32>()) / 7.0;
+
+        let outer = f64::from(runs[i - 4].2.min(runs[i + 4].2));
+
+        if outer > module / 2.0
+            && pattern
+                .iter()
+                .all(|r| (f64::from(r.2) - module).abs() < module / 2.0)
+        {
+            found.push((runs[i].1 + (runs[i].2 - 1) / 2, module));
+        }
+    }
+
+    found
+}
+
+fn find_aztec(
+    prepared: &GrayImage,
+    center: Point,
+    module_x: f64,
+    module_y: f64,
+) -> Option<AztecLocation> {
+    let module = |x: i32, y: i32| {
+        center
+            + Delta {
+                dx: f64::from(x) * module_x,
+                dy: f64::from(y) * module_y,
+            }
+    };
+
+    let dark = |p: Point| -> Option<bool> {
+        if p.x < 0.0 || p.y < 0.0 {
+            return None;
+        }
+
+        let (x, y) = (p.x.round() as u32, p.y.round() as u32);
+
+        if x >= prepared.width() || y >= prepared.height() {
+            return None;
+        }
+
+        Some(is_dark(prepared, x, y))
+    };
+
+    // Step 3
+    // Full-range Aztec Codes have one more light and dark ring, compact Aztec Codes have the mode message there
+    let ring = |d: i32| -> Option<Vec<bool>> {
+        (-d..d)
+            .flat_map(|i| vec![(i, -d), (d, i), (-i, d), (-d, -i)])
+            .map(|(x, y)| dark(module(x, y)))
+            .collect()
+    };
+
+    let compact = !(ring(5)?.iter().all(|d| !d) && ring(6)?.iter().all(|d| *d));
+    let d = if compact { 5 } else { 7 };
+
+    // Step 4
+    // Read the sides of the ring with the mode message, clockwise from the top left
+    let corners = [module(-d, -d), module(d, -d), module(d, d), module(-d, d)];
+    let length = 2 * d as u32;
+
+    let mut sides = [0; 4];
+
+    for (i, side) in sides.iter_mut().enumerate() {
+        let from = corners[i];
+        let step = (corners[(i + 1) % 4] - from) / f64::from(length);
+
+        for j in 0..length {
+            *side = (*side << 1) | u32::from(dark(from + f64::from(j) * step)?);
+        }
+    }
+
+    // The orientation marks are the first two and last bit of each side
+    let mut corner_bits = sides.iter().fold(0, |acc, side| {
+        (acc << 3) | ((side >> (length - 2)) << 1) | (side & 1)
+    });
+    corner_bits = ((corner_bits & 1) << 11) | (corner_bits >> 1);
+
+    // The orientation patterns differ in 8 bits, allow up to 2 errors
+    let shift = ORIENTATION
+        .iter()
+        .position(|expected| (corner_bits ^ expected).count_ones() <= 2)?;
+
+    let mut parameters: u64 = 0;
+
+    for i in 0..4 {
+        let side = u64::from(sides[(shift + i) % 4]);
+
+        if compact {
+            // ..XXXXXXX.
+            parameters = (parameters << 7) | ((side >> 1) & 0b111_1111);
+        } else {
+            // ..XXXXX.XXXXX. with the center module on the reference grid
+            parameters =
+                (parameters << 10) | ((side >> 2) & (0b1_1111 << 5)) | ((side >> 1) & 0b1_1111);
+        }
+    }
+
+    let (layers, data_codewords) = match mode_message(parameters, compact) {
+        Ok(mode) => mode,
+        Err(e) => {
+            debug!("AZTEC MODE MESSAGE {}", e);
+            return None;
+        }
+    };
+
+    if compact && layers > 4 {
+        return None;
+    }
+
+    debug!(
+        "AZTEC AT {:?} COMPACT {} LAYERS {} DATA {}",
+        center, compact, layers, data_codewords
+    );
+
+    Some(AztecLocation {
+        center,
+        top_left: corners[shift],
+        top_right: corners[(shift + 1) % 4],
+        bottom_left: corners[(shift + 3) % 4],
+        module_size: (module_x + module_y) / 2.0,
+        compact,
+        layers,
+        data_codewords,
+    })
+}
+
+fn is_dark(prepared: &GrayImage, x: u32, y: u32) -> bool {
+    prepared.get_pixel(x, y)[0] == 0
+}
+
+fn close(a: u32, b: u32, module: f64) -> bool {
+    (f64::from(a) - f64::from(b)).abs() <= module
+}
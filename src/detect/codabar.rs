@@ -0,0 +1,40 @@
+use super::linear::{narrow_wide_chars, scan, Row};
+use super::{Detect, Location};
+
+use crate::util::linear::LinearSymbology;
+
+use image::GrayImage;
+
+// Minimum width of the quiet zone on either side of the barcode, in narrow runs
+const QUIET_ZONE: f64 = 10.0;
+
+// Start and stop characters `A` to `D`, with 1 for the wide runs
+const START_STOP: [u32; 4] = [0b0011010, 0b0101001, 0b0001011, 0b0001110];
+
+/// Scan a prepared image for Codabar barcodes
+///
+/// The general idea of this method is as follows:
+/// 1. Scan all rows for runs of alternating dark and light pixels
+/// 2. Look for a quiet zone followed by one of the start characters, which are reversed if the barcode is upside down
+/// 3. Follow the characters of 7 runs with 2 or 3 wide runs each until one of the stop characters is found
+/// 4. Combine the matches on nearby rows into a single barcode, using the row in the middle as the scan line
+///
+/// Barcodes are only detected when their bars are vertical, though they may be upside down.
+pub struct CodabarScan {}
+
+impl CodabarScan {
+    /// Construct a new CodabarScan
+    pub fn new() -> CodabarScan {
+        CodabarScan {}
+    }
+}
+
+impl Detect<GrayImage> for CodabarScan {
+    fn detect(&self, prepared: &GrayImage) -> Vec<Location> {
+        scan(prepared, LinearSymbology::Codabar, QUIET_ZONE, find)
+    }
+}
+
+fn find(row: &Row, first: usize) -> Option<(usize, f64)> {
+    narrow_wide_chars(row, first, &START_STOP, 7, &[2, 3])
+}
@@ -0,0 +1,40 @@
+use super::linear::{fixed_width, scan, Row};
+use super::{Detect, Location};
+
+use crate::util::linear::LinearSymbology;
+
+use image::GrayImage;
+
+// Minimum width of the quiet zone on either side of the barcode, in modules
+const QUIET_ZONE: f64 = 10.0;
+
+const START: [[u32; 6]; 3] = [[2, 1, 1, 4, 1, 2], [2, 1, 1, 2, 1, 4], [2, 1, 1, 2, 3, 2]];
+const STOP: [u32; 7] = [2, 3, 3, 1, 1, 1, 2];
+
+/// Scan a prepared image for Code 128 barcodes
+///
+/// The general idea of this method is as follows:
+/// 1. Scan all rows for runs of alternating dark and light pixels
+/// 2. Look for a quiet zone followed by one of the start patterns, or the stop pattern if the barcode is upside down
+/// 3. Follow the symbols of 11 modules each until the stop pattern is found, or one of the start patterns if the barcode is upside down
+/// 4. Combine the matches on nearby rows into a single barcode, using the row in the middle as the scan line
+///
+/// Barcodes are only detected when their bars are vertical, though they may be upside down.
+pub struct Code128Scan {}
+
+impl Code128Scan {
+    /// Construct a new Code128Scan
+    pub fn new() -> Code128Scan {
+        Code128Scan {}
+    }
+}
+
+impl Detect<GrayImage> for Code128Scan {
+    fn detect(&self, prepared: &GrayImage) -> Vec<Location> {
+        scan(prepared, LinearSymbology::Code128, QUIET_ZONE, find)
+    }
+}
+
+fn find(row: &Row, first: usize) -> Option<(usize, f64)> {
+    fixed_width(row, first, &[&START[0], &START[1], &START[2]], &STOP, 6, 11)
+}
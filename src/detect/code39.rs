@@ -0,0 +1,40 @@
+use super::linear::{narrow_wide_chars, scan, Row};
+use super::{Detect, Location};
+
+use crate::util::linear::LinearSymbology;
+
+use image::GrayImage;
+
+// Minimum width of the quiet zone on either side of the barcode, in narrow runs
+const QUIET_ZONE: f64 = 10.0;
+
+// Start and stop character `*`, with 1 for the wide runs
+const START_STOP: u32 = 0b010_010_100;
+
+/// Scan a prepared image for Code 39 barcodes
+///
+/// The general idea of this method is as follows:
+/// 1. Scan all rows for runs of alternating dark and light pixels
+/// 2. Look for a quiet zone followed by the start character, which is reversed if the barcode is upside down
+/// 3. Follow the characters of 9 runs with 3 wide runs each until the stop character is found
+/// 4. Combine the matches on nearby rows into a single barcode, using the row in the middle as the scan line
+///
+/// Barcodes are only detected when their bars are vertical, though they may be upside down.
+pub struct Code39Scan {}
+
+impl Code39Scan {
+    /// Construct a new Code39Scan
+    pub fn new() -> Code39Scan {
+        Code39Scan {}
+    }
+}
+
+impl Detect<GrayImage> for Code39Scan {
+    fn detect(&self, prepared: &GrayImage) -> Vec<Location> {
+        scan(prepared, LinearSymbology::Code39, QUIET_ZONE, find)
+    }
+}
+
+fn find(row: &Row, first: usize) -> Option<(usize, f64)> {
+    narrow_wide_chars(row, first, &[START_STOP], 9, &[3])
+}
@@ -0,0 +1,42 @@
+use super::linear::{fixed_width, scan, Row};
+use super::{Detect, Location};
+
+use crate::util::linear::LinearSymbology;
+
+use image::GrayImage;
+
+// Minimum width of the quiet zone on either side of the barcode, in modules
+const QUIET_ZONE: f64 = 10.0;
+
+const START: [u32; 6] = [1, 1, 1, 1, 4, 1];
+
+// The stop character is followed by a termination bar
+const STOP: [u32; 7] = [1, 1, 1, 1, 4, 1, 1];
+
+/// Scan a prepared image for Code 93 barcodes
+///
+/// The general idea of this method is as follows:
+/// 1. Scan all rows for runs of alternating dark and light pixels
+/// 2. Look for a quiet zone followed by the start character, or the termination bar and stop character if the barcode is upside down
+/// 3. Follow the characters of 9 modules each until the stop character and termination bar are found, or the start character if the barcode is upside down
+/// 4. Combine the matches on nearby rows into a single barcode, using the row in the middle as the scan line
+///
+/// Barcodes are only detected when their bars are vertical, though they may be upside down.
+pub struct Code93Scan {}
+
+impl Code93Scan {
+    /// Construct a new Code93Scan
+    pub fn new() -> Code93Scan {
+        Code93Scan {}
+    }
+}
+
+impl Detect<GrayImage> for Code93Scan {
+    fn detect(&self, prepared: &GrayImage) -> Vec<Location> {
+        scan(prepared, LinearSymbology::Code93, QUIET_ZONE, find)
+    }
+}
+
+fn find(row: &Row, first: usize) -> Option<(usize, f64)> {
+    fixed_width(row, first, &[&START], &STOP, 6, 9)
+}
@@ -0,0 +1,257 @@
+use super::linear::{close, Row, MIN_ROWS};
+use super::{Detect, Location};
+
+use crate::decode::databar::character::{finder, EXPANDED_FINDERS, OMNI_FINDERS};
+
+use crate::util::linear::{LinearLocation, LinearSymbology};
+use crate::util::Point;
+
+use image::GrayImage;
+
+use std::cmp::Reverse;
+
+// Number of runs from one finder pattern to the next, the finder pattern itself and two characters
+const PAIR_RUNS: usize = 21;
+
+// Maximum distance between the rows of a stacked barcode, including the separator pattern, in modules
+const MAX_ROW_GAP: f64 = 5.0;
+
+/// Scan a prepared image for GS1 DataBar Omnidirectional barcodes, including GS1 DataBar Stacked and Stacked Omnidirectional
+///
+/// The general idea of this method is as follows:
+/// 1. Scan all rows for runs of alternating dark and light pixels
+/// 2. Look for finder patterns with a character of 15 or 16 modules next to them
+/// 3. Combine the matches on nearby rows into rows of the barcode, using the row in the middle as the scan line
+/// 4. Combine rows of the barcode right below each other into a stacked barcode, until both finder patterns are found
+///
+/// GS1 DataBar needs no quiet zone, but barcodes are only detected when their bars are vertical, though they may be upside down.
+pub struct DataBarScan {}
+
+impl DataBarScan {
+    /// Construct a new DataBarScan
+    pub fn new() -> DataBarScan {
+        DataBarScan {}
+    }
+}
+
+impl Detect<GrayImage> for DataBarScan {
+    fn detect(&self, prepared: &GrayImage) -> Vec<Location> {
+        scan(prepared, LinearSymbology::DataBar, &OMNI_FINDERS, (15, 16))
+    }
+}
+
+/// Scan a prepared image for GS1 DataBar Expanded barcodes, including GS1 DataBar Expanded Stacked
+///
+/// Works the same way as [`DataBarScan`], with the finder patterns of GS1 DataBar Expanded and characters of 17 modules.
+/// Rows are combined into a stacked barcode as long as they are right below each other.
+///
+/// [`DataBarScan`]: struct.DataBarScan.html
+pub struct DataBarExpandedScan {}
+
+impl DataBarExpandedScan {
+    /// Construct a new DataBarExpandedScan
+    pub fn new() -> DataBarExpandedScan {
+        DataBarExpandedScan {}
+    }
+}
+
+impl Detect<GrayImage> for DataBarExpandedScan {
+    fn detect(&self, prepared: &GrayImage) -> Vec<Location> {
+        scan(
+            prepared,
+            LinearSymbology::DataBarExpanded,
+            &EXPANDED_FINDERS,
+            (17, 17),
+        )
+    }
+}
+
+#[derive(Debug)]
+struct Match {
+    y: u32,
+    start: u32,
+    end: u32,
+    module_size: f64,
+
+    // Finder patterns and whether they are reversed
+    finders: Vec<(usize, bool)>,
+}
+
+fn scan(
+    prepared: &GrayImage,
+    symbology: LinearSymbology,
+    finders: &[[u32; 5]],
+    char_modules: (u32, u32),
+) -> Vec<Location> {
+    let mut groups: Vec<Vec<Match>> = vec![];
+
+    for y in 0..prepared.height() {
+        let row = super::runs((0..prepared.width()).map(|x| prepared.get_pixel(x, y)[0] == 0));
+
+        for found in find(&row, y, finders, char_modules) {
+            debug!("{:?} MATCH {:?}", symbology, found);
+
+            // Rows of a stacked barcode are kept apart by their finder patterns or the separator between them
+            let group = groups.iter_mut().find(|group| {
+                let previous = group.last().unwrap();
+
+                previous.finders == found.finders
+                    && close(previous.start, found.start, 2.0 * found.module_size)
+                    && close(previous.end, found.end, 2.0 * found.module_size)
+                    && close(previous.y, found.y, 2.0)
+            });
+
+            match group {
+                Some(group) => group.push(found),
+                None => groups.push(vec![found]),
+            }
+        }
+    }
+
+    let mut stacks: Vec<Vec<Vec<Match>>> = vec![];
+
+    for group in groups.into_iter().filter(|group| group.len() >= MIN_ROWS) {
+        let stack = stacks.iter_mut().find(|stack| {
+            let previous = stack.last().unwrap();
+            let (last, first) = (previous.last().unwrap(), &group[0]);
+
+            close(last.y, first.y, MAX_ROW_GAP * first.module_size)
+                && first.start <= last.end
+                && last.start <= first.end
+        });
+
+        match stack {
+            Some(stack) => stack.push(group),
+            None => stacks.push(vec![group]),
+        }
+    }
+
+    stacks
+        .into_iter()
+        .filter(|stack| {
+            // An Omnidirectional barcode has exactly two finder patterns, possibly on different rows
+            symbology != LinearSymbology::DataBar
+                || stack
+                    .iter()
+                    .map(|group| group[0].finders.len())
+                    .sum::<usize>()
+                    == 2
+        })
+        .map(|stack| {
+            let mut lines = stack.iter().map(|group| {
+                let found = &group[group.len() / 2];
+
+                (
+                    Point {
+                        x: f64::from(found.start),
+                        y: f64::from(found.y),
+                    },
+                    Point {
+                        x: f64::from(found.end),
+                        y: f64::from(found.y),
+                    },
+                )
+            });
+
+            let (start, end) = lines.next().unwrap();
+
+            Location::Linear(LinearLocation {
+                symbology,
+                start,
+                end,
+                module_size: stack[0][0].module_size,
+                stacked: lines.collect(),
+            })
+        })
+        .collect()
+}
+
+// Find the rows of a barcode as chains of finder patterns with a character next to them, one pair apart
+fn find(row: &Row, y: u32, finders: &[[u32; 5]], char_modules: (u32, u32)) -> Vec<Match> {
+    let widths: Vec<u32> = row.iter().map(|r| r.2).collect();
+
+    let is_char = |first: Option<usize>, module: f64| match first.and_then(|f| widths.get(f..f + 8))
+    {
+        Some(runs) => {
+            let modules = f64::from(runs.iter().sum::<u32>()) / module;
+            modules > f64::from(char_modules.0) - 1.0 && modules < f64::from(char_modules.1) + 1.0
+        }
+        None => false,
+    };
+
+    let candidates: Vec<(usize, (usize, bool), f64)> = (0..widths.len().saturating_sub(4))
+        .filter_map(|i| {
+            let found = finder(&widths[i..i + 5], finders)?;
+            let module = f64::from(widths[i..i + 5].iter().sum::<u32>()) / 15.0;
+
+            match is_char(i.checked_sub(8), module) || is_char(Some(i + 5), module) {
+                true => Some((i, found, module)),
+                false => None,
+            }
+        })
+        .collect();
+
+    let mut matches = vec![];
+    let mut used = vec![false; candidates.len()];
+
+    for c in 0..candidates.len() {
+        if used[c] {
+            continue;
+        }
+
+        let mut chain = vec![c];
+        while let Some(next) = candidates
+            .iter()
+            .position(|n| n.0 == candidates[*chain.last().unwrap()].0 + PAIR_RUNS)
+        {
+            chain.push(next);
+        }
+
+        for i in chain.iter() {
+            used[*i] = true;
+        }
+
+        let (first, _, module) = candidates[chain[0]];
+        let (last, _, _) = candidates[*chain.last().unwrap()];
+
+        // The guard is next to the outer characters, or next to the finder pattern if a character is missing
+        let start = match is_char(first.checked_sub(8), module) {
+            true => first
+                .checked_sub(10)
+                .into_iter()
+                .chain(first.checked_sub(9)),
+            false => first.checked_sub(2).into_iter().chain(first.checked_sub(1)),
+        }
+        .find(|i| row[*i].0);
+
+        let after = match is_char(Some(last + 5), module) {
+            true => last + 13,
+            false => last + 5,
+        };
+        let end = (after..after + 2)
+            .rev()
+            .find(|i| matches!(row.get(*i), Some(run) if run.0));
+
+        if let (Some(start), Some(end)) = (start, end) {
+            matches.push(Match {
+                y,
+                start: row[start].1,
+                end: row[end].1 + row[end].2 - 1,
+                module_size: module,
+                finders: chain.iter().map(|c| candidates[*c].1).collect(),
+            });
+        }
+    }
+
+    // Finder patterns may also be found inside the characters, prefer the longest chains
+    matches.sort_by_key(|found| Reverse(found.finders.len()));
+
+    let mut kept: Vec<Match> = vec![];
+    for found in matches {
+        if kept.iter().all(|k| found.end < k.start || k.end < found.start) {
+            kept.push(found);
+        }
+    }
+
+    kept
+}
@@ -0,0 +1,334 @@
+use super::{Detect, Location};
+
+use crate::util::datamatrix::DataMatrixLocation;
+use crate::util::{Delta, Point};
+
+use image::GrayImage;
+
+// Shortest side of a Data Matrix Code is 8 modules, assume at least 2 pixels per module
+const MIN_EDGE: u32 = 16;
+
+// Number of rows and columns of all Data Matrix sizes
+const SIZES: [(u32, u32); 30] = [
+    (10, 10),
+    (12, 12),
+    (14, 14),
+    (16, 16),
+    (18, 18),
+    (20, 20),
+    (22, 22),
+    (24, 24),
+    (26, 26),
+    (32, 32),
+    (36, 36),
+    (40, 40),
+    (44, 44),
+    (48, 48),
+    (52, 52),
+    (64, 64),
+    (72, 72),
+    (80, 80),
+    (88, 88),
+    (96, 96),
+    (104, 104),
+    (120, 120),
+    (132, 132),
+    (144, 144),
+    (8, 18),
+    (8, 32),
+    (12, 26),
+    (12, 36),
+    (16, 36),
+    (16, 48),
+];
+
+/// Scan a prepared image for Data Matrix Codes
+///
+/// The general idea of this method is as follows:
+/// 1. Scan all rows and columns for long dark lines with a light quiet zone along one side, these are candidates for the outer edges of the solid finder pattern
+/// 2. Combine a horizontal and a vertical line that meet in a corner into the L-shaped finder pattern
+/// 3. Count the modules of the dashed clock tracks along the two opposite edges to determine the size of the Data Matrix Code, then verify every module of both clock tracks
+///
+/// Data Matrix Codes are only detected when they are aligned with the image axes, though they may be rotated by multiples of 90 degrees.
+pub struct DataMatrixScan {}
+
+impl DataMatrixScan {
+    /// Construct a new DataMatrixScan
+    pub fn new() -> DataMatrixScan {
+        DataMatrixScan {}
+    }
+}
+
+impl Detect<GrayImage> for DataMatrixScan {
+    fn detect(&self, prepared: &GrayImage) -> Vec<Location> {
+        // Step 1
+        // Find the edges in both directions
+        let horizontal = edges(prepared, false);
+        let vertical = edges(prepared, true);
+
+        debug!(
+            "DATA MATRIX EDGES {} HORIZONTAL {} VERTICAL",
+            horizontal.len(),
+            vertical.len()
+        );
+
+        let mut locations: Vec<DataMatrixLocation> = vec![];
+
+        // Step 2
+        // Find the pairs of edges that form an L
+        for h in &horizontal {
+            for v in &vertical {
+                let finder = match finder(h, v) {
+                    Some(finder) => finder,
+                    None => continue,
+                };
+
+                // Step 3
+                // Check the clock tracks
+                if let Some(loc) = find_datamatrix(prepared, &finder) {
+                    let duplicate = locations.iter().any(|other| {
+                        let d = other.bottom_left - loc.bottom_left;
+                        (d.dx * d.dx + d.dy * d.dy).sqrt() < loc.module_size
+                    });
+
+                    if !duplicate {
+                        locations.push(loc);
+                    }
+                }
+            }
+        }
+
+        locations.into_iter().map(Location::DataMatrix).collect()
+    }
+}
+
+// A straight dark line with light pixels along one side
+// For horizontal edges `fixed` is the row and `from` and `to` are columns, vice versa for vertical edges
+#[derive(Debug)]
+struct Edge {
+    fixed: u32,
+    from: u32,
+    to: u32,
+    outside: i32,
+}
+
+// An L-shaped finder, `corner` is the outer pixel where both legs meet
+#[derive(Debug)]
+struct Finder {
+    corner: Point,
+    bottom: Delta,
+    bottom_length: f64,
+    left: Delta,
+    left_length: f64,
+}
+
+fn edges(prepared: &GrayImage, vertical: bool) -> Vec<Edge> {
+    let (lines, length) = if vertical {
+        (prepared.width(), prepared.height())
+    } else {
+        (prepared.height(), prepared.width())
+    };
+
+    let dark = |line: u32, pos: u32| {
+        if vertical {
+            prepared.get_pixel(line, pos)[0] == 0
+        } else {
+            prepared.get_pixel(pos, line)[0] == 0
+        }
+    };
+
+    let mut edges = vec![];
+
+    for line in 0..lines {
+        let mut pos = 0;
+
+        while pos < length {
+            if !dark(line, pos) {
+                pos += 1;
+                continue;
+            }
+
+            let from = pos;
+            while pos < length && dark(line, pos) {
+                pos += 1;
+            }
+            let to = pos - 1;
+
+            if to - from + 1 < MIN_EDGE {
+                continue;
+            }
+
+            for outside in &[-1, 1] {
+                let other = i64::from(line) + i64::from(*outside);
+
+                // Anything outside of the image counts as light
+                let light = if other < 0 || other >= i64::from(lines) {
+                    to - from + 1
+                } else {
+                    (from..=to).filter(|p| !dark(other as u32, *p)).count() as u32
+                };
+
+                if light * 10 >= (to - from + 1) * 9 {
+                    edges.push(Edge {
+                        fixed: line,
+                        from,
+                        to,
+                        outside: *outside,
+                    });
+                }
+            }
+        }
+    }
+
+    edges
+}
+
+fn finder(h: &Edge, v: &Edge) -> Option<Finder> {
+    // The vertical edge needs to run along one of the ends of the horizontal edge
+    let h_end = if close(v.fixed, h.from) && v.outside == -1 {
+        0
+    } else if close(v.fixed, h.to) && v.outside == 1 {
+        1
+    } else {
+        return None;
+    };
+
+    // And vice versa
+    let v_end = if close(h.fixed, v.from) && h.outside == -1 {
+        0
+    } else if close(h.fixed, v.to) && h.outside == 1 {
+        1
+    } else {
+        return None;
+    };
+
+    let corner = Point {
+        x: f64::from(if h_end == 0 { h.from } else { h.to }),
+        y: f64::from(if v_end == 0 { v.from } else { v.to }),
+    };
+
+    let h_dir = Delta {
+        dx: if h_end == 0 { 1.0 } else { -1.0 },
+        dy: 0.0,
+    };
+    let v_dir = Delta {
+        dx: 0.0,
+        dy: if v_end == 0 { 1.0 } else { -1.0 },
+    };
+
+    let h_length = f64::from(h.to - h.from + 1);
+    let v_length = f64::from(v.to - v.from + 1);
+
+    // Going from the bottom leg to the left leg always turns the same way, unless the Data Matrix is mirrored
+    if h_dir.dx * v_dir.dy < 0.0 {
+        Some(Finder {
+            corner,
+            bottom: h_dir,
+            bottom_length: h_length,
+            left: v_dir,
+            left_length: v_length,
+        })
+    } else {
+        Some(Finder {
+            corner,
+            bottom: v_dir,
+            bottom_length: v_length,
+            left: h_dir,
+            left_length: h_length,
+        })
+    }
+}
+
+fn find_datamatrix(prepared: &GrayImage, finder: &Finder) -> Option<DataMatrixLocation> {
+    // The clock tracks start with a dark module at the finder and end with a light module,
+    // so the number of modules is twice the number of dark runs.
+    // First count close to the outer edge, then again through the centers of the modules
+    let mut inset = (1.0, 1.0);
+    let mut size = (0, 0);
+
+    for _ in 0..2 {
+        let top = finder.corner + (finder.left_length - 1.0 - inset.1) * finder.left;
+        let right = finder.corner + (finder.bottom_length - 1.0 - inset.0) * finder.bottom;
+
+        let columns = 2 * dark_runs(prepared, top, finder.bottom, finder.bottom_length)?;
+        let rows = 2 * dark_runs(prepared, right, finder.left, finder.left_length)?;
+
+        size = (rows, columns);
+        inset = (
+            finder.bottom_length / f64::from(columns) / 2.0,
+            finder.left_length / f64::from(rows) / 2.0,
+        );
+    }
+
+    trace!("DATA MATRIX SIZE {:?} AT {:?}", size, finder);
+
+    if !SIZES.contains(&size) {
+        return None;
+    }
+
+    let (rows, columns) = size;
+
+    let module_x = finder.bottom_length / f64::from(columns);
+    let module_y = finder.left_length / f64::from(rows);
+
+    let first_x = module_x / 2.0 - 0.5;
+    let first_y = module_y / 2.0 - 0.5;
+    let last_x = finder.bottom_length - module_x / 2.0 - 0.5;
+    let last_y = finder.left_length - module_y / 2.0 - 0.5;
+
+    let loc = DataMatrixLocation {
+        top_left: finder.corner + first_x * finder.bottom + last_y * finder.left,
+        bottom_left: finder.corner + first_x * finder.bottom + first_y * finder.left,
+        bottom_right: finder.corner + last_x * finder.bottom + first_y * finder.left,
+        module_size: (module_x + module_y) / 2.0,
+        rows,
+        columns,
+    };
+
+    if !verify_clock(prepared, &loc) {
+        return None;
+    }
+
+    Some(loc)
+}
+
+// Check every module of both clock tracks, these alternate starting with a dark module at the finder
+fn verify_clock(prepared: &GrayImage, loc: &DataMatrixLocation) -> bool {
+    let dx = (loc.bottom_right - loc.bottom_left) / f64::from(loc.columns - 1);
+    let dy = (loc.bottom_left - loc.top_left) / f64::from(loc.rows - 1);
+
+    let dark = |p: Point| prepared.get_pixel(p.x.round() as u32, p.y.round() as u32)[0] == 0;
+
+    let top = (0..loc.columns).all(|x| dark(loc.top_left + f64::from(x) * dx) == (x % 2 == 0));
+    let right = (0..loc.rows).all(|y| dark(loc.bottom_right - f64::from(y) * dy) == (y % 2 == 0));
+
+    top && right
+}
+
+fn dark_runs(prepared: &GrayImage, start: Point, step: Delta, length: f64) -> Option<u32> {
+    let mut runs = 0;
+    let mut last_dark = false;
+    let mut distance = 0.0;
+
+    while distance < length {
+        let p = start + distance * step;
+        let dark = prepared.get_pixel(p.x.round() as u32, p.y.round() as u32)[0] == 0;
+
+        if dark && !last_dark {
+            runs += 1;
+        }
+
+        last_dark = dark;
+        distance += 1.0;
+    }
+
+    if runs == 0 {
+        None
+    } else {
+        Some(runs)
+    }
+}
+
+fn close(a: u32, b: u32) -> bool {
+    (i64::from(a) - i64::from(b)).abs() <= 1
+}
@@ -0,0 +1,317 @@
+use super::{runs, Detect, Location};
+
+use crate::util::ean::EANLocation;
+use crate::util::Point;
+
+use image::GrayImage;
+
+// Minimum width of the quiet zone on either side of the barcode, in modules
+const QUIET_ZONE: f64 = 5.0;
+
+// Minimum number of rows a barcode must be found on, to filter out patterns that happen to match on a single row
+const MIN_ROWS: usize = 3;
+
+// Maximum width of the gap between the barcode and its add-on, in modules
+const MAX_ADDON_GAP: f64 = 15.0;
+
+#[derive(Debug, Clone, Copy)]
+enum Element {
+    // Bars and spaces with a fixed width in modules
+    Guard(&'static [u32]),
+
+    // Two bars and two spaces with a total width of 7 modules
+    Digit,
+}
+
+impl Element {
+    fn runs(&self) -> usize {
+        match self {
+            Element::Guard(widths) => widths.len(),
+            Element::Digit => 4,
+        }
+    }
+
+    fn modules(&self) -> u32 {
+        match self {
+            Element::Guard(widths) => widths.iter().sum(),
+            Element::Digit => 7,
+        }
+    }
+}
+
+const GUARD: Element = Element::Guard(&[1, 1, 1]);
+const MIDDLE_GUARD: Element = Element::Guard(&[1, 1, 1, 1, 1]);
+const UPC_E_END_GUARD: Element = Element::Guard(&[1, 1, 1, 1, 1, 1]);
+const ADDON_GUARD: Element = Element::Guard(&[1, 1, 2]);
+const ADDON_GUARD_REVERSED: Element = Element::Guard(&[2, 1, 1]);
+const SEPARATOR: Element = Element::Guard(&[1, 1]);
+
+/// Scan a prepared image for EAN-13, EAN-8, UPC-A and UPC-E barcodes
+///
+/// The general idea of this method is as follows:
+/// 1. Scan all rows for runs of alternating dark and light pixels
+/// 2. Slide a window over the runs, looking for a quiet zone followed by the guard patterns and digits of one of the symbologies
+/// 3. Look for a 2 or 5 digit add-on next to the barcode
+/// 4. Combine the matches on nearby rows into a single barcode, using the row in the middle as the scan line
+/// 5. Discard barcodes that were only found on a few rows
+///
+/// Barcodes are only detected when their bars are vertical, though they may be upside down.
+pub struct EANScan {}
+
+impl EANScan {
+    /// Construct a new EANScan
+    pub fn new() -> EANScan {
+        EANScan {}
+    }
+}
+
+#[derive(Debug)]
+struct Match {
+    y: u32,
+    start: u32,
+    end: u32,
+    addon: Option<(u32, u32)>,
+    module_size: f64,
+}
+
+impl Detect<GrayImage> for EANScan {
+    fn detect(&self, prepared: &GrayImage) -> Vec<Location> {
+        let layouts = layouts();
+        let mut groups: Vec<Vec<Match>> = vec![];
+
+        for y in 0..prepared.height() {
+            // Step 1
+            // Find all runs in the row
+            let row = runs((0..prepared.width()).map(|x| prepared.get_pixel(x, y)[0] == 0));
+
+            // Step 2
+            // Slide over the runs, the first run of the window is the quiet zone
+            let mut first = 1;
+
+            while first < row.len() {
+                let found = layouts
+                    .iter()
+                    .filter_map(|layout| matches(&row, first, layout))
+                    .find(|(last, module)| {
+                        quiet_zone(&row, first - 1, *module) && quiet_zone(&row, last + 1, *module)
+                    });
+
+                let (last, module_size) = match found {
+                    Some(found) => found,
+                    None => {
+                        first += 1;
+                        continue;
+                    }
+                };
+
+                // Step 3
+                // Look for an add-on after the barcode, or before it if the barcode is upside down
+                let addon = find_addon(&row, first, last, module_size);
+
+                let found = Match {
+                    y,
+                    start: row[first].1,
+                    end: end(&row, last),
+                    addon,
+                    module_size,
+                };
+
+                debug!("EAN MATCH {:?}", found);
+
+                // Step 4
+                // Add the match to the barcode found on the previous rows, if any
+                let group = groups.iter_mut().find(|group| {
+                    let previous = group.last().unwrap();
+
+                    close(previous.start, found.start, 2.0 * module_size)
+                        && close(previous.end, found.end, 2.0 * module_size)
+                        && close(previous.y, found.y, QUIET_ZONE * module_size)
+                });
+
+                match group {
+                    Some(group) => group.push(found),
+                    None => groups.push(vec![found]),
+                }
+
+                first = last + 1;
+            }
+        }
+
+        groups
+            .into_iter()
+            .filter(|group| group.len() >= MIN_ROWS)
+            .map(location)
+            .collect()
+    }
+}
+
+// Layouts of the symbologies, upside down UPC-E has a different layout since it is not symmetrical
+fn layouts() -> Vec<Vec<Element>> {
+    let digits = |count: usize| vec![Element::Digit; count];
+
+    vec![
+        [
+            vec![GUARD],
+            digits(6),
+            vec![MIDDLE_GUARD],
+            digits(6),
+            vec![GUARD],
+        ]
+        .concat(),
+        [
+            vec![GUARD],
+            digits(4),
+            vec![MIDDLE_GUARD],
+            digits(4),
+            vec![GUARD],
+        ]
+        .concat(),
+        [vec![GUARD], digits(6), vec![UPC_E_END_GUARD]].concat(),
+        [vec![UPC_E_END_GUARD], digits(6), vec![GUARD]].concat(),
+    ]
+}
+
+// Layouts of the add-ons, with separators between the digits
+fn addon_layouts(reversed: bool) -> Vec<Vec<Element>> {
+    [2, 5]
+        .iter()
+        .rev()
+        .map(|count| {
+            let mut layout = vec![ADDON_GUARD, Element::Digit];
+
+            for _ in 1..*count {
+                layout.push(SEPARATOR);
+                layout.push(Element::Digit);
+            }
+
+            if reversed {
+                layout[0] = ADDON_GUARD_REVERSED;
+                layout.reverse();
+            }
+
+            layout
+        })
+        .collect()
+}
+
+// Match the layout against the runs, starting at the provided dark run
+//
+// Returns the index of the last run and the module size
+fn matches(row: &[(bool, u32, u32)], first: usize, layout: &[Element]) -> Option<(usize, f64)> {
+    let count: usize = layout.iter().map(Element::runs).sum();
+    let last = first + count - 1;
+
+    if last >= row.len() || !row[first].0 {
+        return None;
+    }
+
+    let modules: u32 = layout.iter().map(Element::modules).sum();
+    let module = f64::from(end(row, last) + 1 - row[first].1) / f64::from(modules);
+
+    let mut i = first;
+
+    for element in layout {
+        match element {
+            Element::Guard(widths) => {
+                for width in widths.iter() {
+                    if (f64::from(row[i].2) - f64::from(*width) * module).abs() >= 0.7 * module {
+                        return None;
+                    }
+
+                    i += 1;
+                }
+            }
+            Element::Digit => {
+                let width: u32 = row[i..i + 4].iter().map(|r| r.2).sum();
+
+                if (f64::from(width) - 7.0 * module).abs() >= 1.5 * module {
+                    return None;
+                }
+
+                i += 4;
+            }
+        }
+    }
+
+    Some((last, module))
+}
+
+fn find_addon(
+    row: &[(bool, u32, u32)],
+    first: usize,
+    last: usize,
+    module: f64,
+) -> Option<(u32, u32)> {
+    let gap = |i: usize| {
+        let width = f64::from(row[i].2);
+        width < MAX_ADDON_GAP * module
+    };
+
+    // After the barcode
+    if last + 2 < row.len() && gap(last + 1) {
+        for layout in addon_layouts(false) {
+            if let Some((addon_last, _)) = matches(row, last + 2, &layout) {
+                if quiet_zone(row, addon_last + 1, module) {
+                    return Some((row[last + 2].1, end(row, addon_last)));
+                }
+            }
+        }
+    }
+
+    // Before the barcode
+    if first >= 2 && gap(first - 1) {
+        for layout in addon_layouts(true) {
+            let count: usize = layout.iter().map(Element::runs).sum();
+
+            if count + 1 >= first {
+                continue;
+            }
+
+            let addon_first = first - 1 - count;
+
+            if matches(row, addon_first, &layout).is_some()
+                && quiet_zone(row, addon_first - 1, module)
+            {
+                return Some((row[addon_first].1, end(row, first - 2)));
+            }
+        }
+    }
+
+    None
+}
+
+fn quiet_zone(row: &[(bool, u32, u32)], i: usize, module: f64) -> bool {
+    matches!(row.get(i), Some(run) if !run.0 && f64::from(run.2) >= QUIET_ZONE * module)
+}
+
+// Last pixel of a run
+fn end(row: &[(bool, u32, u32)], i: usize) -> u32 {
+    row[i].1 + row[i].2 - 1
+}
+
+fn close(a: u32, b: u32, max: f64) -> bool {
+    (f64::from(a) - f64::from(b)).abs() <= max
+}
+
+// Use the middle row of the matches, preferring the rows where the add-on was found
+fn location(group: Vec<Match>) -> Location {
+    let with_addon: Vec<&Match> = group.iter().filter(|m| m.addon.is_some()).collect();
+
+    let found = if with_addon.is_empty() {
+        &group[group.len() / 2]
+    } else {
+        with_addon[with_addon.len() / 2]
+    };
+
+    let point = |x: u32| Point {
+        x: f64::from(x),
+        y: f64::from(found.y),
+    };
+
+    Location::EAN(EANLocation {
+        start: point(found.start),
+        end: point(found.end),
+        addon: found.addon.map(|(start, end)| (point(start), point(end))),
+        module_size: found.module_size,
+    })
+}
@@ -0,0 +1,99 @@
+use super::linear::{scan, Row};
+use super::{Detect, Location};
+
+use crate::decode::linear::narrow_wide;
+
+use crate::util::linear::LinearSymbology;
+
+use image::GrayImage;
+
+// Minimum width of the quiet zone on either side of the barcode, in narrow runs
+const QUIET_ZONE: f64 = 10.0;
+
+// Minimum number of digit pairs, since shorter barcodes are too easily found in random patterns
+const MIN_PAIRS: usize = 3;
+
+/// Scan a prepared image for ITF barcodes, including ITF-14
+///
+/// The general idea of this method is as follows:
+/// 1. Scan all rows for runs of alternating dark and light pixels
+/// 2. Look for a quiet zone followed by the start pattern of four narrow runs, or the reversed stop pattern if the barcode is upside down
+/// 3. Follow the pairs of interleaved digits of 10 runs, with 2 wide bars and 2 wide spaces each, until the stop pattern and a quiet zone are found
+/// 4. Combine the matches on nearby rows into a single barcode, using the row in the middle as the scan line
+///
+/// Bearer bars are tolerated, both the bars above and below the barcode and a full frame,
+/// as only the quiet zone between the barcode and the frame is required.
+///
+/// Barcodes are only detected when their bars are vertical, though they may be upside down.
+pub struct ITFScan {}
+
+impl ITFScan {
+    /// Construct a new ITFScan
+    pub fn new() -> ITFScan {
+        ITFScan {}
+    }
+}
+
+impl Detect<GrayImage> for ITFScan {
+    fn detect(&self, prepared: &GrayImage) -> Vec<Location> {
+        scan(prepared, LinearSymbology::ITF, QUIET_ZONE, find)
+    }
+}
+
+fn find(row: &Row, first: usize) -> Option<(usize, f64)> {
+    let widths: Vec<u32> = row[first..].iter().map(|r| r.2).collect();
+
+    // Forward the barcode starts with four narrow runs and stops with a wide bar and two narrow runs,
+    // upside down it starts with two narrow runs and a wide bar and stops with four narrow runs
+    let (begin, end, narrow_count) = match narrow_wide(widths.get(..3)?) {
+        Some(0b001) => (3, 4, 2),
+        _ => (4, 3, 4),
+    };
+
+    let start = widths.get(..narrow_count)?;
+    let narrow = f64::from(start.iter().sum::<u32>()) / narrow_count as f64;
+
+    if start
+        .iter()
+        .any(|w| (f64::from(*w) - narrow).abs() >= 0.5 * narrow)
+    {
+        return None;
+    }
+
+    let mut i = begin;
+    let mut pairs = 0;
+
+    loop {
+        let quiet = matches!(row.get(first + i + end), Some(run) if !run.0 && f64::from(run.2) >= QUIET_ZONE * narrow);
+
+        if quiet && pairs >= MIN_PAIRS && is_stop(widths.get(i..i + end)?, narrow) {
+            return Some((first + i + end - 1, narrow));
+        }
+
+        let pair = widths.get(i..i + 10)?;
+
+        let bars: Vec<u32> = pair.iter().step_by(2).copied().collect();
+        let spaces: Vec<u32> = pair.iter().skip(1).step_by(2).copied().collect();
+
+        for digit in [bars, spaces].iter() {
+            if narrow_wide(digit)?.count_ones() != 2
+                || f64::from(*digit.iter().min()?) >= 2.0 * narrow
+            {
+                return None;
+            }
+        }
+
+        i += 10;
+        pairs += 1;
+    }
+}
+
+// Stop pattern of a wide bar and two narrow runs, or four narrow runs if the barcode is upside down
+fn is_stop(widths: &[u32], narrow: f64) -> bool {
+    let is_narrow = |w: &u32| (f64::from(*w) - narrow).abs() < 0.5 * narrow;
+
+    match widths.len() {
+        3 => f64::from(widths[0]) >= 1.5 * narrow && widths[1..].iter().all(is_narrow),
+        _ => widths.iter().all(is_narrow),
+    }
+}
@@ -0,0 +1,298 @@
+use super::Location;
+
+use crate::decode::linear::{narrow_wide, reverse_bits};
+
+use crate::util::linear::{LinearLocation, LinearSymbology};
+use crate::util::Point;
+
+use image::GrayImage;
+
+/// Runs of the same color along a row, as (dark, start, length)
+pub type Row = [(bool, u32, u32)];
+
+/// Minimum number of rows a barcode must be found on, to filter out patterns that happen to match on a single row
+pub const MIN_ROWS: usize = 3;
+
+/// A barcode found on a single row of pixels
+#[derive(Debug)]
+pub struct Match {
+    /// Row of pixels
+    pub y: u32,
+
+    /// First pixel of the leftmost bar
+    pub start: u32,
+
+    /// Last pixel of the rightmost bar
+    pub end: u32,
+
+    /// Module size in pixels
+    pub module_size: f64,
+}
+
+/// Scan all rows of a prepared image for a linear symbology
+///
+/// The provided function is called for every dark run, and should return the index of the last run of the barcode and the module size if it starts there.
+/// Both ends of the barcode need a quiet zone of the provided number of modules.
+/// Matches on nearby rows are combined into a single barcode, using the row in the middle as the scan line.
+pub fn scan<F>(
+    prepared: &GrayImage,
+    symbology: LinearSymbology,
+    quiet_zone: f64,
+    find: F,
+) -> Vec<Location>
+where
+    F: Fn(&Row, usize) -> Option<(usize, f64)>,
+{
+    groups(
+        prepared,
+        &format!("{:?}", symbology),
+        quiet_zone,
+        quiet_zone,
+        find,
+    )
+    .into_iter()
+    .map(|group| {
+        let found = &group[group.len() / 2];
+
+        Location::Linear(LinearLocation {
+            symbology,
+            start: Point {
+                x: f64::from(found.start),
+                y: f64::from(found.y),
+            },
+            end: Point {
+                x: f64::from(found.end),
+                y: f64::from(found.y),
+            },
+            module_size: found.module_size,
+            stacked: vec![],
+        })
+    })
+    .collect()
+}
+
+/// Scan all rows of a prepared image for barcodes, and group the matches on nearby rows
+///
+/// The provided function is called for every dark run, and should return the index of the last run of the barcode and the module size if it starts there.
+/// Both ends of the barcode need a quiet zone of the provided number of modules.
+/// Matches belong to the same barcode if they are at most the provided gap in modules below each other.
+/// Only groups of at least [`MIN_ROWS`] matches are returned, with the matches from top to bottom.
+///
+/// [`MIN_ROWS`]: constant.MIN_ROWS.html
+pub fn groups<F>(
+    prepared: &GrayImage,
+    name: &str,
+    quiet_zone: f64,
+    gap: f64,
+    find: F,
+) -> Vec<Vec<Match>>
+where
+    F: Fn(&Row, usize) -> Option<(usize, f64)>,
+{
+    let mut groups: Vec<Vec<Match>> = vec![];
+
+    for y in 0..prepared.height() {
+        let row = super::runs((0..prepared.width()).map(|x| prepared.get_pixel(x, y)[0] == 0));
+
+        let mut first = 1;
+
+        while first < row.len() {
+            if !row[first].0 {
+                first += 1;
+                continue;
+            }
+
+            let found = find(&row, first).filter(|(last, module)| {
+                is_quiet_zone(&row, first - 1, quiet_zone * module)
+                    && is_quiet_zone(&row, last + 1, quiet_zone * module)
+            });
+
+            let (last, module_size) = match found {
+                Some(found) => found,
+                None => {
+                    first += 1;
+                    continue;
+                }
+            };
+
+            let found = Match {
+                y,
+                start: row[first].1,
+                end: row[last].1 + row[last].2 - 1,
+                module_size,
+            };
+
+            debug!("{} MATCH {:?}", name, found);
+
+            let group = groups.iter_mut().find(|group| {
+                let previous = group.last().unwrap();
+
+                close(previous.start, found.start, 2.0 * module_size)
+                    && close(previous.end, found.end, 2.0 * module_size)
+                    && close(previous.y, found.y, gap * module_size)
+            });
+
+            match group {
+                Some(group) => group.push(found),
+                None => groups.push(vec![found]),
+            }
+
+            first = last + 1;
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|group| group.len() >= MIN_ROWS)
+        .collect()
+}
+
+/// Follow a symbology with characters of a fixed number of runs and modules, between start and stop patterns
+///
+/// The barcode starts with any of the start patterns, which must all have the same length, and is followed until the stop pattern.
+/// If the barcode is upside down, it is followed from the reversed stop pattern until any of the reversed start patterns.
+/// The total width of each character must be within 1.5 modules of the expected width.
+///
+/// Returns the index of the last run and the module size
+pub fn fixed_width(
+    row: &Row,
+    first: usize,
+    starts: &[&[u32]],
+    stop: &[u32],
+    char_runs: usize,
+    char_modules: u32,
+) -> Option<(usize, f64)> {
+    let reverse = |widths: &[u32]| widths.iter().rev().copied().collect::<Vec<u32>>();
+
+    let (module, begin, ends) = match starts.iter().find_map(|start| matches(row, first, start)) {
+        Some(module) => (module, starts[0].len(), vec![stop.to_vec()]),
+        None => (
+            matches(row, first, &reverse(stop))?,
+            stop.len(),
+            starts.iter().map(|start| reverse(start)).collect(),
+        ),
+    };
+
+    let mut i = first + begin;
+    let mut chars = 0;
+
+    loop {
+        if let Some(end) = ends.iter().find(|end| matches(row, i, end).is_some()) {
+            let last = i + end.len() - 1;
+            let modules =
+                starts[0].iter().sum::<u32>() + chars * char_modules + stop.iter().sum::<u32>();
+            let total = row[last].1 + row[last].2 - row[first].1;
+
+            return Some((last, f64::from(total) / f64::from(modules)));
+        }
+
+        let width = f64::from(width(row, i, char_runs)?);
+
+        if (width - f64::from(char_modules) * module).abs() >= 1.5 * module {
+            return None;
+        }
+
+        i += char_runs;
+        chars += 1;
+    }
+}
+
+/// Follow a symbology with characters of narrow and wide runs separated by a narrow space, between start and stop characters
+///
+/// The barcode starts and stops with any of the guard characters, which are reversed if the barcode is upside down.
+/// Every character must have one of the provided numbers of wide runs.
+///
+/// Returns the index of the last run and the width of a narrow run
+pub fn narrow_wide_chars(
+    row: &Row,
+    first: usize,
+    guards: &[u32],
+    char_runs: usize,
+    wide: &[u32],
+) -> Option<(usize, f64)> {
+    let bits = char_bits(row, first, char_runs)?;
+
+    let reversed: Vec<u32> = guards
+        .iter()
+        .map(|guard| reverse_bits(*guard, char_runs))
+        .collect();
+
+    let guards = if guards.contains(&bits) {
+        guards.to_vec()
+    } else if reversed.contains(&bits) {
+        reversed
+    } else {
+        return None;
+    };
+
+    // Estimate the narrow width from the narrow runs of the first character
+    let narrow_runs: Vec<u32> = (0..char_runs)
+        .filter(|i| bits & (1 << (char_runs - 1 - i)) == 0)
+        .map(|i| row[first + i].2)
+        .collect();
+    let narrow = f64::from(narrow_runs.iter().sum::<u32>()) / narrow_runs.len() as f64;
+
+    let mut i = first + char_runs;
+
+    loop {
+        // Space between the characters
+        if f64::from(row.get(i)?.2) >= 3.0 * narrow {
+            return None;
+        }
+
+        let bits = char_bits(row, i + 1, char_runs)?;
+
+        if !wide.contains(&bits.count_ones()) {
+            return None;
+        }
+
+        if guards.contains(&bits) {
+            return Some((i + char_runs, narrow));
+        }
+
+        i += char_runs + 1;
+    }
+}
+
+/// Check that the runs starting at the provided index match the widths in modules
+///
+/// Returns the module size estimated from the total width of the runs
+pub fn matches(row: &Row, first: usize, widths: &[u32]) -> Option<f64> {
+    if first + widths.len() > row.len() {
+        return None;
+    }
+
+    let runs = &row[first..first + widths.len()];
+    let total: u32 = runs.iter().map(|r| r.2).sum();
+    let module = f64::from(total) / f64::from(widths.iter().sum::<u32>());
+
+    if runs
+        .iter()
+        .zip(widths)
+        .all(|(run, width)| (f64::from(run.2) - f64::from(*width) * module).abs() < 0.7 * module)
+    {
+        Some(module)
+    } else {
+        None
+    }
+}
+
+/// Total width of the runs starting at the provided index, in pixels
+pub fn width(row: &Row, first: usize, count: usize) -> Option<u32> {
+    row.get(first..first + count)
+        .map(|runs| runs.iter().map(|r| r.2).sum())
+}
+
+fn char_bits(row: &Row, first: usize, count: usize) -> Option<u32> {
+    let runs: Vec<u32> = row.get(first..first + count)?.iter().map(|r| r.2).collect();
+    narrow_wide(&runs)
+}
+
+fn is_quiet_zone(row: &Row, i: usize, min: f64) -> bool {
+    matches!(row.get(i), Some(run) if !run.0 && f64::from(run.2) >= min)
+}
+
+/// Check that two pixel positions are at most the provided distance apart
+pub fn close(a: u32, b: u32, max: f64) -> bool {
+    (f64::from(a) - f64::from(b)).abs() <= max
+}
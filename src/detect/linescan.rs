@@ -1,11 +1,13 @@
 use super::{Detect, Location};
 
 use std::cmp::min;
+use std::f64::consts::PI;
 use std::iter::repeat;
 use std::iter::Iterator;
 
-use crate::util::qr::QRLocation;
-use crate::util::Point;
+use crate::prepare::PreparedGrayscale;
+use crate::util::qr::{MicroQRLocation, QRLocation, RMQRLocation, RMQR_SIZES};
+use crate::util::{Delta, Point};
 
 use image::{GrayImage, Pixel};
 
@@ -21,6 +23,14 @@ use std::{env::temp_dir, fs::create_dir_all};
 /// 1. Scan line by line horizontally for possible QR Finder patterns (the three squares)
 /// 2. If a possible pattern is found, check vertically and diagonally to confirm it is indeed a pattern
 /// 3. Try to find combinations of three patterns that are perpendicular and with similar distance that form a complete QR Code
+/// 4. Check any remaining patterns for timing patterns running along two of its sides, which form a Micro QR Code
+/// 5. If not, follow two of its sides to the opposite corners and look for the sub finder pattern of an rMQR Code
+///
+/// Steps 4 and 5 are skipped by [`detect_qr`], which the [`Decoder`] calls when no Micro QR or rMQR decoder is set.
+/// Micro QR and rMQR Codes are detected in any orientation, their orientation is measured from the outer edge of the finder pattern.
+///
+/// [`detect_qr`]: trait.Detect.html#method.detect_qr
+/// [`Decoder`]: ../struct.Decoder.html
 pub struct LineScan {}
 
 impl LineScan {
@@ -30,10 +40,32 @@ impl LineScan {
     }
 }
 
-type Refine = dyn Fn(&LineScan, &GrayImage, &Point, f64) -> Option<QRFinderPosition>;
+/// The finder patterns are looked for in the prepared image only, the grayscale image is left to the extractor
+impl Detect<PreparedGrayscale> for LineScan {
+    fn detect(&self, prepared: &PreparedGrayscale) -> Vec<Location> {
+        self.scan(&prepared.prepared, true)
+    }
+
+    fn detect_qr(&self, prepared: &PreparedGrayscale) -> Vec<Location> {
+        self.scan(&prepared.prepared, false)
+    }
+}
 
 impl Detect<GrayImage> for LineScan {
     fn detect(&self, prepared: &GrayImage) -> Vec<Location> {
+        self.scan(prepared, true)
+    }
+
+    fn detect_qr(&self, prepared: &GrayImage) -> Vec<Location> {
+        self.scan(prepared, false)
+    }
+}
+
+type Refine = dyn Fn(&LineScan, &GrayImage, &Point, f64) -> Option<QRFinderPosition>;
+
+impl LineScan {
+    // Scan for QR Codes, and for Micro QR and rMQR Codes as well if single_finders is set
+    fn scan(&self, prepared: &GrayImage, single_finders: bool) -> Vec<Location> {
         // The order of refinement is important.
         // The candidate is found in horizontal direction, so the first refinement is vertical
         let refine_func: Vec<(Box<Refine>, f64, f64, bool)> = vec![
@@ -163,6 +195,7 @@ impl Detect<GrayImage> for LineScan {
         let mut locations: Vec<Location> = vec![];
 
         let max_candidates = candidates.len();
+        let mut used = vec![false; max_candidates];
 
         // Step 3
         // Loop through all candidates to see if any combination results in an actual QR
@@ -198,11 +231,42 @@ impl Detect<GrayImage> for LineScan {
                         candidates[candidate1].module_size,
                     ) {
                         locations.push(Location::QR(qr));
+
+                        used[candidate1] = true;
+                        used[candidate2] = true;
+                        used[candidate3] = true;
                     }
                 }
             }
         }
 
+        if !single_finders {
+            return locations;
+        }
+
+        // Step 4
+        // Any candidates not part of a QR might be the single finder of a Micro QR
+        for (candidate, used) in candidates.iter().zip(used) {
+            if used {
+                continue;
+            }
+
+            let axes = finder_axes(prepared, candidate);
+
+            trace!("FINDER AXES {:?}", axes);
+
+            if let Some(micro_qr) = axes.as_ref().and_then(|axes| find_micro_qr(prepared, axes)) {
+                locations.push(Location::MicroQR(micro_qr));
+                continue;
+            }
+
+            // Step 5
+            // Or the finder of an rMQR
+            if let Some(rmqr) = axes.and_then(|axes| find_rmqr(prepared, &axes)) {
+                locations.push(Location::RMQR(rmqr));
+            }
+        }
+
         locations
     }
 }
@@ -532,6 +596,369 @@ fn find_qr_internal(
     }
 }
 
+// Number of rays cast from the center of a finder to measure its orientation
+const RAYS: u32 = 72;
+
+// Orientation of a single finder pattern, measured from the outer edge of its dark ring
+#[derive(Debug)]
+struct FinderAxes {
+    // Center of the finder, in pixels
+    center: Point,
+
+    // Unit vector along one of the sides of the finder, the other sides are at multiples of 90 degrees
+    right: Delta,
+
+    // Module size in pixels, measured along the sides of the finder
+    module_size: f64,
+}
+
+impl FinderAxes {
+    // Unit vectors along all four sides of the finder
+    fn directions(&self) -> [Delta; 4] {
+        let right = self.right;
+
+        [
+            right,
+            Delta {
+                dx: -right.dy,
+                dy: right.dx,
+            },
+            Delta {
+                dx: -right.dx,
+                dy: -right.dy,
+            },
+            Delta {
+                dx: right.dy,
+                dy: -right.dx,
+            },
+        ]
+    }
+}
+
+// The outer edge of the finder is a square, so its distance to the center is largest at the four corners.
+// The phase of that pattern, which repeats every 90 degrees, gives the direction of the corners and therefore of the sides.
+fn finder_axes(prepared: &GrayImage, candidate: &QRFinderPosition) -> Option<FinderAxes> {
+    let center = candidate.location;
+
+    // The candidate module size is too large for rotated finders, by up to a factor of the square root of 2
+    let max = 6.0 * candidate.module_size;
+
+    let ray = |angle: f64| Delta {
+        dx: angle.cos(),
+        dy: angle.sin(),
+    };
+
+    let mut rays = 0;
+    let mut sum_cos = 0.0;
+    let mut sum_sin = 0.0;
+
+    for i in 0..RAYS {
+        let angle = 2.0 * PI * f64::from(i) / f64::from(RAYS);
+
+        if let Some(edge) = ring_edge(prepared, center, ray(angle), max) {
+            rays += 1;
+            sum_cos += edge * (4.0 * angle).cos();
+            sum_sin += edge * (4.0 * angle).sin();
+        }
+    }
+
+    // Allow for some noise, but most of the finder needs to be visible
+    if rays < RAYS * 3 / 4 {
+        return None;
+    }
+
+    let angle = sum_sin.atan2(sum_cos) / 4.0 - PI / 4.0;
+
+    // Measure the distance to each side, averaging a few rays around the perpendicular
+    let mut sides = [0.0; 4];
+
+    for (side, distance) in sides.iter_mut().enumerate() {
+        let perpendicular = angle + PI / 2.0 * side as f64;
+        let mut count = 0.0;
+
+        for offset in &[-3.0_f64, 0.0, 3.0] {
+            let offset = offset.to_radians();
+
+            if let Some(edge) = ring_edge(prepared, center, ray(perpendicular + offset), max) {
+                *distance += edge * offset.cos();
+                count += 1.0;
+            }
+        }
+
+        if count == 0.0 {
+            return None;
+        }
+
+        *distance /= count;
+    }
+
+    let right = ray(angle);
+    let down = ray(angle + PI / 2.0);
+
+    // The outer edge is 3.5 modules from the center on every side
+    Some(FinderAxes {
+        center: center + (sides[0] - sides[2]) / 2.0 * right + (sides[1] - sides[3]) / 2.0 * down,
+        right,
+        module_size: sides.iter().sum::<f64>() / 14.0,
+    })
+}
+
+// Follow a ray from the center of a finder, through the light ring to the outer edge of the dark ring
+// Returns the distance in pixels to that edge, if found within max pixels
+fn ring_edge(prepared: &GrayImage, center: Point, ray: Delta, max: f64) -> Option<f64> {
+    let mut last_dark = true;
+    let mut changes = 0;
+    let mut distance = 0.0;
+
+    if !is_dark(prepared, center)? {
+        return None;
+    }
+
+    while distance < max {
+        let dark = is_dark(prepared, center + distance * ray)?;
+
+        if dark != last_dark {
+            changes += 1;
+            last_dark = dark;
+        }
+
+        // Dark to light, light to dark and dark to light again
+        if changes == 3 {
+            // The edge is somewhere between this step and the previous one
+            return Some(distance - 0.25);
+        }
+
+        distance += 0.5;
+    }
+
+    None
+}
+
+fn find_micro_qr(prepared: &GrayImage, axes: &FinderAxes) -> Option<MicroQRLocation> {
+    let finder = axes.center;
+    let module_size = axes.module_size;
+    let directions = axes.directions();
+
+    // Try every side of the finder, the timing patterns run along the outer edge of the finder
+    // in the 'right' and 'down' directions
+    for right in &directions {
+        for down in &[Delta { dx: -right.dy, dy: right.dx }, Delta { dx: right.dy, dy: -right.dx }] {
+            let right = *right * module_size;
+            let down = *down * module_size;
+
+            let horizontal = timing_length(prepared, finder - 3.0 * down, right);
+            let vertical = timing_length(prepared, finder - 3.0 * right, down);
+
+            trace!("MICRO QR TIMING {:?} {:?}", horizontal, vertical);
+
+            match (horizontal, vertical) {
+                (Some(horizontal), Some(vertical)) if horizontal == vertical => {
+                    let side = f64::from(horizontal);
+
+                    return Some(MicroQRLocation {
+                        top_left: finder,
+                        top_right: finder + (side - 4.0) * right - 3.0 * down,
+                        bottom_left: finder - 3.0 * right + (side - 4.0) * down,
+                        module_size,
+                        version: (horizontal - 9) / 2,
+                    });
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    None
+}
+
+// Follow a timing pattern starting at the center of the edge of the finder
+// Returns the side of the Micro QR in modules, if valid
+fn timing_length(prepared: &GrayImage, start: Point, step: Delta) -> Option<u32> {
+    // The separator next to the finder needs to be light
+    if is_dark(prepared, start + 4.0 * step)? {
+        return None;
+    }
+
+    // The finder center is in module 3, the timing pattern starts at module 8 and alternates dark/light
+    // The first even module that is light is the quiet zone after the last dark module
+    for module in 8..=18 {
+        let dark = is_dark(prepared, start + f64::from(module - 3) * step)?;
+
+        if module % 2 == 0 && !dark {
+            let side = module - 1;
+            return if side >= 11 { Some(side) } else { None };
+        }
+
+        if module % 2 == 1 && dark {
+            return None;
+        }
+    }
+
+    None
+}
+
+fn find_rmqr(prepared: &GrayImage, axes: &FinderAxes) -> Option<RMQRLocation> {
+    let finder = axes.center;
+    let module_size = axes.module_size;
+    let directions = axes.directions();
+
+    // Try every side of the finder, the top edge runs in the 'right' direction and the left edge in the 'down' direction
+    for right in &directions {
+        for down in &[Delta { dx: -right.dy, dy: right.dx }, Delta { dx: right.dy, dy: -right.dx }] {
+            let corner = finder - 3.0 * module_size * *right - 3.0 * module_size * *down;
+
+            let horizontal = edge_length(prepared, corner, *right, module_size);
+            let vertical = edge_length(prepared, corner, *down, module_size);
+
+            // The edges contain no light runs longer than a single module, so measure the distance up to the quiet zone
+            let version = RMQR_SIZES.iter().position(|(width, height)| {
+                diff(horizontal / module_size, f64::from(width - 1)) < 0.1
+                    && (vertical / module_size - f64::from(height - 1)).abs() < 0.5
+            });
+
+            trace!("RMQR EDGES {} {} {:?}", horizontal, vertical, version);
+
+            let version = match version {
+                Some(version) => version,
+                None => continue,
+            };
+
+            let (width, height) = RMQR_SIZES[version];
+            let right = horizontal / f64::from(width - 1) * *right;
+            let down = vertical / f64::from(height - 1) * *down;
+
+            let estimate = corner + f64::from(width - 3) * right + f64::from(height - 3) * down;
+
+            // A small error in the measured orientation adds up along the long edge,
+            // so look for the sub finder around its estimated center
+            let sub_finder = match find_sub_finder(prepared, estimate, right, down) {
+                Some(sub_finder) => sub_finder,
+                None => continue,
+            };
+
+            // Rotate the edges so the sub finder is exactly where it should be
+            let (right, down) = align(sub_finder - finder, right, down, width - 6, height - 6);
+            let corner = finder - 3.0 * right - 3.0 * down;
+
+            return Some(RMQRLocation {
+                top_left: finder,
+                top_right: corner + f64::from(width - 1) * right,
+                bottom_left: corner + f64::from(height - 1) * down,
+                module_size,
+                width,
+                height,
+                version: version as u32,
+            });
+        }
+    }
+
+    None
+}
+
+// Follow an edge of the rMQR, starting at the center of the corner module
+// Returns the distance in pixels to the center of the last module of the edge
+fn edge_length(prepared: &GrayImage, start: Point, step: Delta, module_size: f64) -> f64 {
+    let mut last_dark = 0.0;
+    let mut distance = 0.0;
+
+    // The quiet zone is at least 2 modules wide
+    while distance - last_dark < 2.0 * module_size {
+        match is_dark(prepared, start + distance * step) {
+            Some(true) => last_dark = distance,
+            Some(false) => {}
+            None => break,
+        }
+
+        distance += 1.0;
+    }
+
+    last_dark - (module_size - 1.0) / 2.0
+}
+
+// Look for the sub finder within a module of the estimated center, in steps of half a module
+// Returns the average of all positions where it was found
+fn find_sub_finder(
+    prepared: &GrayImage,
+    estimate: Point,
+    right: Delta,
+    down: Delta,
+) -> Option<Point> {
+    let mut found = vec![];
+
+    for x in -2..=2 {
+        for y in -2..=2 {
+            let center = estimate + f64::from(x) / 2.0 * right + f64::from(y) / 2.0 * down;
+
+            if is_sub_finder(prepared, center, right, down) {
+                found.push(center);
+            }
+        }
+    }
+
+    if found.is_empty() {
+        return None;
+    }
+
+    let count = found.len() as f64;
+
+    Some(Point {
+        x: found.iter().map(|p| p.x).sum::<f64>() / count,
+        y: found.iter().map(|p| p.y).sum::<f64>() / count,
+    })
+}
+
+// Rotate one module along both edges, so that moving across and along them ends up at the given offset
+fn align(offset: Delta, right: Delta, down: Delta, across: u32, along: u32) -> (Delta, Delta) {
+    let length = |d: Delta| (d.dx * d.dx + d.dy * d.dy).sqrt();
+    let (right_size, down_size) = (length(right), length(down));
+    let (right, down) = (right / right_size, down / down_size);
+
+    // The offset expressed along the current edges, versus where it should be
+    let actual = (offset.dx * down.dx + offset.dy * down.dy)
+        .atan2(offset.dx * right.dx + offset.dy * right.dy);
+    let expected = (f64::from(along) * down_size).atan2(f64::from(across) * right_size);
+    let (sin, cos) = (actual - expected).sin_cos();
+
+    (
+        right_size * (cos * right + sin * down),
+        down_size * (cos * down - sin * right),
+    )
+}
+
+// The sub finder pattern is a dark module, surrounded by a light ring and a dark ring
+fn is_sub_finder(prepared: &GrayImage, center: Point, right: Delta, down: Delta) -> bool {
+    let dark = |x: f64, y: f64| is_dark(prepared, center + x * right + y * down);
+
+    if dark(0.0, 0.0) != Some(true) {
+        return false;
+    }
+
+    for (x, y) in &[(-1.0, -1.0), (0.0, -1.0), (1.0, -1.0), (-1.0, 0.0), (1.0, 0.0), (-1.0, 1.0), (0.0, 1.0), (1.0, 1.0)] {
+        if dark(*x, *y) != Some(false) {
+            return false;
+        }
+    }
+
+    for (x, y) in &[(0.0, -2.0), (-2.0, 0.0), (2.0, 0.0), (0.0, 2.0)] {
+        if dark(*x, *y) != Some(true) {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn is_dark(prepared: &GrayImage, p: Point) -> Option<bool> {
+    let x = p.x.round();
+    let y = p.y.round();
+
+    if x < 0.0 || y < 0.0 || x >= f64::from(prepared.width()) || y >= f64::from(prepared.height()) {
+        return None;
+    }
+
+    Some(prepared.get_pixel(x as u32, y as u32)[0] == 0)
+}
+
 #[derive(Debug)]
 pub struct QRFinderPosition {
     pub location: Point,
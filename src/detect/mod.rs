@@ -1,8 +1,11 @@
 //! Detect various barcodes in pre-processed images
-//!
-//! (well, only QR so far)
 
-use crate::util::qr::QRLocation;
+use crate::util::aztec::AztecLocation;
+use crate::util::datamatrix::DataMatrixLocation;
+use crate::util::ean::EANLocation;
+use crate::util::linear::LinearLocation;
+use crate::util::pdf417::PDF417Location;
+use crate::util::qr::{MicroQRLocation, QRLocation, RMQRLocation};
 
 /// Detect barcode in a prepared image
 ///
@@ -39,15 +42,82 @@ use crate::util::qr::QRLocation;
 pub trait Detect<PREPD> {
     /// Does the actual detecting
     fn detect(&self, prepared: &PREPD) -> Vec<Location>;
+
+    /// Does the detecting when only QR Codes will be decoded
+    ///
+    /// The [`Decoder`] calls this instead of `detect` when no Micro QR or rMQR decoder is set,
+    /// so implementations can skip looking for those. By default this is the same as `detect`.
+    fn detect_qr(&self, prepared: &PREPD) -> Vec<Location> {
+        self.detect(prepared)
+    }
 }
 
+mod aztec;
+mod codabar;
+mod code128;
+mod code39;
+mod code93;
+mod databar;
+mod datamatrix;
+mod ean;
+mod itf;
+mod linear;
 mod linescan;
+mod pdf417;
 
+pub use self::aztec::AztecScan;
+pub use self::codabar::CodabarScan;
+pub use self::code128::Code128Scan;
+pub use self::code39::Code39Scan;
+pub use self::code93::Code93Scan;
+pub use self::databar::{DataBarExpandedScan, DataBarScan};
+pub use self::datamatrix::DataMatrixScan;
+pub use self::ean::EANScan;
+pub use self::itf::ITFScan;
 pub use self::linescan::LineScan;
+pub use self::pdf417::PDF417Scan;
+
+// Runs of the same color along a line of pixels, as (dark, start, length)
+fn runs<I>(pixels: I) -> Vec<(bool, u32, u32)>
+where
+    I: Iterator<Item = bool>,
+{
+    let mut runs: Vec<(bool, u32, u32)> = vec![];
+
+    for (i, dark) in pixels.enumerate() {
+        match runs.last_mut() {
+            Some(run) if run.0 == dark => run.2 += 1,
+            _ => runs.push((dark, i as u32, 1)),
+        }
+    }
+
+    runs
+}
 
 /// Location of a detected barcode
 #[derive(Debug)]
 pub enum Location {
     /// Location of a detected QR Code
     QR(QRLocation),
+
+    /// Location of a detected Micro QR Code
+    MicroQR(MicroQRLocation),
+
+    /// Location of a detected rMQR Code
+    RMQR(RMQRLocation),
+
+    /// Location of a detected Data Matrix Code
+    DataMatrix(DataMatrixLocation),
+
+    /// Location of a detected Aztec Code
+    Aztec(AztecLocation),
+
+    /// Location of a detected EAN or UPC barcode
+    EAN(EANLocation),
+
+    /// Location of a detected linear barcode, along the scan line through it
+    Linear(LinearLocation),
+
+    /// Location of a detected PDF417 Code, along the scan lines through its top and bottom rows
+    PDF417(PDF417Location),
 }
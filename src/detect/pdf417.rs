@@ -0,0 +1,70 @@
+use super::linear::{fixed_width, groups, Match, Row};
+use super::{Detect, Location};
+
+use crate::util::pdf417::PDF417Location;
+use crate::util::Point;
+
+use image::GrayImage;
+
+// Minimum width of the quiet zone on either side of the PDF417 Code, in modules
+const QUIET_ZONE: f64 = 2.0;
+
+// Maximum distance between rows of pixels of the same PDF417 Code, in modules, so a damaged row does not split it in two
+const ROW_GAP: f64 = 10.0;
+
+const START: [u32; 8] = [8, 1, 1, 1, 1, 1, 1, 3];
+const STOP: [u32; 9] = [7, 1, 1, 3, 1, 1, 1, 2, 1];
+
+/// Scan a prepared image for PDF417 Codes
+///
+/// The general idea of this method is as follows:
+/// 1. Scan all rows for runs of alternating dark and light pixels
+/// 2. Look for a quiet zone followed by the start pattern, or the stop pattern if the PDF417 Code is upside down
+/// 3. Follow the symbol characters of 17 modules each until the stop pattern is found, or the start pattern if the PDF417 Code is upside down
+/// 4. Combine the matches on nearby rows into a single PDF417 Code, from the topmost to the bottommost match
+///
+/// Every row of a PDF417 Code starts and ends with the same patterns, so the rows of the PDF417 Code are told apart by the extractor.
+/// PDF417 Codes are only detected when their bars are vertical, though they may be upside down.
+pub struct PDF417Scan {}
+
+impl PDF417Scan {
+    /// Construct a new PDF417Scan
+    pub fn new() -> PDF417Scan {
+        PDF417Scan {}
+    }
+}
+
+impl Detect<GrayImage> for PDF417Scan {
+    fn detect(&self, prepared: &GrayImage) -> Vec<Location> {
+        groups(prepared, "PDF417", QUIET_ZONE, ROW_GAP, find)
+            .into_iter()
+            .map(|group| {
+                let top = &group[0];
+                let bottom = &group[group.len() - 1];
+
+                let line = |found: &Match| {
+                    (
+                        Point {
+                            x: f64::from(found.start),
+                            y: f64::from(found.y),
+                        },
+                        Point {
+                            x: f64::from(found.end),
+                            y: f64::from(found.y),
+                        },
+                    )
+                };
+
+                Location::PDF417(PDF417Location {
+                    top: line(top),
+                    bottom: line(bottom),
+                    module_size: group[group.len() / 2].module_size,
+                })
+            })
+            .collect()
+    }
+}
+
+fn find(row: &Row, first: usize) -> Option<(usize, f64)> {
+    fixed_width(row, first, &[&START], &STOP, 8, 17)
+}
@@ -0,0 +1,22 @@
+//! Encode data into barcodes
+//!
+//! QR Codes are encoded into a [`QRData`] module matrix, which can be rendered into an image
+//!
+//! # Example
+//! ```
+//! # extern crate bardecoder;
+//! use bardecoder::encode::{render, QREncoder};
+//! use bardecoder::ECLevel;
+//!
+//! let data = QREncoder::new(ECLevel::MEDIUM).encode("HELLO WORLD").unwrap();
+//! let image = render(&data, 4, 4);
+//!
+//! assert_eq!(data.version, 1);
+//! assert_eq!(image.width(), (21 + 2 * 4) * 4);
+//! ```
+//!
+//! [`QRData`]: ../util/qr/struct.QRData.html
+
+mod qr;
+
+pub use self::qr::{render, QREncoder};
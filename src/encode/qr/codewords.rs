@@ -0,0 +1,131 @@
+use crate::decode::qr::galois::{EXP8, GF8};
+use crate::decode::qr::BlockInfo;
+
+// Pad codewords that fill up the remaining capacity, alternately
+const PAD: [u8; 2] = [0xEC, 0x11];
+
+/// Terminate the data bits, and pack them into codewords padded to the capacity in bits
+pub fn data(mut bits: Vec<bool>, capacity: usize) -> Vec<u8> {
+    // Terminator of up to four zero bits, then zeros up to the next codeword
+    let terminator = (capacity - bits.len()).min(4);
+    bits.resize(bits.len() + terminator, false);
+    bits.resize(bits.len() + (8 - bits.len() % 8) % 8, false);
+
+    let mut data: Vec<u8> = bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0, |b, bit| (b << 1) | u8::from(*bit)))
+        .collect();
+
+    for i in 0..capacity / 8 - data.len() {
+        data.push(PAD[i % 2]);
+    }
+
+    data
+}
+
+/// Split the data codewords over the blocks, add the error correction codewords of every block and interleave them
+///
+/// The data codewords are interleaved first, taking one from every block in turn and skipping the shorter blocks at the end,
+/// followed by the error correction codewords in the same way.
+pub fn interleave(data: &[u8], block_info: &[BlockInfo]) -> Vec<u8> {
+    let mut blocks = vec![];
+    let mut start = 0;
+
+    for bi in block_info {
+        let block = &data[start..start + bi.data_per as usize];
+        let ec = ec_codewords(block, (bi.total_per - bi.data_per) as usize);
+        blocks.push((block, ec));
+        start += bi.data_per as usize;
+    }
+
+    let mut codewords = vec![];
+
+    for i in 0..block_info.iter().map(|bi| bi.data_per).max().unwrap_or(0) as usize {
+        codewords.extend(blocks.iter().filter_map(|(block, _)| block.get(i)));
+    }
+
+    for i in 0..block_info
+        .iter()
+        .map(|bi| bi.total_per - bi.data_per)
+        .max()
+        .unwrap_or(0) as usize
+    {
+        codewords.extend(blocks.iter().filter_map(|(_, ec)| ec.get(i)));
+    }
+
+    codewords
+}
+
+// Error correction codewords, the remainder of the data divided by the generator polynomial (x - a^0) ... (x - a^(count - 1))
+fn ec_codewords(data: &[u8], count: usize) -> Vec<u8> {
+    // Coefficients of the generator polynomial, highest power first
+    let mut generator = vec![GF8(1)];
+
+    for root in EXP8.iter().take(count) {
+        let mut next = vec![GF8(0); generator.len() + 1];
+
+        for (j, g) in generator.iter().enumerate() {
+            next[j] = next[j] + *g;
+            next[j + 1] = next[j + 1] + *g * *root;
+        }
+
+        generator = next;
+    }
+
+    let mut remainder = vec![GF8(0); count];
+
+    for d in data {
+        let factor = GF8(*d) + remainder[0];
+
+        remainder.remove(0);
+        remainder.push(GF8(0));
+
+        for (r, g) in remainder.iter_mut().zip(&generator[1..]) {
+            *r = *r + *g * factor;
+        }
+    }
+
+    remainder.iter().map(|r| r.0).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_data() {
+        // 01234567 in version 1-M, padded to 16 codewords
+        let bits: Vec<bool> = "00010000001000000000110001010110011000011"
+            .bytes()
+            .map(|b| b == b'1')
+            .collect();
+
+        assert_eq!(
+            vec![16, 32, 12, 86, 97, 128, 236, 17, 236, 17, 236, 17, 236, 17, 236, 17],
+            data(bits, 128)
+        );
+    }
+
+    #[test]
+    pub fn test_ec_codewords() {
+        // HELLO WORLD in version 1-M
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+
+        assert_eq!(
+            vec![196, 35, 39, 119, 235, 215, 231, 226, 93, 23],
+            ec_codewords(&data, 10)
+        );
+    }
+
+    #[test]
+    pub fn test_interleave() {
+        let block_info = vec![BlockInfo::new(1, 4, 1, 1), BlockInfo::new(1, 5, 2, 1)];
+        let codewords = interleave(&[1, 2, 3], &block_info);
+
+        // Data of both blocks, the second block's last data codeword, then error correction
+        assert_eq!(vec![1, 2, 3], codewords[..3].to_vec());
+        assert_eq!(3 + 3 + 3, codewords.len());
+    }
+}
@@ -0,0 +1,260 @@
+use crate::decode::qr::blocks::{alignment_centers, alignment_location, is_data, y_range};
+use crate::decode::qr::format::{condition, MASK};
+use crate::decode::qr::version::version_information;
+use crate::util::qr::{ECLevel, QRData, QRError};
+
+// Generator polynomial of the BCH code protecting the format information
+const FORMAT_GENERATOR: u32 = 0b101_0011_0111;
+
+// Penalty scores of the mask evaluation
+const PENALTY_RUN: u32 = 3;
+const PENALTY_BLOCK: u32 = 3;
+const PENALTY_FINDER: u32 = 40;
+const PENALTY_BALANCE: u32 = 10;
+
+/// Place the interleaved codewords in a QR Code of the provided version, along with all function patterns
+///
+/// The data modules are visited in the same order the decoder reads them. Every mask pattern is tried,
+/// and the QR Code with the lowest penalty score is returned.
+pub fn matrix(version: u32, level: &ECLevel, codewords: &[u8]) -> Result<QRData, QRError> {
+    let side = 4 * version + 17;
+    let mut template = QRData::new(vec![255; (side * side) as usize], version);
+
+    function_patterns(&mut template)?;
+
+    let positions = positions(&template)?;
+    let bits: Vec<bool> = codewords
+        .iter()
+        .flat_map(|c| (0..8).rev().map(move |i| (c >> i) & 1 == 1))
+        .collect();
+
+    let mut best: Option<(u32, QRData)> = None;
+
+    for mask in 0..8 {
+        let condition = condition(mask).unwrap();
+        let mut data = QRData::new(template.data.clone(), version);
+
+        // Any remainder bits after the last codeword are zero
+        for (i, (x, y)) in positions.iter().enumerate() {
+            let bit = bits.get(i).copied().unwrap_or(false);
+            set(&mut data, *x, *y, bit ^ condition(*x, *y));
+        }
+
+        format_information(&mut data, level, mask);
+
+        let penalty = penalty(&data);
+        debug!("MASK {:03b} PENALTY {}", mask, penalty);
+
+        if !matches!(best, Some((p, _)) if p <= penalty) {
+            best = Some((penalty, data));
+        }
+    }
+
+    Ok(best.unwrap().1)
+}
+
+fn set(data: &mut QRData, x: u32, y: u32, dark: bool) {
+    data.data[(y * data.side + x) as usize] = if dark { 0 } else { 255 };
+}
+
+// Positions of the data modules, in the order in which the bits are placed
+fn positions(data: &QRData) -> Result<Vec<(u32, u32)>, QRError> {
+    let loc = alignment_location(data.version)?;
+    let mut positions = vec![];
+    let mut x = data.side - 1;
+
+    loop {
+        for y in y_range(x, data.side) {
+            for x in [x, x - 1].iter() {
+                if is_data(data, &loc, *x, y) {
+                    positions.push((*x, y));
+                }
+            }
+        }
+
+        if x == 1 {
+            break;
+        }
+
+        x -= 2;
+        if x == 6 {
+            // skip timing pattern
+            x = 5
+        }
+    }
+
+    Ok(positions)
+}
+
+fn function_patterns(data: &mut QRData) -> Result<(), QRError> {
+    let side = data.side;
+
+    // Finder patterns, with the separators around them left light
+    for (left, top) in [(0, 0), (side - 7, 0), (0, side - 7)].iter() {
+        for dy in 0..7_u32 {
+            for dx in 0..7_u32 {
+                let ring = dx.abs_diff(3).max(dy.abs_diff(3));
+                set(data, left + dx, top + dy, ring != 2);
+            }
+        }
+    }
+
+    // Timing patterns
+    for i in 8..side - 8 {
+        set(data, i, 6, i % 2 == 0);
+        set(data, 6, i, i % 2 == 0);
+    }
+
+    // Alignment patterns, except where they would overlap the finder patterns
+    let centers = alignment_centers(data.version)?;
+    let last = centers.last().copied();
+
+    for cx in centers.iter() {
+        for cy in centers.iter() {
+            if (*cx == 6 && (*cy == 6 || Some(*cy) == last)) || (Some(*cx) == last && *cy == 6) {
+                continue;
+            }
+
+            for dy in 0..5_u32 {
+                for dx in 0..5_u32 {
+                    let ring = dx.abs_diff(2).max(dy.abs_diff(2));
+                    set(data, cx - 2 + dx, cy - 2 + dy, ring != 1);
+                }
+            }
+        }
+    }
+
+    // The dark module next to the bottom left finder pattern
+    set(data, 8, side - 8, true);
+
+    // Version information in the bottom left and top right corners
+    if data.version >= 7 {
+        let version = version_information(data.version);
+
+        for i in 0..18 {
+            let bit = (version >> i) & 1 == 1;
+            set(data, side - 11 + i % 3, i / 3, bit);
+            set(data, i / 3, side - 11 + i % 3, bit);
+        }
+    }
+
+    Ok(())
+}
+
+// Place both copies of the format information, in the same order the decoder reads them
+fn format_information(data: &mut QRData, level: &ECLevel, mask: u8) {
+    let side = data.side;
+
+    let level = match level {
+        ECLevel::LOW => 0b01,
+        ECLevel::MEDIUM => 0b00,
+        ECLevel::QUARTILE => 0b11,
+        ECLevel::HIGH => 0b10,
+    };
+
+    let format = u32::from(level << 3 | mask) << 10;
+    let format = format | remainder(format, FORMAT_GENERATOR);
+
+    let bits = (0..15).map(|i| ((format >> (14 - i)) & 1) as u8 ^ MASK[i] == 1);
+
+    let first = (0..9)
+        .filter(|x| *x != 6)
+        .map(|x| (x, 8))
+        .chain((0..8).rev().filter(|y| *y != 6).map(|y| (8, y)));
+
+    let second = (side - 7..side)
+        .rev()
+        .map(|y| (8, y))
+        .chain((side - 8..side).map(|x| (x, 8)));
+
+    for ((x, y), bit) in first.zip(bits.clone()).chain(second.zip(bits)) {
+        set(data, x, y, bit);
+    }
+}
+
+// Remainder of the division of a value by a generator polynomial over GF(2)
+fn remainder(value: u32, generator: u32) -> u32 {
+    let degree = 31 - generator.leading_zeros();
+    let mut remainder = value;
+
+    for i in (degree..32).rev() {
+        if (remainder >> i) & 1 == 1 {
+            remainder ^= generator << (i - degree);
+        }
+    }
+
+    remainder
+}
+
+// Penalty score of a masked QR Code, a lower score makes it easier to read
+fn penalty(data: &QRData) -> u32 {
+    let side = data.side;
+    let mut penalty = 0;
+
+    let rows = (0..side).map(|y| (0..side).map(|x| data[[x, y]]).collect::<Vec<u8>>());
+    let columns = (0..side).map(|x| (0..side).map(|y| data[[x, y]]).collect::<Vec<u8>>());
+
+    for line in rows.chain(columns) {
+        // Runs of five or more modules of the same color
+        let mut start = 0;
+        for end in 1..=line.len() {
+            if end == line.len() || line[end] != line[start] {
+                if end - start >= 5 {
+                    penalty += PENALTY_RUN + (end - start - 5) as u32;
+                }
+                start = end;
+            }
+        }
+
+        // Patterns looking like a finder pattern, with four light modules on either side
+        let padded: Vec<u8> = [0; 4]
+            .iter()
+            .chain(line.iter())
+            .chain([0; 4].iter())
+            .copied()
+            .collect();
+
+        for window in padded.windows(11) {
+            if window == [1, 0, 1, 1, 1, 0, 1, 0, 0, 0, 0]
+                || window == [0, 0, 0, 0, 1, 0, 1, 1, 1, 0, 1]
+            {
+                penalty += PENALTY_FINDER;
+            }
+        }
+    }
+
+    // Blocks of 2 by 2 modules of the same color
+    for y in 0..side - 1 {
+        for x in 0..side - 1 {
+            let color = data[[x, y]];
+            if data[[x + 1, y]] == color
+                && data[[x, y + 1]] == color
+                && data[[x + 1, y + 1]] == color
+            {
+                penalty += PENALTY_BLOCK;
+            }
+        }
+    }
+
+    // Deviation from an equal number of dark and light modules, in steps of 5%
+    let dark = data.data.iter().filter(|p| **p == 0).count() as u32;
+    let total = side * side;
+    penalty +=
+        PENALTY_BALANCE * ((20 * dark).max(10 * total) - (20 * dark).min(10 * total)) / total;
+
+    penalty
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_remainder() {
+        // Format information of level M with mask 5
+        assert_eq!(
+            0b00101 << 10 | 0b0011011100,
+            0b00101 << 10 | remainder(0b00101 << 10, FORMAT_GENERATOR)
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use crate::decode::qr::block_info;
+use crate::util::qr::{ECLevel, QRData, QRError};
+
+use image::{GrayImage, Luma};
+
+mod codewords;
+mod matrix;
+mod segments;
+
+// ECI assignment number of UTF-8
+const UTF8_ECI: u32 = 26;
+
+/// Encode data into a QR Code
+///
+/// The smallest version that fits the data at the chosen error correction level is used.
+/// The data is split into numeric, alphanumeric and byte segments so that it takes up as few bits as possible,
+/// and of the eight mask patterns the one with the lowest penalty score is applied.
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// use bardecoder::encode::QREncoder;
+/// use bardecoder::ECLevel;
+///
+/// let data = QREncoder::new(ECLevel::HIGH).encode("https://example.com/1234567890").unwrap();
+///
+/// assert_eq!(data.version, 4);
+/// assert_eq!(data.side, 33);
+/// ```
+pub struct QREncoder {
+    level: ECLevel,
+}
+
+impl QREncoder {
+    /// Construct a new QREncoder for the provided error correction level
+    pub fn new(level: ECLevel) -> QREncoder {
+        QREncoder { level }
+    }
+
+    /// Encode text into a QR Code
+    ///
+    /// Text that is not plain ASCII is encoded as UTF-8, preceded by the ECI designator for UTF-8
+    /// since readers interpret bytes as ISO 8859-1 otherwise.
+    pub fn encode(&self, text: &str) -> Result<QRData, QRError> {
+        let eci = match text.is_ascii() {
+            true => None,
+            false => Some(UTF8_ECI),
+        };
+
+        self.encode_data(text.as_bytes(), eci)
+    }
+
+    /// Encode raw bytes into a QR Code, without any ECI designator
+    pub fn encode_bytes(&self, bytes: &[u8]) -> Result<QRData, QRError> {
+        self.encode_data(bytes, None)
+    }
+
+    fn encode_data(&self, bytes: &[u8], eci: Option<u32>) -> Result<QRData, QRError> {
+        for version in 1..=40 {
+            let block_info = block_info(version, &self.level)?;
+            let capacity = 8 * block_info
+                .iter()
+                .map(|bi| bi.data_per as usize)
+                .sum::<usize>();
+
+            let mut bits = vec![];
+
+            if let Some(eci) = eci {
+                segments::push(&mut bits, 0b0111, 4);
+                segments::push(&mut bits, eci, 8);
+            }
+
+            for segment in segments::segments(bytes, version)? {
+                segment.bits(&mut bits, version)?;
+            }
+
+            if bits.len() > capacity {
+                continue;
+            }
+
+            debug!("ENCODING {} BITS IN VERSION {}", bits.len(), version);
+
+            let data = codewords::data(bits, capacity);
+            let codewords = codewords::interleave(&data, &block_info);
+
+            return matrix::matrix(version, &self.level, &codewords);
+        }
+
+        Err(QRError {
+            msg: format!(
+                "{} bytes of data do not fit in a QR Code with level {:?}",
+                bytes.len(),
+                self.level
+            ),
+        })
+    }
+}
+
+/// Render a QR Code into an image, with black modules of the provided size in pixels
+///
+/// The quiet zone around the QR Code is provided in modules, the QR Code specification requires at least 4.
+pub fn render(data: &QRData, module_size: u32, quiet_zone: u32) -> GrayImage {
+    let size = (data.side + 2 * quiet_zone) * module_size;
+
+    GrayImage::from_fn(size, size, |x, y| {
+        let x = (x / module_size).checked_sub(quiet_zone);
+        let y = (y / module_size).checked_sub(quiet_zone);
+
+        match (x, y) {
+            (Some(x), Some(y)) if x < data.side && y < data.side && data[[x, y]] == 1 => Luma([0]),
+            _ => Luma([255]),
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::decode::{Decode, QRDecoder, QRDecoderWithInfo};
+
+    #[test]
+    pub fn test_encode() {
+        for level in [
+            ECLevel::LOW,
+            ECLevel::MEDIUM,
+            ECLevel::QUARTILE,
+            ECLevel::HIGH,
+        ]
+        .iter()
+        {
+            let encoder = QREncoder::new(*level);
+
+            for text in [
+                "01234567",
+                "HELLO WORLD",
+                "Version 7 and up carry version information, so this text is a little longer than the others",
+                "Ünïcödé",
+            ]
+            .iter()
+            {
+                let data = encoder.encode(text).unwrap();
+                assert_eq!(Ok(String::from(*text)), QRDecoder::new().decode(Ok(data)));
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_smallest_version() {
+        // Version 1 holds 41 digits at level L, and 17 digits at level H
+        let data = QREncoder::new(ECLevel::LOW)
+            .encode(&"1".repeat(41))
+            .unwrap();
+        assert_eq!(1, data.version);
+
+        let data = QREncoder::new(ECLevel::LOW)
+            .encode(&"1".repeat(42))
+            .unwrap();
+        assert_eq!(2, data.version);
+
+        let data = QREncoder::new(ECLevel::HIGH)
+            .encode(&"1".repeat(17))
+            .unwrap();
+        assert_eq!(1, data.version);
+
+        let (_, info) = QRDecoderWithInfo::new()
+            .decode(QREncoder::new(ECLevel::QUARTILE).encode("HELLO WORLD"))
+            .unwrap();
+        assert_eq!(ECLevel::QUARTILE, info.ec_level);
+    }
+
+    #[test]
+    pub fn test_too_long() {
+        assert!(QREncoder::new(ECLevel::HIGH)
+            .encode_bytes(&[0; 1274])
+            .is_err());
+
+        let data = QREncoder::new(ECLevel::HIGH)
+            .encode_bytes(&[0; 1273])
+            .unwrap();
+        assert_eq!(40, data.version);
+    }
+
+    #[test]
+    pub fn test_render() {
+        let data = QREncoder::new(ECLevel::LOW).encode("01234567").unwrap();
+        let image = render(&data, 3, 4);
+
+        assert_eq!((87, 87), image.dimensions());
+
+        // Quiet zone, then the top left finder pattern
+        assert_eq!(255, image.get_pixel(11, 11)[0]);
+        assert_eq!(0, image.get_pixel(12, 12)[0]);
+        assert_eq!(255, image.get_pixel(15, 15)[0]);
+        assert_eq!(0, image.get_pixel(18, 18)[0]);
+    }
+}
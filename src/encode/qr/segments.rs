@@ -0,0 +1,221 @@
+use crate::decode::qr::data::length_bits;
+use crate::util::qr::{QRError, QRMode};
+
+const ALPHANUMERIC: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+const MODES: [QRMode; 3] = [QRMode::NUMERIC, QRMode::ALPHANUMERIC, QRMode::BYTE];
+
+/// Part of the data that is encoded in a single mode
+#[derive(Debug, PartialEq, Eq)]
+pub struct Segment<'a> {
+    pub mode: QRMode,
+    pub data: &'a [u8],
+}
+
+impl<'a> Segment<'a> {
+    /// Append the mode indicator, character count and encoded data of this segment
+    pub fn bits(&self, bits: &mut Vec<bool>, version: u32) -> Result<(), QRError> {
+        let (indicator, count) = match self.mode {
+            QRMode::NUMERIC => (0b0001, self.data.len()),
+            QRMode::ALPHANUMERIC => (0b0010, self.data.len()),
+            QRMode::BYTE => (0b0100, self.data.len()),
+            QRMode::KANJI => (0b1000, self.data.len() / 2),
+        };
+
+        push(bits, indicator, 4);
+        push(bits, count as u32, length_bits(self.mode, version)?);
+
+        match self.mode {
+            QRMode::NUMERIC => {
+                // Groups of three digits in 10 bits, with the last one or two digits in 4 or 7 bits
+                for group in self.data.chunks(3) {
+                    let value = group.iter().fold(0, |v, d| 10 * v + u32::from(d - b'0'));
+                    push(bits, value, [0, 4, 7, 10][group.len()]);
+                }
+            }
+            QRMode::ALPHANUMERIC => {
+                // Pairs of characters in 11 bits, with the last character in 6 bits
+                for pair in self.data.chunks(2) {
+                    let value = pair
+                        .iter()
+                        .fold(0, |v, c| 45 * v + alphanumeric(*c).unwrap());
+                    push(bits, value, [0, 6, 11][pair.len()]);
+                }
+            }
+            _ => {
+                for byte in self.data {
+                    push(bits, u32::from(*byte), 8);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Append the lowest bits of a value, most significant bit first
+pub fn push(bits: &mut Vec<bool>, value: u32, count: u8) {
+    bits.extend((0..count).rev().map(|i| (value >> i) & 1 == 1));
+}
+
+/// Split data into numeric, alphanumeric and byte segments, so that the encoded data has the fewest bits for the version
+///
+/// For every byte the shortest encoding of the data up to there is kept for each mode that the last segment can be in,
+/// with all lengths in sixths of a bit since numeric and alphanumeric characters take up a fraction of a bit more than a whole.
+/// Characters of more than one byte are always encoded in byte mode, so segments never split them.
+pub fn segments(data: &[u8], version: u32) -> Result<Vec<Segment<'_>>, QRError> {
+    let mut headers = [0; 3];
+    for (header, mode) in headers.iter_mut().zip(MODES.iter()) {
+        *header = 6 * (4 + u32::from(length_bits(*mode, version)?));
+    }
+
+    let mut costs: [Option<u32>; 3] = [Some(headers[0]), Some(headers[1]), Some(headers[2])];
+
+    // For every byte and the mode of the segment after it, the mode the byte was encoded in
+    let mut modes: Vec<[usize; 3]> = vec![];
+
+    for byte in data {
+        let mut encoded = [None; 3];
+        for (mode, cost) in encoded.iter_mut().enumerate() {
+            *cost = match (costs[mode], cost_of(mode, *byte)) {
+                (Some(total), Some(cost)) => Some(total + cost),
+                _ => None,
+            };
+        }
+
+        // Start a new segment after this byte, rounding the previous segment up to whole bits
+        let mut from = [0, 1, 2];
+        costs = encoded;
+
+        for (mode, header) in headers.iter().enumerate() {
+            for (previous, cost) in encoded.iter().enumerate() {
+                let switched = match cost {
+                    Some(cost) => 6 * whole_bits(*cost) + header,
+                    None => continue,
+                };
+
+                if !matches!(costs[mode], Some(cost) if cost <= switched) {
+                    costs[mode] = Some(switched);
+                    from[mode] = previous;
+                }
+            }
+        }
+
+        modes.push(from);
+    }
+
+    let mut mode = (0..3)
+        .filter(|mode| costs[*mode].is_some())
+        .min_by_key(|mode| whole_bits(costs[*mode].unwrap()))
+        .unwrap();
+
+    let mut byte_modes = vec![0; data.len()];
+    for (i, from) in modes.iter().enumerate().rev() {
+        mode = from[mode];
+        byte_modes[i] = mode;
+    }
+
+    let mut segments: Vec<Segment> = vec![];
+    let mut start = 0;
+
+    for end in 1..=data.len() {
+        if end == data.len() || byte_modes[end] != byte_modes[start] {
+            segments.push(Segment {
+                mode: MODES[byte_modes[start]],
+                data: &data[start..end],
+            });
+            start = end;
+        }
+    }
+
+    Ok(segments)
+}
+
+// Length in sixths of a bit of a byte in the provided mode, if the mode can encode it
+fn cost_of(mode: usize, byte: u8) -> Option<u32> {
+    match mode {
+        0 if byte.is_ascii_digit() => Some(20),
+        1 if alphanumeric(byte).is_some() => Some(33),
+        2 => Some(48),
+        _ => None,
+    }
+}
+
+fn alphanumeric(byte: u8) -> Option<u32> {
+    ALPHANUMERIC
+        .iter()
+        .position(|c| *c == byte)
+        .map(|p| p as u32)
+}
+
+// Number of whole bits taken up by a length in sixths of a bit, rounded up
+#[allow(clippy::manual_div_ceil)]
+fn whole_bits(sixths: u32) -> u32 {
+    (sixths + 5) / 6
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn modes(data: &str, version: u32) -> Vec<(QRMode, &str)> {
+        segments(data.as_bytes(), version)
+            .unwrap()
+            .iter()
+            .map(|s| (s.mode, std::str::from_utf8(s.data).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    pub fn test_segments() {
+        assert_eq!(
+            vec![(QRMode::NUMERIC, "0123456789")],
+            modes("0123456789", 1)
+        );
+        assert_eq!(
+            vec![(QRMode::ALPHANUMERIC, "HELLO WORLD")],
+            modes("HELLO WORLD", 1)
+        );
+        assert_eq!(vec![(QRMode::BYTE, "hello")], modes("hello", 1));
+        assert!(modes("", 1).is_empty());
+
+        // A short run of digits is not worth a segment of its own
+        assert_eq!(vec![(QRMode::BYTE, "abc123def")], modes("abc123def", 1));
+
+        // A long one is
+        assert_eq!(
+            vec![
+                (QRMode::BYTE, "abc"),
+                (QRMode::NUMERIC, "123456789012"),
+                (QRMode::BYTE, "def"),
+            ],
+            modes("abc123456789012def", 1)
+        );
+
+        // Multi-byte characters stay in byte mode
+        assert_eq!(
+            vec![(QRMode::BYTE, "é"), (QRMode::NUMERIC, "12345678901234")],
+            modes("é12345678901234", 1)
+        );
+    }
+
+    #[test]
+    pub fn test_bits() {
+        let mut bits = vec![];
+        Segment {
+            mode: QRMode::NUMERIC,
+            data: b"01234567",
+        }
+        .bits(&mut bits, 1)
+        .unwrap();
+
+        let expected = "0001 0000001000 0000001100 0101011001 1000011";
+        let expected: Vec<bool> = expected
+            .bytes()
+            .filter(|b| *b != b' ')
+            .map(|b| b == b'1')
+            .collect();
+
+        assert_eq!(expected, bits);
+    }
+}
@@ -0,0 +1,67 @@
+use super::Extract;
+
+use crate::util::aztec::{AztecData, AztecError, AztecLocation};
+
+use image::GrayImage;
+
+/// Extract Aztec Data from a preprocessed image
+///
+/// The module grid is determined by the corners of the mode message ring around the bullseye.
+/// Data is extracted by sampling the center pixel of the estimated module locations.
+pub struct AztecExtractor {}
+
+impl AztecExtractor {
+    /// Construct a new AztecExtractor
+    pub fn new() -> AztecExtractor {
+        AztecExtractor {}
+    }
+}
+
+impl Extract<GrayImage, AztecLocation, AztecData, AztecError> for AztecExtractor {
+    fn extract(&self, prepared: &GrayImage, loc: AztecLocation) -> Result<AztecData, AztecError> {
+        debug!("AZTEC LOC {:?}", loc);
+
+        let size = loc.size();
+
+        // Distance from the center to the mode message ring
+        let ring = if loc.compact { 5.0 } else { 7.0 };
+
+        let dx = (loc.top_right - loc.top_left) / (2.0 * ring);
+        let dy = (loc.bottom_left - loc.top_left) / (2.0 * ring);
+
+        // Position of the top left module of the mode message ring in the symbol
+        let offset = f64::from(size / 2) - ring;
+
+        let mut data = vec![];
+
+        for y in 0..size {
+            let line = loc.top_left + (f64::from(y) - offset) * dy;
+
+            for x in 0..size {
+                let p = line + (f64::from(x) - offset) * dx;
+                let px = p.x.round();
+                let py = p.y.round();
+
+                if px < 0.0
+                    || py < 0.0
+                    || px >= f64::from(prepared.width())
+                    || py >= f64::from(prepared.height())
+                {
+                    return Err(AztecError {
+                        msg: String::from("Aztec Code extends beyond the image"),
+                    });
+                }
+
+                data.push(prepared.get_pixel(px as u32, py as u32)[0]);
+            }
+        }
+
+        Ok(AztecData::new(
+            data,
+            size,
+            loc.compact,
+            loc.layers,
+            loc.data_codewords,
+        ))
+    }
+}
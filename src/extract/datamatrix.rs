@@ -0,0 +1,59 @@
+use super::Extract;
+
+use crate::util::datamatrix::{DataMatrixData, DataMatrixError, DataMatrixLocation};
+
+use image::GrayImage;
+
+/// Extract Data Matrix Data from a preprocessed image
+///
+/// The module grid is determined by the corners of the L-shaped finder pattern.
+/// Data is extracted by sampling the center pixel of the estimated module locations.
+pub struct DataMatrixExtractor {}
+
+impl DataMatrixExtractor {
+    /// Construct a new DataMatrixExtractor
+    pub fn new() -> DataMatrixExtractor {
+        DataMatrixExtractor {}
+    }
+}
+
+impl Extract<GrayImage, DataMatrixLocation, DataMatrixData, DataMatrixError>
+    for DataMatrixExtractor
+{
+    fn extract(
+        &self,
+        prepared: &GrayImage,
+        loc: DataMatrixLocation,
+    ) -> Result<DataMatrixData, DataMatrixError> {
+        debug!("DATA MATRIX LOC {:?}", loc);
+
+        let dx = (loc.bottom_right - loc.bottom_left) / f64::from(loc.columns - 1);
+        let dy = (loc.bottom_left - loc.top_left) / f64::from(loc.rows - 1);
+
+        let mut data = vec![];
+
+        for y in 0..loc.rows {
+            let line = loc.top_left + f64::from(y) * dy;
+
+            for x in 0..loc.columns {
+                let p = line + f64::from(x) * dx;
+                let px = p.x.round();
+                let py = p.y.round();
+
+                if px < 0.0
+                    || py < 0.0
+                    || px >= f64::from(prepared.width())
+                    || py >= f64::from(prepared.height())
+                {
+                    return Err(DataMatrixError {
+                        msg: String::from("Data Matrix Code extends beyond the image"),
+                    });
+                }
+
+                data.push(prepared.get_pixel(px as u32, py as u32)[0]);
+            }
+        }
+
+        Ok(DataMatrixData::new(data, loc.rows, loc.columns))
+    }
+}
@@ -0,0 +1,35 @@
+use super::{scan_line, Extract};
+
+use crate::util::ean::{EANData, EANError, EANLocation};
+
+use image::GrayImage;
+
+/// Extract EAN Data from a preprocessed image
+///
+/// The pixels are sampled along the scan line between the first and last bar of the barcode, and of the add-on if present.
+/// Data is extracted as the widths of the resulting runs of dark and light pixels.
+pub struct EANExtractor {}
+
+impl EANExtractor {
+    /// Construct a new EANExtractor
+    pub fn new() -> EANExtractor {
+        EANExtractor {}
+    }
+}
+
+impl Extract<GrayImage, EANLocation, EANData, EANError> for EANExtractor {
+    fn extract(&self, prepared: &GrayImage, loc: EANLocation) -> Result<EANData, EANError> {
+        debug!("EAN LOC {:?}", loc);
+
+        let runs = scan_line(prepared, loc.start, loc.end).map_err(|msg| EANError { msg })?;
+
+        let addon = match loc.addon {
+            Some((start, end)) => {
+                Some(scan_line(prepared, start, end).map_err(|msg| EANError { msg })?)
+            }
+            None => None,
+        };
+
+        Ok(EANData::new(runs, addon))
+    }
+}
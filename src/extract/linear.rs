@@ -0,0 +1,40 @@
+use super::{scan_line, Extract};
+
+use crate::util::linear::{LinearData, LinearError, LinearLocation};
+
+use image::GrayImage;
+
+/// Extract Linear Data from a preprocessed image
+///
+/// The pixels are sampled along the scan line between the first and last bar of the barcode.
+/// Data is extracted as the widths of the resulting runs of dark and light pixels, which works the same way for all linear symbologies.
+/// The lower rows of a stacked barcode are extracted along their own scan lines.
+pub struct LinearExtractor {}
+
+impl LinearExtractor {
+    /// Construct a new LinearExtractor
+    pub fn new() -> LinearExtractor {
+        LinearExtractor {}
+    }
+}
+
+impl Extract<GrayImage, LinearLocation, LinearData, LinearError> for LinearExtractor {
+    fn extract(
+        &self,
+        prepared: &GrayImage,
+        loc: LinearLocation,
+    ) -> Result<LinearData, LinearError> {
+        debug!("LINEAR LOC {:?}", loc);
+
+        let runs = scan_line(prepared, loc.start, loc.end).map_err(|msg| LinearError { msg })?;
+
+        let mut data = LinearData::new(loc.symbology, runs);
+
+        for (start, end) in loc.stacked {
+            data.stacked
+                .push(scan_line(prepared, start, end).map_err(|msg| LinearError { msg })?);
+        }
+
+        Ok(data)
+    }
+}
@@ -1,8 +1,22 @@
 //! Extract data from an image
 
+use crate::util::{Delta, Point};
+
+use image::GrayImage;
+
+mod aztec;
+mod datamatrix;
+mod ean;
+mod linear;
+mod pdf417;
 mod qr;
 
-pub use self::qr::QRExtractor;
+pub use self::aztec::AztecExtractor;
+pub use self::datamatrix::DataMatrixExtractor;
+pub use self::ean::EANExtractor;
+pub use self::linear::LinearExtractor;
+pub use self::pdf417::PDF417Extractor;
+pub use self::qr::{MicroQRExtractor, QRExtractor, RMQRExtractor, Sampling};
 
 /// Extract data from a prepared image, given the location as determined by the [`Detect`] step
 ///
@@ -43,3 +57,52 @@ pub trait Extract<PREPD, LOC, DATA, ERROR> {
     /// Does the actual extracting
     fn extract(&self, prepared: &PREPD, loc: LOC) -> Result<DATA, ERROR>;
 }
+
+// Sample the pixels from start to end in steps of one pixel, and count the widths of the runs
+// Used by the extractors for linear barcodes, which must start and end with a bar
+fn scan_line(prepared: &GrayImage, start: Point, end: Point) -> Result<Vec<u32>, String> {
+    let distance = ((end.x - start.x).powi(2) + (end.y - start.y).powi(2)).sqrt();
+    let samples = distance.round() as u32 + 1;
+
+    let step = match samples {
+        1 => Delta { dx: 0.0, dy: 0.0 },
+        _ => (end - start) / f64::from(samples - 1),
+    };
+
+    let mut runs: Vec<u32> = vec![];
+    let mut previous = None;
+
+    for i in 0..samples {
+        let p = start + f64::from(i) * step;
+        let px = p.x.round();
+        let py = p.y.round();
+
+        if px < 0.0
+            || py < 0.0
+            || px >= f64::from(prepared.width())
+            || py >= f64::from(prepared.height())
+        {
+            return Err(String::from("Barcode extends beyond the image"));
+        }
+
+        let dark = prepared.get_pixel(px as u32, py as u32)[0] == 0;
+
+        if previous == Some(dark) {
+            *runs.last_mut().unwrap() += 1;
+        } else {
+            runs.push(1);
+            previous = Some(dark);
+        }
+
+        // The first run must be a bar
+        if i == 0 && !dark {
+            return Err(String::from("Scan line does not start on a bar"));
+        }
+    }
+
+    if previous != Some(true) {
+        return Err(String::from("Scan line does not end on a bar"));
+    }
+
+    Ok(runs)
+}
@@ -0,0 +1,308 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use super::{scan_line, Extract};
+
+use crate::util::pdf417::{cluster, PDF417Data, PDF417Error, PDF417Location, PDF417Symbols};
+
+use image::GrayImage;
+
+const START: [u32; 8] = [8, 1, 1, 1, 1, 1, 1, 3];
+const STOP: [u32; 9] = [7, 1, 1, 3, 1, 1, 1, 2, 1];
+
+/// Extract PDF417 Data from a preprocessed image
+///
+/// Every row of pixels between the top and bottom scan lines of the location is read as a sequence of symbol characters,
+/// from the start pattern onwards and from the stop pattern backwards, so a damaged character only loses the characters between it and the next damaged one.
+/// The rows of the PDF417 Code are told apart by the cluster number of the symbol characters, which repeats every three rows,
+/// together with the row indicators at either end of the row, which number the groups of three rows.
+/// The row indicators also hold the number of rows and columns and the error correction level.
+///
+/// Each codeword is the value read most often for its row and column. Codewords that could not be read at all are returned as erasures.
+///
+/// The bar and space patterns of the symbol characters are listed in ISO/IEC 15438 and are not included in this library,
+/// so they have to be provided as [`PDF417Symbols`].
+///
+/// [`PDF417Symbols`]: ../util/pdf417/struct.PDF417Symbols.html
+pub struct PDF417Extractor {
+    symbols: PDF417Symbols,
+}
+
+impl PDF417Extractor {
+    /// Construct a new PDF417Extractor, reading the symbol characters with the provided patterns
+    pub fn new(symbols: PDF417Symbols) -> PDF417Extractor {
+        PDF417Extractor { symbols }
+    }
+}
+
+// Column of a symbol character along a scan line, counted from the start or the stop pattern. Column 0 is the row indicator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Column {
+    Left(u32),
+    Right(u32),
+}
+
+// Size and error correction level of the PDF417 Code, as held by the row indicators
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Indicator {
+    // Number of rows divided by three
+    RowGroups,
+
+    // Error correction level times three, plus the remainder of the number of rows divided by three
+    LevelAndRows,
+
+    // Number of columns
+    Columns,
+}
+
+// Number of times each value was read, per key
+type Votes<K> = HashMap<K, HashMap<u32, u32>>;
+
+impl Extract<GrayImage, PDF417Location, PDF417Data, PDF417Error> for PDF417Extractor {
+    fn extract(
+        &self,
+        prepared: &GrayImage,
+        loc: PDF417Location,
+    ) -> Result<PDF417Data, PDF417Error> {
+        debug!("PDF417 LOC {:?}", loc);
+
+        let mut codewords: Votes<(u32, Column)> = HashMap::new();
+        let mut indicators: Votes<Indicator> = HashMap::new();
+
+        let (top, bottom) = (loc.top, loc.bottom);
+        let height = (bottom.0.y - top.0.y).round().max(0.0) as u32;
+
+        for y in 0..=height {
+            let t = match height {
+                0 => 0.0,
+                _ => f64::from(y) / f64::from(height),
+            };
+
+            let start = top.0 + t * (bottom.0 - top.0);
+            let end = top.1 + t * (bottom.1 - top.1);
+
+            let chars = match scan_line(prepared, start, end) {
+                Ok(runs) => read_line(runs, &self.symbols),
+                Err(_) => continue,
+            };
+
+            // Characters of another cluster than most are misread, or straddle two rows
+            let line_cluster = match majority(chars.iter().map(|(_, cluster, _)| (*cluster, 1))) {
+                Some(line_cluster) => line_cluster,
+                None => continue,
+            };
+
+            let chars: Vec<(Column, u32)> = chars
+                .into_iter()
+                .filter(|(_, cluster, _)| *cluster == line_cluster)
+                .map(|(column, _, value)| (column, value))
+                .collect();
+
+            let indicator = |column| {
+                chars
+                    .iter()
+                    .find(|(c, _)| *c == column)
+                    .map(|(_, value)| *value)
+            };
+
+            let left = indicator(Column::Left(0));
+            let right = indicator(Column::Right(0));
+
+            let row_group = match (left, right) {
+                (Some(left), Some(right)) if left / 30 != right / 30 => continue,
+                (Some(value), _) | (_, Some(value)) => value / 30,
+                (None, None) => continue,
+            };
+
+            let row = 3 * row_group + line_cluster / 3;
+
+            let (left_indicator, right_indicator) = match line_cluster {
+                0 => (Indicator::RowGroups, Indicator::Columns),
+                3 => (Indicator::LevelAndRows, Indicator::RowGroups),
+                _ => (Indicator::Columns, Indicator::LevelAndRows),
+            };
+
+            if let Some(left) = left {
+                vote(&mut indicators, left_indicator, left % 30);
+            }
+
+            if let Some(right) = right {
+                vote(&mut indicators, right_indicator, right % 30);
+            }
+
+            for (column, value) in chars {
+                match column {
+                    Column::Left(0) | Column::Right(0) => {}
+                    _ => vote(&mut codewords, (row, column), value),
+                }
+            }
+        }
+
+        let read = |indicator| {
+            indicators
+                .get(&indicator)
+                .and_then(|votes| majority(votes.iter().map(|(value, count)| (*value, *count))))
+        };
+
+        let (row_groups, level_and_rows, columns) = match (
+            read(Indicator::RowGroups),
+            read(Indicator::LevelAndRows),
+            read(Indicator::Columns),
+        ) {
+            (Some(row_groups), Some(level_and_rows), Some(columns)) => {
+                (row_groups, level_and_rows, columns + 1)
+            }
+            _ => {
+                return Err(PDF417Error {
+                    msg: String::from("Could not read the row indicators of the PDF417 Code"),
+                })
+            }
+        };
+
+        let rows = 3 * row_groups + level_and_rows % 3 + 1;
+        let ec_level = level_and_rows / 3;
+
+        debug!(
+            "PDF417 OF {} ROWS, {} COLUMNS AND ERROR CORRECTION LEVEL {}",
+            rows, columns, ec_level
+        );
+
+        let mut data = Vec::with_capacity((rows * columns) as usize);
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let values = [
+                    codewords.get(&(row, Column::Left(column + 1))),
+                    codewords.get(&(row, Column::Right(columns - column))),
+                ];
+
+                data.push(majority(values.iter().flatten().flat_map(|votes| {
+                    votes.iter().map(|(value, count)| (*value, *count))
+                })));
+            }
+        }
+
+        Ok(PDF417Data::new(data, rows, columns, ec_level))
+    }
+}
+
+// Read the symbol characters along a scan line as their column, cluster and value
+//
+// The characters are read from the start pattern until one can not be read,
+// and then from the stop pattern back to the last character that was read
+fn read_line(mut runs: Vec<u32>, symbols: &PDF417Symbols) -> Vec<(Column, u32, u32)> {
+    if runs.len() < START.len() + STOP.len() {
+        return vec![];
+    }
+
+    // Upside down
+    if modules(&runs[..START.len()], 17) != START {
+        runs.reverse();
+
+        if modules(&runs[..START.len()], 17) != START {
+            return vec![];
+        }
+    }
+
+    let mut chars = vec![];
+
+    let mut i = START.len();
+    let mut module = f64::from(runs[..i].iter().sum::<u32>()) / 17.0;
+    let mut column = 0;
+
+    while i + 8 + STOP.len() <= runs.len() {
+        match read_char(&runs[i..i + 8], module, symbols) {
+            Some((cluster, value)) => chars.push((Column::Left(column), cluster, value)),
+            None => break,
+        }
+
+        module = f64::from(runs[i..i + 8].iter().sum::<u32>()) / 17.0;
+        i += 8;
+        column += 1;
+    }
+
+    let stop = runs.len() - STOP.len();
+
+    if i == stop || modules(&runs[stop..], 18) != STOP {
+        return chars;
+    }
+
+    let mut j = stop;
+    let mut module = f64::from(runs[stop..].iter().sum::<u32>()) / 18.0;
+    let mut column = 0;
+
+    while j >= i + 8 {
+        match read_char(&runs[j - 8..j], module, symbols) {
+            Some((cluster, value)) => chars.push((Column::Right(column), cluster, value)),
+            None => break,
+        }
+
+        module = f64::from(runs[j - 8..j].iter().sum::<u32>()) / 17.0;
+        j -= 8;
+        column += 1;
+    }
+
+    chars
+}
+
+// Read a symbol character of 17 modules as its cluster and value, if it has the expected width
+fn read_char(runs: &[u32], module: f64, symbols: &PDF417Symbols) -> Option<(u32, u32)> {
+    let width = f64::from(runs.iter().sum::<u32>());
+
+    if (width - 17.0 * module).abs() >= 1.5 * module {
+        return None;
+    }
+
+    let widths = modules(runs, 17);
+
+    if widths.contains(&0) {
+        return None;
+    }
+
+    let pattern = widths.iter().enumerate().fold(0, |pattern, (i, width)| {
+        let bar = (i % 2 == 0) as u32;
+        (0..*width).fold(pattern, |pattern, _| pattern << 1 | bar)
+    });
+
+    let value = symbols.value(pattern)?;
+
+    Some((cluster(&widths), value))
+}
+
+// Widths of the runs in modules, rounding the edges between the runs to the nearest module
+fn modules(runs: &[u32], total: u32) -> Vec<u32> {
+    let sum = f64::from(runs.iter().sum::<u32>());
+
+    let edges: Vec<u32> = std::iter::once(0)
+        .chain(runs.iter().scan(0, |edge, run| {
+            *edge += run;
+            Some((f64::from(*edge) * f64::from(total) / sum).round() as u32)
+        }))
+        .collect();
+
+    edges.windows(2).map(|edge| edge[1] - edge[0]).collect()
+}
+
+fn vote<K>(votes: &mut Votes<K>, key: K, value: u32)
+where
+    K: std::hash::Hash + Eq,
+{
+    *votes.entry(key).or_default().entry(value).or_insert(0) += 1;
+}
+
+// Value with the most votes, the lowest one in case of a tie
+fn majority<I>(votes: I) -> Option<u32>
+where
+    I: Iterator<Item = (u32, u32)>,
+{
+    let mut counts: HashMap<u32, u32> = HashMap::new();
+
+    for (value, count) in votes {
+        *counts.entry(value).or_insert(0) += count;
+    }
+
+    counts
+        .into_iter()
+        .max_by_key(|(value, count)| (*count, Reverse(*value)))
+        .map(|(value, _)| value)
+}
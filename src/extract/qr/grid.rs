@@ -0,0 +1,176 @@
+use super::timing::Timing;
+use super::{center, find_alignment};
+
+use crate::decode::qr::blocks::alignment_centers;
+use crate::util::qr::QRError;
+use crate::util::{Delta, Perspective, Point};
+
+use image::GrayImage;
+
+/// Piecewise transform from the modules of a QR Code to the pixels of the prepared image
+///
+/// The alignment patterns divide the QR Code into cells, each of which is sampled with its own [`Perspective`]
+/// through the centers of the alignment patterns in its corners, or of the finder patterns for the cells next to those.
+/// This follows lens distortion, which bends the grid of larger QR Codes too much for a single transform.
+/// Once refined, the columns and rows of modules are moved to where the timing patterns show they really are.
+pub struct Grid {
+    // Alignment pattern centers between the cells along either axis
+    bounds: Vec<u32>,
+
+    // Transforms of the cells, row by row
+    cells: Vec<Perspective>,
+
+    // Drift of the columns and rows of modules, once refined
+    timing: Option<Timing>,
+}
+
+impl Grid {
+    /// Locate all alignment patterns of the version around where the global transform expects them
+    ///
+    /// Versions below 7 have at most a single alignment pattern, so the global transform is used as is.
+    pub fn new(prepared: &GrayImage, version: u32, global: Perspective) -> Result<Grid, QRError> {
+        let lines = alignment_centers(version)?;
+        if lines.len() < 3 {
+            return Ok(Grid {
+                bounds: vec![],
+                cells: vec![global],
+                timing: None,
+            });
+        }
+
+        let n = lines.len();
+
+        // Modules at the corners of the cells and where they were found in the image
+        let mut modules: Vec<Point> = Vec::with_capacity(n * n);
+        let mut centers: Vec<Point> = Vec::with_capacity(n * n);
+
+        // How far the found patterns are from where the global transform expects them
+        let mut offsets: Vec<Delta> = Vec::with_capacity(n * n);
+
+        for (j, cy) in lines.iter().enumerate() {
+            for (i, cx) in lines.iter().enumerate() {
+                let expected = map(&global, f64::from(*cx), f64::from(*cy));
+
+                // There are no alignment patterns at the finder patterns, the global transform maps the centers
+                // of the finder patterns exactly so those are used instead
+                if (i == 0 || j == 0) && (i + j == 0 || i + j == n - 1) {
+                    let finder = |line: usize| {
+                        if line == 0 {
+                            3.0
+                        } else {
+                            f64::from(lines[line]) + 3.0
+                        }
+                    };
+                    let module = Point {
+                        x: finder(i),
+                        y: finder(j),
+                    };
+
+                    modules.push(module);
+                    centers.push(global.map(module));
+                    offsets.push(Delta { dx: 0.0, dy: 0.0 });
+                    continue;
+                }
+
+                // Distortion changes gradually, so the neighbours tell where to look
+                let mut neighbours = vec![];
+                if i > 0 {
+                    neighbours.push(offsets[j * n + i - 1]);
+                }
+                if j > 0 {
+                    neighbours.push(offsets[(j - 1) * n + i]);
+                }
+
+                let offset = neighbours
+                    .iter()
+                    .fold(Delta { dx: 0.0, dy: 0.0 }, |sum, o| {
+                        sum + *o / neighbours.len() as f64
+                    });
+                let estimate = expected + offset;
+
+                let dx = map(&global, f64::from(*cx) + 1.0, f64::from(*cy)) - expected;
+                let dy = map(&global, f64::from(*cx), f64::from(*cy) + 1.0) - expected;
+
+                let found = match find_alignment(prepared, estimate, dx, dy) {
+                    Some(alignment) => center(prepared, alignment, 1),
+                    None => {
+                        debug!("ALIGNMENT ({}, {}) NOT FOUND NEAR {:?}", cx, cy, estimate);
+                        estimate
+                    }
+                };
+
+                modules.push(Point {
+                    x: f64::from(*cx),
+                    y: f64::from(*cy),
+                });
+                centers.push(found);
+                offsets.push(found - expected);
+            }
+        }
+
+        debug!("ALIGNMENTS {:?}", centers);
+
+        let mut cells = Vec::with_capacity((n - 1) * (n - 1));
+
+        for j in 0..n - 1 {
+            for i in 0..n - 1 {
+                let corners = [
+                    j * n + i,
+                    j * n + i + 1,
+                    (j + 1) * n + i + 1,
+                    (j + 1) * n + i,
+                ];
+
+                cells.push(Perspective::new(
+                    &[
+                        modules[corners[0]],
+                        modules[corners[1]],
+                        modules[corners[2]],
+                        modules[corners[3]],
+                    ],
+                    &[
+                        centers[corners[0]],
+                        centers[corners[1]],
+                        centers[corners[2]],
+                        centers[corners[3]],
+                    ],
+                ));
+            }
+        }
+
+        Ok(Grid {
+            bounds: lines[1..n - 1].to_vec(),
+            cells,
+            timing: None,
+        })
+    }
+
+    /// Trace the timing patterns of a QR Code of the provided size, and correct the drift of the modules along them
+    pub fn refine(&mut self, prepared: &GrayImage, size: u32) {
+        self.timing = Some(Timing::new(prepared, self, size));
+    }
+
+    /// Map a point in modules onto the image, the center of module (x, y) being at (x, y)
+    ///
+    /// Modules outside of the outermost alignment patterns are extrapolated from the cells along the edge.
+    pub fn map(&self, x: f64, y: f64) -> Point {
+        let (x, y) = match &self.timing {
+            Some(timing) => timing.correct(x, y),
+            None => (x, y),
+        };
+
+        let cell = |coord: f64| {
+            self.bounds
+                .iter()
+                .filter(|b| f64::from(**b) <= coord)
+                .count()
+        };
+        let per_side = self.bounds.len() + 1;
+
+        map(&self.cells[cell(y) * per_side + cell(x)], x, y)
+    }
+}
+
+fn map(perspective: &Perspective, x: f64, y: f64) -> Point {
+    perspective.map(Point { x, y })
+}
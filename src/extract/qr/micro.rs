@@ -0,0 +1,65 @@
+use super::super::Extract;
+
+use crate::util::qr::{MicroQRLocation, QRData, QRError};
+
+use image::GrayImage;
+
+/// Extract Micro QR Data from a preprocessed image
+///
+/// The module grid is determined by the finder and the ends of both timing patterns.
+/// Data is extracted by sampling the center pixel of the estimated module locations.
+pub struct MicroQRExtractor {}
+
+impl MicroQRExtractor {
+    /// Construct a new MicroQRExtractor
+    pub fn new() -> MicroQRExtractor {
+        MicroQRExtractor {}
+    }
+}
+
+impl Extract<GrayImage, MicroQRLocation, QRData, QRError> for MicroQRExtractor {
+    fn extract(&self, prepared: &GrayImage, loc: MicroQRLocation) -> Result<QRData, QRError> {
+        debug!("MICRO LOC {:?}", loc);
+
+        let side = 2 * loc.version + 9;
+
+        // The finder center is module (3, 3), the ends of the timing patterns are modules (side - 1, 0) and (0, side - 1)
+        // Solve for the module deltas in both directions
+        let n = f64::from(side - 4);
+        let a = loc.top_right - loc.top_left;
+        let b = loc.bottom_left - loc.top_left;
+
+        let dx = (n * a + 3.0 * b) / (n * n - 9.0);
+        let dy = (3.0 * a + n * b) / (n * n - 9.0);
+
+        let start = loc.top_left - 3.0 * dx - 3.0 * dy;
+
+        debug!("START {:?} DX {:?} DY {:?}", start, dx, dy);
+
+        let mut data = vec![];
+
+        for y in 0..side {
+            let line = start + f64::from(y) * dy;
+
+            for x in 0..side {
+                let p = line + f64::from(x) * dx;
+                let px = p.x.round();
+                let py = p.y.round();
+
+                if px < 0.0
+                    || py < 0.0
+                    || px >= f64::from(prepared.width())
+                    || py >= f64::from(prepared.height())
+                {
+                    return Err(QRError {
+                        msg: String::from("Micro QR Code extends beyond the image"),
+                    });
+                }
+
+                data.push(prepared.get_pixel(px as u32, py as u32)[0]);
+            }
+        }
+
+        Ok(QRData::new_micro(data, loc.version))
+    }
+}
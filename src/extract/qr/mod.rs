@@ -1,7 +1,21 @@
 use super::Extract;
 
+use crate::decode::qr::version::correct;
+
+mod grid;
+mod micro;
+mod rmqr;
+mod soft;
+mod timing;
+
+use self::grid::Grid;
+
+pub use self::micro::MicroQRExtractor;
+pub use self::rmqr::RMQRExtractor;
+
+use crate::prepare::PreparedGrayscale;
 use crate::util::qr::{QRData, QRError, QRLocation};
-use crate::util::{Delta, Point};
+use crate::util::{Delta, Perspective, Point};
 
 use image::GrayImage;
 
@@ -17,17 +31,90 @@ use std::{
 
 /// Extract QR Data from a preprocessed image
 ///
-/// If the version of the QR is higher than 1, this extractor will first try to find the bottom left-most
-/// alignment pattern and adjust for any perspective skewing.
+/// The version estimated from the distance between the finder patterns is easily off by one for larger QR Codes.
+/// From version 7 up, the version is therefore read from the version information next to the finder patterns instead.
+///
+/// If the version of the QR is higher than 1, this extractor will first try to find the bottom right-most
+/// alignment pattern. Together with the centers of the three finder patterns it determines a [`Perspective`] transform,
+/// which corrects for the QR Code being photographed at an angle. Version 1 has no alignment pattern,
+/// so the fourth corner is estimated from the other three instead.
+///
+/// From version 7 up, the QR Code has more alignment patterns, which are all located starting from where the transform
+/// expects them. Each cell between four alignment patterns is then sampled through a transform of its own,
+/// so that lens distortion bending the grid of large QR Codes is followed as well.
+///
+/// Finally the edges of the modules of both timing patterns are traced, which corrects the columns and rows of modules
+/// that drifted from where the transforms expect them, such as when a thermal printer printed some wider than others.
+///
+/// By default, data is extracted by sampling the center pixel of every module, as mapped through the transform.
+/// The confidence in every module is the share of points a quarter module away from its center that agree with it,
+/// so the decoder can treat codewords that were sampled close to the edges of their modules as erasures.
+/// Noisy images are better served by letting a sub-grid of points inside every module vote on its color,
+/// see [`Sampling`] and [`with_sampling`].
+///
+/// When the grayscale image is kept next to the prepared one, see [`WithGrayscale`], the modules are sampled with soft
+/// decisions instead. A small kernel around the center of every module is compared against the gray levels of the dark
+/// and light modules around it, which gives the probability that the module is dark. The confidence then tells how far
+/// that probability is from 0.5, so the decoder knows which codewords are the least reliable. With [`Sampling`] other
+/// than `Center`, the gray value of every module is the mean of its sub-grid of points instead, weighted the same way.
 ///
-/// Data is extracted by sampling the center pixel of the estimated module locations.
-/// These are determined by dividing each row and column into equal parts.
-pub struct QRExtractor {}
+/// [`Perspective`]: ../util/struct.Perspective.html
+/// [`WithGrayscale`]: ../prepare/struct.WithGrayscale.html
+/// [`Sampling`]: enum.Sampling.html
+/// [`with_sampling`]: #method.with_sampling
+pub struct QRExtractor {
+    sampling: Sampling,
+}
+
+/// Strategy to decide the color of every module of a QR Code, from the prepared image or the grayscale one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sampling {
+    /// Sample the center pixel of every module only
+    Center,
+
+    /// Sample an N×N sub-grid of points spread evenly over the middle half of every module, and let the majority decide
+    Majority(u32),
+
+    /// Sample an N×N sub-grid of points spread evenly over the middle half of every module,
+    /// the closer to the center the heavier its vote
+    Weighted(u32),
+}
 
 impl QRExtractor {
-    /// Construct a new QRExtractor
+    /// Construct a new QRExtractor, sampling the center of every module
     pub fn new() -> QRExtractor {
-        QRExtractor {}
+        QRExtractor::with_sampling(Sampling::Center)
+    }
+
+    /// Construct a new QRExtractor with the provided sampling strategy
+    ///
+    /// # Example
+    /// ```
+    /// # extern crate bardecoder;
+    /// # extern crate image;
+    /// use bardecoder::decode::QRDecoder;
+    /// use bardecoder::extract::{QRExtractor, Sampling};
+    ///
+    /// let mut db = bardecoder::default_builder::<image::DynamicImage>();
+    /// db.qr(
+    ///     Box::new(QRExtractor::with_sampling(Sampling::Majority(3))),
+    ///     Box::new(QRDecoder::new()),
+    /// );
+    /// ```
+    pub fn with_sampling(sampling: Sampling) -> QRExtractor {
+        QRExtractor { sampling }
+    }
+
+    /// Determine the perspective transform from the modules of the QR Code to the pixels of the prepared image
+    ///
+    /// Module (x, y) is mapped onto its center in the image, with (0, 0) being the top left module.
+    /// The version of the location is used as is, without reading the version information.
+    pub fn perspective(
+        &self,
+        prepared: &GrayImage,
+        loc: &QRLocation,
+    ) -> Result<Perspective, QRError> {
+        determine_perspective(prepared, loc.version, 17 + loc.version * 4, loc)
     }
 }
 
@@ -35,35 +122,55 @@ impl Extract<GrayImage, QRLocation, QRData, QRError> for QRExtractor {
     fn extract(&self, prepared: &GrayImage, loc: QRLocation) -> Result<QRData, QRError> {
         debug!("LOC {:?}", loc);
 
+        let estimated_version = loc.version;
+        let (loc, grid) = locate(prepared, loc)?;
         let size = 17 + loc.version * 4;
-        let p = determine_perspective(prepared, loc.version, size, &loc)?;
-
-        debug!("PERSPECTIVE {:?}", p);
-
-        let mut start = loc.top_left - 3.0 * p.dy - 3.0 * p.ddy;
-
-        debug!("START {:?}", start);
 
         let mut data = vec![];
+        let mut confidence = vec![];
 
         #[cfg(feature = "debug-images")]
         let mut img = DynamicImage::ImageLuma8(prepared.clone()).to_rgb8();
 
-        let mut dy = p.dy - 3.0 * p.ddy;
-        let mut dx = p.dx - 3.0 * p.ddx;
-        for _ in 0..size {
-            let mut line = start - 3.0 * dx;
+        for y in 0..size {
+            for x in 0..size {
+                let (mx, my) = (f64::from(x), f64::from(y));
+
+                // Modules that fall outside of the image are taken to be light, and left to the error correction
+                let (x, y) = match pixel_at(prepared, grid.map(mx, my)) {
+                    Some(p) => p,
+                    None => {
+                        data.push(255);
+                        confidence.push(0);
+                        continue;
+                    }
+                };
+
+                let (pixel, module_confidence) = match self.sampling {
+                    Sampling::Center => {
+                        let pixel = prepared.get_pixel(x, y)[0];
 
-            for _ in 0..size {
-                let x = line.x.round() as u32;
-                let y = line.y.round() as u32;
-                let pixel = prepared.get_pixel(x, y)[0];
+                        // The closer to the edge of the module its center is sampled,
+                        // the more of the points around it disagree
+                        let agreeing = CONFIDENCE_OFFSETS
+                            .iter()
+                            .filter_map(|(dx, dy)| pixel_at(prepared, grid.map(mx + dx, my + dy)))
+                            .filter(|(x, y)| prepared.get_pixel(*x, *y)[0] == pixel)
+                            .count();
+
+                        (pixel, (255 * agreeing / CONFIDENCE_OFFSETS.len()) as u8)
+                    }
+                    Sampling::Majority(n) => vote(prepared, &grid, mx, my, n, |_, _| 1.0),
+                    Sampling::Weighted(n) => vote(prepared, &grid, mx, my, n, center_weight),
+                };
+
+                confidence.push(module_confidence);
 
                 #[cfg(feature = "debug-images")]
                 {
                     if pixel == 0 {
                         for i in max(0, x.saturating_sub(2))..min(img.dimensions().0, x + 2) {
-                            for j in max(0, y.saturating_sub(2))..min(img.dimensions().0, y + 2) {
+                            for j in max(0, y.saturating_sub(2))..min(img.dimensions().1, y + 2) {
                                 img.put_pixel(i, j, Rgb([255, 0, 0]));
                             }
                         }
@@ -71,12 +178,7 @@ impl Extract<GrayImage, QRLocation, QRData, QRError> for QRExtractor {
                 }
 
                 data.push(pixel);
-                line = line + dx;
             }
-            dx = dx + p.ddx;
-
-            start = start + dy;
-            dy = dy + p.ddy;
         }
 
         #[cfg(feature = "debug-images")]
@@ -87,8 +189,8 @@ impl Extract<GrayImage, QRLocation, QRData, QRError> for QRExtractor {
 
             if let Ok(_) = create_dir_all(tmp.clone()) {
                 tmp.push(format!(
-                    "extract_start_{}_{}_dx_{}_{}_dy_{}_{}.png",
-                    start.x, start.y, dx.dx, dx.dy, dy.dx, dy.dy
+                    "extract_top_left_{}_{}_version_{}.png",
+                    loc.top_left.x, loc.top_left.y, loc.version
                 ));
 
                 if let Ok(_) = DynamicImage::ImageRgb8(img).save(tmp.clone()) {
@@ -97,133 +199,259 @@ impl Extract<GrayImage, QRLocation, QRData, QRError> for QRExtractor {
             }
         }
 
-        Ok(QRData::new(data, loc.version))
+        let mut qr_data = QRData::new(data, loc.version);
+        qr_data.estimated_version = estimated_version;
+        qr_data.confidence = confidence;
+
+        Ok(qr_data)
     }
 }
 
-fn determine_perspective(
-    prepared: &GrayImage,
-    version: u32,
-    size: u32,
-    loc: &QRLocation,
-) -> Result<Perspective, QRError> {
-    let mut dx = loc.top_right - loc.top_left;
-    dx = dx / f64::from(size - 7);
+impl Extract<PreparedGrayscale, QRLocation, QRData, QRError> for QRExtractor {
+    fn extract(&self, images: &PreparedGrayscale, loc: QRLocation) -> Result<QRData, QRError> {
+        debug!("LOC {:?}", loc);
 
-    let mut dy = loc.bottom_left - loc.top_left;
-    dy = dy / f64::from(size - 7);
+        let estimated_version = loc.version;
+        let (loc, grid) = locate(&images.prepared, loc)?;
+        let size = 17 + loc.version * 4;
 
-    if version == 1 {
-        return Ok(Perspective::new(
-            dx,
-            Delta { dx: 0.0, dy: 0.0 },
-            dy,
-            Delta { dx: 0.0, dy: 0.0 },
-        ));
+        let darkness = soft::darkness(
+            &images.grayscale,
+            &images.prepared,
+            &grid,
+            size,
+            self.sampling,
+        );
+
+        // Modules that fall outside of the image are taken to be light, and left to the error correction
+        let data = darkness
+            .iter()
+            .map(|p| match p {
+                Some(p) if *p >= 0.5 => 0,
+                _ => 255,
+            })
+            .collect();
+
+        let confidence = darkness
+            .iter()
+            .map(|p| p.map_or(0, |p| ((2.0 * p - 1.0).abs() * 255.0).round() as u8))
+            .collect();
+
+        let mut qr_data = QRData::new(data, loc.version);
+        qr_data.estimated_version = estimated_version;
+        qr_data.confidence = confidence;
+
+        Ok(qr_data)
     }
+}
 
-    let mut est_alignment = Point {
-        x: (loc.top_right - 3.0 * dx + f64::from(size - 10) * dy).x,
-        y: (loc.bottom_left + f64::from(size - 10) * dx - 3.0 * dy).y,
+// Read the version information and map the modules of the QR Code onto the prepared image
+fn locate(prepared: &GrayImage, loc: QRLocation) -> Result<(QRLocation, Grid), QRError> {
+    let loc = QRLocation {
+        version: version(prepared, &loc).unwrap_or(loc.version),
+        ..loc
     };
 
-    let mut found = false;
+    let size = 17 + loc.version * 4;
+    let p = determine_perspective(prepared, loc.version, size, &loc)?;
 
-    'distance: for i in 0..4 {
-        'scale: for j in &[0, 1, -1, 2, -2, 3] {
-            let scale = 1.0 + (f64::from(*j) / 10.0);
+    debug!("PERSPECTIVE {:?}", p);
 
-            if i == 0 {
-                if is_alignment(prepared, est_alignment, dx, dy, scale) {
-                    found = true;
-                    break 'distance;
-                }
+    let mut grid = Grid::new(prepared, loc.version, p)?;
+    grid.refine(prepared, size);
 
-                continue 'scale;
-            }
-
-            for x in -i..=i {
-                let alignment = est_alignment + f64::from(x) / 2.0 * dx - f64::from(i) / 2.0 * dy;
-                if is_alignment(prepared, alignment, dx, dy, scale) {
-                    est_alignment = alignment;
-                    found = true;
-                    break 'distance;
-                }
-
-                let alignment = est_alignment + f64::from(x) / 2.0 * dx + f64::from(i) / 2.0 * dy;
-                if is_alignment(prepared, alignment, dx, dy, scale) {
-                    est_alignment = alignment;
-                    found = true;
-                    break 'distance;
-                }
-            }
-
-            for y in -i + 1..i {
-                let alignment = est_alignment - f64::from(i) / 2.0 * dx + f64::from(y) / 2.0 * dy;
-                if is_alignment(prepared, alignment, dx, dy, scale) {
-                    est_alignment = alignment;
-                    found = true;
-                    break 'distance;
-                }
+    Ok((loc, grid))
+}
 
-                let alignment = est_alignment + f64::from(i) / 2.0 * dx + f64::from(y) / 2.0 * dy;
-                if is_alignment(prepared, alignment, dx, dy, scale) {
-                    est_alignment = alignment;
-                    found = true;
-                    break 'distance;
+// Part of a module along either axis that the sub-grid of points is spread over, as blur bleeds the neighbouring
+// modules into the edges
+const SUB_GRID_SPAN: f64 = 0.5;
+
+// Let an n×n sub-grid of points inside the module vote on its color, returns the color and the confidence in it
+//
+// The confidence is 255 when all points agree, down to 0 for a tie.
+fn vote<W>(prepared: &GrayImage, grid: &Grid, mx: f64, my: f64, n: u32, weight: W) -> (u8, u8)
+where
+    W: Fn(f64, f64) -> f64,
+{
+    let offsets = sub_grid(n);
+
+    let mut dark = 0.0;
+    let mut total = 0.0;
+
+    for dy in &offsets {
+        for dx in &offsets {
+            if let Some((x, y)) = pixel_at(prepared, grid.map(mx + dx, my + dy)) {
+                let weight = weight(*dx, *dy);
+
+                total += weight;
+                if prepared.get_pixel(x, y)[0] == 0 {
+                    dark += weight;
                 }
             }
         }
     }
 
-    if !found {
-        return Err(QRError {
-            msg: String::from("Unable to find alignment pattern"),
-        });
+    if total == 0.0 {
+        return (255, 0);
     }
 
-    let al_x = est_alignment.x.round() as u32;
-    let al_y = est_alignment.y.round() as u32;
-    let mut left_x = 0;
-    let mut right_x = prepared.dimensions().0;
-    for x in (0..al_x).rev() {
-        if prepared.get_pixel(x, al_y)[0] == 255 {
-            left_x = x;
-            break;
-        }
+    let share = dark / total;
+    let pixel = if share > 0.5 { 0 } else { 255 };
+
+    (pixel, ((2.0 * share - 1.0).abs() * 255.0).round() as u8)
+}
+
+// Offsets from the center of a module along either axis, in modules, of an n×n sub-grid of points inside it
+fn sub_grid(n: u32) -> Vec<f64> {
+    let n = n.max(1);
+
+    (0..n)
+        .map(|i| ((f64::from(i) + 0.5) / f64::from(n) - 0.5) * SUB_GRID_SPAN)
+        .collect()
+}
+
+// Weight of a point of the sub-grid, the closer to the center of the module the heavier
+fn center_weight(dx: f64, dy: f64) -> f64 {
+    (1.0 - 2.0 * dx.abs()) * (1.0 - 2.0 * dy.abs())
+}
+
+// Points around the center of a module, in modules, that should be of the same color when the module is sampled well
+const CONFIDENCE_OFFSETS: [(f64, f64); 4] = [(-0.25, 0.0), (0.25, 0.0), (0.0, -0.25), (0.0, 0.25)];
+
+// Lowest estimated version to read the version information for, since the estimate may be one too low
+const MIN_VERSION_ESTIMATE: u32 = 6;
+
+// Maximum difference between the estimated version and the version information, to avoid trusting a misread
+const MAX_VERSION_DIFF: u32 = 2;
+
+// Read the version information blocks next to the top right and bottom left finder patterns
+//
+// Both blocks are 6x3 modules at a fixed offset from the refined centers of the finder patterns, so they can be
+// sampled before the version is known. The module size is taken from both the finder patterns and the estimated version,
+// and the block with the fewest errors is used.
+fn version(prepared: &GrayImage, loc: &QRLocation) -> Option<u32> {
+    if loc.version < MIN_VERSION_ESTIMATE {
+        return None;
     }
 
-    for x in al_x..prepared.dimensions().0 {
-        if prepared.get_pixel(x, al_y)[0] == 255 {
-            right_x = x;
-            break;
+    let top_left = finder_center(prepared, loc.top_left, loc.module_size);
+    let top_right = finder_center(prepared, loc.top_right, loc.module_size);
+    let bottom_left = finder_center(prepared, loc.bottom_left, loc.module_size);
+
+    let across = top_right - top_left;
+    let down = bottom_left - top_left;
+    let across_len = (across.dx * across.dx + across.dy * across.dy).sqrt();
+    let down_len = (down.dx * down.dx + down.dy * down.dy).sqrt();
+
+    let modules = f64::from(4 * loc.version + 10);
+    let module_sizes = [
+        (loc.module_size, loc.module_size),
+        (across_len / modules, down_len / modules),
+    ];
+
+    let dark = |p: Point| {
+        let (x, y) = (p.x.round(), p.y.round());
+        if x < 0.0
+            || y < 0.0
+            || x >= f64::from(prepared.width())
+            || y >= f64::from(prepared.height())
+        {
+            return None;
         }
-    }
 
-    debug!("LEFT X {} RIGHT X {}", left_x, right_x);
-    est_alignment.x = (f64::from(left_x) + f64::from(right_x)) / 2.0;
+        Some(prepared.get_pixel(x as u32, y as u32)[0] == 0)
+    };
+
+    let mut found = vec![];
+
+    for (size_x, size_y) in module_sizes.iter() {
+        let dx = across / across_len * *size_x;
+        let dy = down / down_len * *size_y;
+
+        // Bit i is found at (side - 11 + i % 3, i / 3) in the top right block and mirrored in the bottom left one
+        let blocks = [(top_right, dx, dy), (bottom_left, dy, dx)];
 
-    let al_x = est_alignment.x.round() as u32;
-    let al_y = est_alignment.y.round() as u32;
-    let mut top_y = 0;
-    let mut bottom_y = prepared.dimensions().1;
+        for (center, short, long) in blocks.iter() {
+            let bits = (0..18).try_fold(0, |bits, i| {
+                let p =
+                    *center + (f64::from(i % 3) - 7.0) * *short + (f64::from(i / 3) - 3.0) * *long;
 
-    for y in (0..al_y).rev() {
-        if prepared.get_pixel(al_x, y)[0] == 255 {
-            top_y = y;
-            break;
+                dark(p).map(|dark| bits | u32::from(dark) << i)
+            });
+
+            if let Some(corrected) = bits.and_then(correct) {
+                found.push(corrected);
+            }
         }
     }
 
-    for y in al_y..prepared.dimensions().1 {
-        if prepared.get_pixel(al_x, y)[0] == 255 {
-            bottom_y = y;
-            break;
-        }
+    debug!("VERSION INFORMATION {:?}", found);
+
+    found
+        .into_iter()
+        .filter(|(version, _)| version.abs_diff(loc.version) <= MAX_VERSION_DIFF)
+        .min_by_key(|(_, errors)| *errors)
+        .map(|(version, _)| version)
+}
+
+// Pixel the point falls in, if it is inside the image
+fn pixel_at(prepared: &GrayImage, p: Point) -> Option<(u32, u32)> {
+    let (x, y) = (p.x.round(), p.y.round());
+    if x < 0.0 || y < 0.0 || x >= f64::from(prepared.width()) || y >= f64::from(prepared.height()) {
+        return None;
     }
 
-    debug!("TOP Y {} BOTTOM Y {}", top_y, bottom_y);
-    est_alignment.y = (f64::from(top_y) + f64::from(bottom_y)) / 2.0;
+    Some((x as u32, y as u32))
+}
+
+fn determine_perspective(
+    prepared: &GrayImage,
+    version: u32,
+    size: u32,
+    loc: &QRLocation,
+) -> Result<Perspective, QRError> {
+    // The finder patterns are only located to the nearest pixel, which is not precise enough to extrapolate from
+    let top_left = finder_center(prepared, loc.top_left, loc.module_size);
+    let top_right = finder_center(prepared, loc.top_right, loc.module_size);
+    let bottom_left = finder_center(prepared, loc.bottom_left, loc.module_size);
+
+    debug!("FINDERS {:?} {:?} {:?}", top_left, top_right, bottom_left);
+
+    let mut dx = top_right - top_left;
+    dx = dx / f64::from(size - 7);
+
+    let mut dy = bottom_left - top_left;
+    dy = dy / f64::from(size - 7);
+
+    // Centers of the finder patterns and the bottom right corner in modules
+    let far = f64::from(size - 4);
+    let mut grid = [
+        Point { x: 3.0, y: 3.0 },
+        Point { x: far, y: 3.0 },
+        Point { x: far, y: far },
+        Point { x: 3.0, y: far },
+    ];
+
+    if version == 1 {
+        let bottom_right = top_right + (bottom_left - top_left);
+
+        return Ok(Perspective::new(
+            &grid,
+            &[top_left, top_right, bottom_right, bottom_left],
+        ));
+    }
+
+    let mut est_alignment = Point {
+        x: (top_right - 3.0 * dx + f64::from(size - 10) * dy).x,
+        y: (bottom_left + f64::from(size - 10) * dx - 3.0 * dy).y,
+    };
+
+    est_alignment = find_alignment(prepared, est_alignment, dx, dy).ok_or(QRError {
+        msg: String::from("Unable to find alignment pattern"),
+    })?;
+
+    est_alignment = center(prepared, est_alignment, 1);
 
     #[cfg(feature = "debug-images")]
     {
@@ -262,20 +490,132 @@ fn determine_perspective(
         }
     }
 
-    let orig_estimate = Point {
-        x: (loc.top_right - 3.0 * dx + f64::from(size - 10) * dy).x,
-        y: (loc.bottom_left + f64::from(size - 10) * dx - 3.0 * dy).y,
+    // The bottom right alignment pattern is 3 modules further in than the finder patterns
+    grid[2] = Point {
+        x: far - 3.0,
+        y: far - 3.0,
     };
 
-    debug!("ORIG EST {:?}, NEW EST {:?}", orig_estimate, est_alignment);
+    Ok(Perspective::new(
+        &grid,
+        &[top_left, top_right, est_alignment, bottom_left],
+    ))
+}
+
+// Search for an alignment pattern in widening rings around the estimated location, allowing for a slightly different module size
+fn find_alignment(prepared: &GrayImage, estimate: Point, dx: Delta, dy: Delta) -> Option<Point> {
+    for i in 0..4 {
+        for j in &[0, 1, -1, 2, -2, 3] {
+            let scale = 1.0 + (f64::from(*j) / 10.0);
+
+            if i == 0 {
+                if is_alignment(prepared, estimate, dx, dy, scale) {
+                    return Some(estimate);
+                }
+
+                continue;
+            }
+
+            let top_bottom = (-i..=i).flat_map(|x| {
+                vec![
+                    estimate + f64::from(x) / 2.0 * dx - f64::from(i) / 2.0 * dy,
+                    estimate + f64::from(x) / 2.0 * dx + f64::from(i) / 2.0 * dy,
+                ]
+            });
+
+            let left_right = (-i + 1..i).flat_map(|y| {
+                vec![
+                    estimate - f64::from(i) / 2.0 * dx + f64::from(y) / 2.0 * dy,
+                    estimate + f64::from(i) / 2.0 * dx + f64::from(y) / 2.0 * dy,
+                ]
+            });
+
+            if let Some(alignment) = top_bottom
+                .chain(left_right)
+                .find(|alignment| is_alignment(prepared, *alignment, dx, dy, scale))
+            {
+                return Some(alignment);
+            }
+        }
+    }
+
+    None
+}
+
+// Center of the finder pattern around the provided point, refined to less than a pixel
+//
+// A gap in the outer ring of a noisy finder pattern makes the refinement stray, in which case the point is kept as is.
+fn finder_center(prepared: &GrayImage, p: Point, module_size: f64) -> Point {
+    let refined = center(prepared, p, 3);
+    let shift = refined - p;
+
+    if (shift.dx * shift.dx + shift.dy * shift.dy).sqrt() > module_size / 2.0 {
+        return p;
+    }
 
-    let mut delta = est_alignment - orig_estimate;
+    refined
+}
 
-    debug!("DELTA {:?}", delta);
+// Center of the pattern around the dark module at the provided point, as the middle of its horizontal and vertical runs
+//
+// The runs are followed outward until the provided number of color changes on either side, so 1 finds the middle of the
+// dark module itself and 3 the middle of the outer ring of a finder pattern.
+fn center(prepared: &GrayImage, p: Point, changes: u32) -> Point {
+    let (px, py) = (p.x.round(), p.y.round());
+    if px < 0.0
+        || py < 0.0
+        || px >= f64::from(prepared.width())
+        || py >= f64::from(prepared.height())
+        || prepared.get_pixel(px as u32, py as u32)[0] != 0
+    {
+        return p;
+    }
 
-    delta = delta / f64::from((size - 10) * (size - 10));
+    let x = middle(
+        (0..prepared.width()).map(|x| prepared.get_pixel(x, py as u32)[0]),
+        px as u32,
+        changes,
+    );
+    let x = x.unwrap_or(p.x);
+
+    let y = middle(
+        (0..prepared.height()).map(|y| prepared.get_pixel(x.round() as u32, y)[0]),
+        py as u32,
+        changes,
+    );
+    let y = y.unwrap_or(p.y);
+
+    Point { x, y }
+}
 
-    Ok(Perspective::new(dx, delta, dy, Delta { dx: 0.0, dy: 0.0 }))
+// Middle between the pixels where the color changes for the provided number of times on either side of the start
+fn middle<I>(pixels: I, start: u32, changes: u32) -> Option<f64>
+where
+    I: Iterator<Item = u8>,
+{
+    let pixels: Vec<u8> = pixels.collect();
+    let start = start as usize;
+
+    let before = edge(&pixels, (0..start).rev(), pixels[start], changes)?;
+    let after = edge(&pixels, start..pixels.len(), pixels[start], changes)?;
+
+    Some((before as f64 + after as f64) / 2.0)
+}
+
+fn edge<I>(pixels: &[u8], mut range: I, mut previous: u8, changes: u32) -> Option<usize>
+where
+    I: Iterator<Item = usize>,
+{
+    let mut count = 0;
+
+    range.find(|i| {
+        if pixels[*i] != previous {
+            previous = pixels[*i];
+            count += 1;
+        }
+
+        count == changes
+    })
 }
 
 fn is_alignment(prepared: &GrayImage, p: Point, dx: Delta, dy: Delta, scale: f64) -> bool {
@@ -376,17 +716,3 @@ fn is_alignment(prepared: &GrayImage, p: Point, dx: Delta, dy: Delta, scale: f64
 
     prepared.get_pixel(p.x.round() as u32, p.y.round() as u32)[0] == 0
 }
-
-#[derive(Debug)]
-struct Perspective {
-    dx: Delta,
-    ddx: Delta,
-    dy: Delta,
-    ddy: Delta,
-}
-
-impl Perspective {
-    fn new(dx: Delta, ddx: Delta, dy: Delta, ddy: Delta) -> Perspective {
-        Perspective { dx, ddx, dy, ddy }
-    }
-}
@@ -0,0 +1,64 @@
+use super::super::Extract;
+
+use crate::util::qr::{QRError, RMQRData, RMQRLocation};
+
+use image::GrayImage;
+
+/// Extract rMQR Data from a preprocessed image
+///
+/// The module grid is determined by the finder and the ends of the top and left edges.
+/// Data is extracted by sampling the center pixel of the estimated module locations.
+pub struct RMQRExtractor {}
+
+impl RMQRExtractor {
+    /// Construct a new RMQRExtractor
+    pub fn new() -> RMQRExtractor {
+        RMQRExtractor {}
+    }
+}
+
+impl Extract<GrayImage, RMQRLocation, RMQRData, QRError> for RMQRExtractor {
+    fn extract(&self, prepared: &GrayImage, loc: RMQRLocation) -> Result<RMQRData, QRError> {
+        debug!("RMQR LOC {:?}", loc);
+
+        // The finder center is module (3, 3), the ends of the edges are modules (width - 1, 0) and (0, height - 1)
+        // Solve for the module deltas in both directions
+        let w = f64::from(loc.width - 4);
+        let h = f64::from(loc.height - 4);
+        let a = loc.top_right - loc.top_left;
+        let b = loc.bottom_left - loc.top_left;
+
+        let dx = (h * a + 3.0 * b) / (w * h - 9.0);
+        let dy = (3.0 * a + w * b) / (w * h - 9.0);
+
+        let start = loc.top_left - 3.0 * dx - 3.0 * dy;
+
+        debug!("START {:?} DX {:?} DY {:?}", start, dx, dy);
+
+        let mut data = vec![];
+
+        for y in 0..loc.height {
+            let line = start + f64::from(y) * dy;
+
+            for x in 0..loc.width {
+                let p = line + f64::from(x) * dx;
+                let px = p.x.round();
+                let py = p.y.round();
+
+                if px < 0.0
+                    || py < 0.0
+                    || px >= f64::from(prepared.width())
+                    || py >= f64::from(prepared.height())
+                {
+                    return Err(QRError {
+                        msg: String::from("rMQR Code extends beyond the image"),
+                    });
+                }
+
+                data.push(prepared.get_pixel(px as u32, py as u32)[0]);
+            }
+        }
+
+        Ok(RMQRData::new(data, loc.version))
+    }
+}
@@ -0,0 +1,140 @@
+use super::grid::Grid;
+use super::{center_weight, pixel_at, sub_grid, Sampling};
+
+use image::GrayImage;
+
+// Offsets from the center of a module along either axis, in modules, of the kernel of pixels averaged for every module
+// when sampling its center
+const KERNEL: [f64; 3] = [-0.25, 0.0, 0.25];
+
+// Number of modules on every side of a module among which the gray levels of dark and light modules are determined
+const NEIGHBOURHOOD: usize = 2;
+
+/// Probability that every module is dark, in row major order, or None for modules outside of the image
+///
+/// Every module is sampled as the mean gray value of a small kernel around its center, so a single bad pixel does not
+/// flip it. Sampling a sub-grid of points averages those instead, weighted towards the center for `Sampling::Weighted`. The prepared image tells which of the surrounding modules are dark and which are light, and the mean gray
+/// values of both are the levels the module is compared against. This follows uneven lighting across the QR Code,
+/// while modules that are neither as dark nor as light as their neighbours end up with a probability close to 0.5.
+pub fn darkness(
+    grayscale: &GrayImage,
+    prepared: &GrayImage,
+    grid: &Grid,
+    size: u32,
+    sampling: Sampling,
+) -> Vec<Option<f64>> {
+    let side = size as usize;
+    let points = kernel(sampling);
+
+    // Mean gray value and whether the prepared image marks it as dark, for every module inside the image
+    let mut samples: Vec<Option<(f64, bool)>> = Vec::with_capacity(side * side);
+
+    for y in 0..size {
+        for x in 0..size {
+            let (mx, my) = (f64::from(x), f64::from(y));
+
+            let center = match pixel_at(prepared, grid.map(mx, my)) {
+                Some((px, py)) => prepared.get_pixel(px, py)[0],
+                None => {
+                    samples.push(None);
+                    continue;
+                }
+            };
+
+            let (mut gray, mut total) = (0.0, 0.0);
+
+            for (dx, dy, weight) in &points {
+                if let Some((px, py)) = pixel_at(grayscale, grid.map(mx + dx, my + dy)) {
+                    gray += weight * f64::from(grayscale.get_pixel(px, py)[0]);
+                    total += weight;
+                }
+            }
+
+            if total == 0.0 {
+                samples.push(None);
+            } else {
+                samples.push(Some((gray / total, center == 0)));
+            }
+        }
+    }
+
+    let overall = levels(samples.iter());
+
+    let mut darkness = Vec::with_capacity(side * side);
+
+    for y in 0..side {
+        for x in 0..side {
+            let (gray, dark) = match samples[y * side + x] {
+                Some(sample) => sample,
+                None => {
+                    darkness.push(None);
+                    continue;
+                }
+            };
+
+            let rows = y.saturating_sub(NEIGHBOURHOOD)..(y + NEIGHBOURHOOD + 1).min(side);
+            let columns = x.saturating_sub(NEIGHBOURHOOD)..(x + NEIGHBOURHOOD + 1).min(side);
+
+            let neighbours = rows.flat_map(|ny| columns.clone().map(move |nx| ny * side + nx));
+            let (dark_level, light_level) = levels(neighbours.map(|i| &samples[i]));
+
+            // Without both dark and light modules nearby, compare against the whole QR Code instead
+            let (dark_level, light_level) =
+                match (dark_level.or(overall.0), light_level.or(overall.1)) {
+                    (Some(d), Some(l)) if l > d => (d, l),
+                    _ => {
+                        darkness.push(Some(if dark { 1.0 } else { 0.0 }));
+                        continue;
+                    }
+                };
+
+            darkness.push(Some(
+                ((light_level - gray) / (light_level - dark_level)).clamp(0.0, 1.0),
+            ));
+        }
+    }
+
+    darkness
+}
+
+// Offsets from the center of a module in modules of the points averaged for every module, along with their weights
+fn kernel(sampling: Sampling) -> Vec<(f64, f64, f64)> {
+    let (offsets, weighted) = match sampling {
+        Sampling::Center => (KERNEL.to_vec(), false),
+        Sampling::Majority(n) => (sub_grid(n), false),
+        Sampling::Weighted(n) => (sub_grid(n), true),
+    };
+
+    offsets
+        .iter()
+        .flat_map(|dy| offsets.iter().map(move |dx| (*dx, *dy)))
+        .map(|(dx, dy)| {
+            let weight = if weighted { center_weight(dx, dy) } else { 1.0 };
+            (dx, dy, weight)
+        })
+        .collect()
+}
+
+// Mean gray values of the dark and the light modules
+fn levels<'a, I>(samples: I) -> (Option<f64>, Option<f64>)
+where
+    I: Iterator<Item = &'a Option<(f64, bool)>>,
+{
+    let (mut dark, mut light) = ((0.0, 0), (0.0, 0));
+
+    for (gray, is_dark) in samples.flatten() {
+        let level = if *is_dark { &mut dark } else { &mut light };
+        level.0 += gray;
+        level.1 += 1;
+    }
+
+    let mean = |(total, count): (f64, u32)| {
+        if count == 0 {
+            None
+        } else {
+            Some(total / f64::from(count))
+        }
+    };
+
+    (mean(dark), mean(light))
+}
@@ -0,0 +1,123 @@
+use super::grid::Grid;
+use super::pixel_at;
+
+use image::GrayImage;
+
+// Steps per module in which the timing patterns are traced
+const STEPS: u32 = 16;
+
+// Drift in modules below which the module is left where the grid puts it, as the edges are only found to within
+// a pixel or so
+const MIN_DRIFT: f64 = 0.1;
+
+// Maximum distance in modules between where an edge of a timing module is expected and where it is found
+const MAX_DRIFT: f64 = 0.5;
+
+/// Correction of the module coordinates along either axis, as measured along the timing patterns
+///
+/// Thermal printers and the like do not print every column or row of modules equally wide, so the modules drift away
+/// from where even a distorted grid expects them. The timing patterns on row and column 6 alternate between dark and
+/// light modules, so the edges found along them tell where every column and row really is.
+pub struct Timing {
+    // Measured centers of the columns and rows of modules, as (expected, found) pairs in order
+    columns: Vec<(f64, f64)>,
+    rows: Vec<(f64, f64)>,
+}
+
+impl Timing {
+    /// Trace the timing patterns through the grid
+    ///
+    /// Columns and rows of which the edges could not be found are interpolated from their neighbours,
+    /// while the centers of the finder patterns are taken to be exactly where the grid puts them.
+    pub fn new(prepared: &GrayImage, grid: &Grid, size: u32) -> Timing {
+        Timing {
+            columns: trace(size, |t| dark(prepared, grid, t, 6.0)),
+            rows: trace(size, |t| dark(prepared, grid, 6.0, t)),
+        }
+    }
+
+    /// Where module (x, y) really is, in the module coordinates of the grid
+    pub fn correct(&self, x: f64, y: f64) -> (f64, f64) {
+        (interpolate(&self.columns, x), interpolate(&self.rows, y))
+    }
+}
+
+fn dark(prepared: &GrayImage, grid: &Grid, x: f64, y: f64) -> Option<bool> {
+    pixel_at(prepared, grid.map(x, y)).map(|(x, y)| prepared.get_pixel(x, y)[0] == 0)
+}
+
+// Find the edges of the timing modules from the separator at 7 to the one at size - 8, and the centers between them
+fn trace<F>(size: u32, dark: F) -> Vec<(f64, f64)>
+where
+    F: Fn(f64) -> Option<bool>,
+{
+    // Position and new color of every change in color
+    let mut changes = vec![];
+    let mut previous = None;
+
+    for step in 0..=(size - 15) * STEPS {
+        let t = 7.0 + f64::from(step) / f64::from(STEPS);
+        let current = dark(t);
+
+        if let (Some(p), Some(c)) = (previous, current) {
+            if p != c {
+                changes.push((t - 0.5 / f64::from(STEPS), c));
+            }
+        }
+
+        if current.is_some() {
+            previous = current;
+        }
+    }
+
+    // The edge between module x and x + 1 turns dark when x is odd, starting with the light separator at 7
+    let edges: Vec<Option<f64>> = (7..size - 8)
+        .map(|x| {
+            let expected = f64::from(x) + 0.5;
+
+            changes
+                .iter()
+                .filter(|(_, to_dark)| *to_dark == (x % 2 == 1))
+                .map(|(t, _)| *t)
+                .filter(|t| (t - expected).abs() <= MAX_DRIFT)
+                .min_by(|a, b| (a - expected).abs().total_cmp(&(b - expected).abs()))
+        })
+        .collect();
+
+    let mut centers = vec![(3.0, 3.0)];
+
+    for (x, pair) in (8..size - 8).zip(edges.windows(2)) {
+        if let (Some(before), Some(after)) = (pair[0], pair[1]) {
+            let width = after - before;
+
+            if width > 1.0 - MAX_DRIFT && width < 1.0 + MAX_DRIFT {
+                let expected = f64::from(x);
+                let found = (before + after) / 2.0;
+
+                if (found - expected).abs() < MIN_DRIFT {
+                    centers.push((expected, expected));
+                } else {
+                    centers.push((expected, found));
+                }
+            }
+        }
+    }
+
+    centers.push((f64::from(size - 4), f64::from(size - 4)));
+
+    debug!("TIMING {:?}", centers);
+
+    centers
+}
+
+// Piecewise linear between the measured centers, and unchanged outside of the finder patterns
+fn interpolate(centers: &[(f64, f64)], coord: f64) -> f64 {
+    match centers.windows(2).find(|pair| coord <= pair[1].0) {
+        Some(pair) if coord >= pair[0].0 => {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+
+            y0 + (coord - x0) * (y1 - y0) / (x1 - x0)
+        }
+        _ => coord,
+    }
+}
@@ -16,12 +16,14 @@ mod decoder;
 
 pub mod decode;
 pub mod detect;
+pub mod encode;
 pub mod extract;
 pub mod prepare;
 pub mod util;
 
 pub use crate::decoder::{
-    default_builder, default_builder_with_info, default_decoder, default_decoder_with_info,
+    default_builder, default_builder_bytes, default_builder_with_info, default_decoder,
+    default_decoder_bytes, default_decoder_with_info,
 };
 pub use crate::decoder::{Decoder, DecoderBuilder};
 pub use crate::util::qr::{ECLevel, QRInfo};
@@ -0,0 +1,67 @@
+use super::Prepare;
+
+use image::imageops::grayscale;
+use image::{GenericImageView, GrayImage, Rgba};
+
+/// Prepared image together with the grayscale source image it was prepared from
+///
+/// Detectors only look at the prepared black/white image, but extractors can sample the grayscale image to tell
+/// how dark every module really is.
+pub struct PreparedGrayscale {
+    /// Source image converted to grayscale
+    pub grayscale: GrayImage,
+
+    /// Black/white image as returned by the wrapped [`Prepare`]
+    ///
+    /// [`Prepare`]: trait.Prepare.html
+    pub prepared: GrayImage,
+}
+
+/// Keep the grayscale source image next to the image prepared by another [`Prepare`]
+///
+/// Use this to let the [`QRExtractor`] sample modules with soft decisions, see there for details.
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// # extern crate image;
+/// use bardecoder::decode::QRDecoder;
+/// use bardecoder::detect::LineScan;
+/// use bardecoder::extract::QRExtractor;
+/// use bardecoder::prepare::{BlockedMean, WithGrayscale};
+/// use bardecoder::DecoderBuilder;
+///
+/// let mut db = DecoderBuilder::new();
+/// db.prepare(Box::new(WithGrayscale::new(BlockedMean::new(5, 7))));
+/// db.detect(Box::new(LineScan::new()));
+/// db.qr(Box::new(QRExtractor::new()), Box::new(QRDecoder::new()));
+///
+/// let decoder = db.build();
+/// # let _: &bardecoder::Decoder<image::DynamicImage, _, _> = &decoder;
+/// ```
+///
+/// [`Prepare`]: trait.Prepare.html
+/// [`QRExtractor`]: ../extract/struct.QRExtractor.html
+pub struct WithGrayscale<P> {
+    prepare: P,
+}
+
+impl<P> WithGrayscale<P> {
+    /// Construct a new WithGrayscale around the provided Prepare
+    pub fn new(prepare: P) -> WithGrayscale<P> {
+        WithGrayscale { prepare }
+    }
+}
+
+impl<D, P> Prepare<D, PreparedGrayscale> for WithGrayscale<P>
+where
+    D: GenericImageView<Pixel = Rgba<u8>>,
+    P: Prepare<D, GrayImage>,
+{
+    fn prepare(&self, input: &D) -> PreparedGrayscale {
+        PreparedGrayscale {
+            grayscale: grayscale(input),
+            prepared: self.prepare.prepare(input),
+        }
+    }
+}
@@ -1,8 +1,10 @@
 //! Prepare an image for data extraction
 
 mod blockedmean;
+mod grayscale;
 
 pub use self::blockedmean::BlockedMean;
+pub use self::grayscale::{PreparedGrayscale, WithGrayscale};
 
 /// Prepare the source image for data extraction, for example by converting it to black/white
 ///
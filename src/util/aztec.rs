@@ -0,0 +1,139 @@
+//! Utility structs for decoding Aztec Codes
+
+use std::ops::Index;
+use thiserror::Error;
+
+use crate::util::Point;
+
+/// Generic Aztec Error message. Can be converted into `failure::Error`
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("Error decoding Aztec Code: {}", msg)]
+pub struct AztecError {
+    /// Detail message
+    pub msg: String,
+}
+
+/// Aztec Data extracted from the source image
+///
+/// While the data is still pixels of value 0/255, using the index will convert it into 1's (pixel 0) and 0's (pixel 255)
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// use bardecoder::util::aztec::AztecData;
+///
+/// let mut data = vec![0; 15 * 15];
+/// data[15 * 15 - 1] = 255;
+/// let aztec_data = AztecData::new(data, 15, true, 1, 4);
+///
+/// assert_eq!(aztec_data[[0, 0]], 1);
+/// assert_eq!(aztec_data[[14, 14]], 0);
+/// ```
+#[derive(Debug)]
+pub struct AztecData {
+    /// Aztec Pixel Data in size x size pixels, stored in row major order. Using the provided index will convert into 1's and 0's.
+    pub data: Vec<u8>,
+
+    /// Width and height of the Aztec Code in modules, including any reference grid lines
+    pub size: u32,
+
+    /// Whether this is a compact Aztec Code
+    pub compact: bool,
+
+    /// Number of data layers around the core
+    pub layers: u32,
+
+    /// Number of data codewords, as read from the mode message
+    pub data_codewords: u32,
+}
+
+impl AztecData {
+    /// Create a new AztecData object with the provided data and parameters
+    pub fn new(
+        data: Vec<u8>,
+        size: u32,
+        compact: bool,
+        layers: u32,
+        data_codewords: u32,
+    ) -> AztecData {
+        AztecData {
+            data,
+            size,
+            compact,
+            layers,
+            data_codewords,
+        }
+    }
+}
+
+impl Index<[u32; 2]> for AztecData {
+    type Output = u8;
+
+    fn index(&self, index: [u32; 2]) -> &u8 {
+        let pixel = self.data[index[1] as usize * self.size as usize + index[0] as usize];
+        if pixel == 0 {
+            &1
+        } else {
+            &0
+        }
+    }
+}
+
+/// Location of the Aztec Code in the source image, in pixels
+///
+/// The corners of the mode message ring around the bullseye are named as if the Aztec Code is upright,
+/// as determined by the orientation marks.
+#[derive(Debug)]
+pub struct AztecLocation {
+    /// Center of the bullseye, in pixels
+    pub center: Point,
+
+    /// Center of the top left module of the mode message ring, in pixels
+    pub top_left: Point,
+
+    /// Center of the top right module of the mode message ring, in pixels
+    pub top_right: Point,
+
+    /// Center of the bottom left module of the mode message ring, in pixels
+    pub bottom_left: Point,
+
+    /// Module size in pixels
+    pub module_size: f64,
+
+    /// Whether this is a compact Aztec Code
+    pub compact: bool,
+
+    /// Number of data layers around the core, as read from the mode message
+    pub layers: u32,
+
+    /// Number of data codewords, as read from the mode message
+    pub data_codewords: u32,
+}
+
+impl AztecLocation {
+    /// Width and height of the Aztec Code in modules, including any reference grid lines
+    pub fn size(&self) -> u32 {
+        aztec_size(self.compact, self.layers)
+    }
+}
+
+/// Width and height of an Aztec Code in modules, including any reference grid lines
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// use bardecoder::util::aztec::aztec_size;
+///
+/// assert_eq!(aztec_size(true, 1), 15);
+/// assert_eq!(aztec_size(false, 1), 19);
+/// assert_eq!(aztec_size(false, 32), 151);
+/// ```
+pub fn aztec_size(compact: bool, layers: u32) -> u32 {
+    if compact {
+        11 + 4 * layers
+    } else {
+        // Reference grid lines are inserted every 16 modules from the center
+        let base = 14 + 4 * layers;
+        base + 1 + 2 * ((base / 2 - 1) / 15)
+    }
+}
@@ -19,6 +19,7 @@ use std::vec::IntoIter;
 /// ```
 pub struct Chomp {
     bytes: Peekable<IntoIter<u8>>,
+    bits_chomped: usize,
     bits_left: BitCount,
     current_byte: Option<u8>,
     bits_left_in_byte: BitCount,
@@ -44,6 +45,7 @@ impl Chomp {
 
         Chomp {
             bytes,
+            bits_chomped: 0,
             bits_left,
             current_byte,
             bits_left_in_byte,
@@ -75,6 +77,16 @@ impl Chomp {
     /// Try to chomp `nr_bits` bits. If not enough bits are left, or requesting more than 8 bits [`None`] will be returned
     /// If requesting fewer than 8 bits, the result will be in the least significant bits of the u8
     pub fn chomp(&mut self, nr_bits: u8) -> Option<u8> {
+        let result = self.chomp_bits(nr_bits);
+
+        if result.is_some() {
+            self.bits_chomped += nr_bits as usize;
+        }
+
+        result
+    }
+
+    fn chomp_bits(&mut self, nr_bits: u8) -> Option<u8> {
         let bit_count = BitCount(nr_bits as usize);
         if !(1..=8).contains(&nr_bits) || bit_count > self.bits_left {
             return None;
@@ -119,6 +131,11 @@ impl Chomp {
         }
     }
 
+    /// Number of bits chomped so far
+    pub fn position(&self) -> usize {
+        self.bits_chomped
+    }
+
     fn nibble(&mut self, nr_bits: BitCount) -> Option<u8> {
         if let Some(ref mut byte) = self.current_byte {
             let result = *byte >> (8 - nr_bits.0);
@@ -177,6 +194,19 @@ mod test {
         assert_eq!(None, chomp.chomp(4));
     }
 
+    #[test]
+    pub fn position() {
+        let mut chomp = Chomp::new(vec![0b11000100, 0b10101010]);
+
+        assert_eq!(0, chomp.position());
+        chomp.chomp(6);
+        assert_eq!(6, chomp.position());
+        chomp.chomp(4);
+        assert_eq!(10, chomp.position());
+        chomp.chomp(6);
+        assert_eq!(16, chomp.position());
+    }
+
     #[test]
     pub fn chomp_u16() {
         let mut chomp = Chomp::new(vec![0b11000100, 0b10101010]);
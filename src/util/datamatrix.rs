@@ -0,0 +1,90 @@
+//! Utility structs for decoding Data Matrix Codes
+
+use std::ops::Index;
+use thiserror::Error;
+
+use crate::util::Point;
+
+/// Generic Data Matrix Error message. Can be converted into `failure::Error`
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("Error decoding Data Matrix Code: {}", msg)]
+pub struct DataMatrixError {
+    /// Detail message
+    pub msg: String,
+}
+
+/// Data Matrix Data extracted from the source image
+///
+/// While the data is still pixels of value 0/255, using the index will convert it into 1's (pixel 0) and 0's (pixel 255)
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// use bardecoder::util::datamatrix::DataMatrixData;
+///
+/// let mut data = vec![0; 8 * 18];
+/// data[8 * 18 - 1] = 255;
+/// let dm_data = DataMatrixData::new(data, 8, 18);
+///
+/// assert_eq!(dm_data[[0, 0]], 1);
+/// assert_eq!(dm_data[[17, 7]], 0);
+/// ```
+#[derive(Debug)]
+pub struct DataMatrixData {
+    /// Data Matrix Pixel Data in columns x rows pixels, stored in row major order. Using the provided index will convert into 1's and 0's.
+    pub data: Vec<u8>,
+
+    /// Number of rows of modules, including the finder and clock patterns
+    pub rows: u32,
+
+    /// Number of columns of modules, including the finder and clock patterns
+    pub columns: u32,
+}
+
+impl DataMatrixData {
+    /// Create a new DataMatrixData object with the provided data and size
+    pub fn new(data: Vec<u8>, rows: u32, columns: u32) -> DataMatrixData {
+        DataMatrixData {
+            data,
+            rows,
+            columns,
+        }
+    }
+}
+
+impl Index<[u32; 2]> for DataMatrixData {
+    type Output = u8;
+
+    fn index(&self, index: [u32; 2]) -> &u8 {
+        let pixel = self.data[index[1] as usize * self.columns as usize + index[0] as usize];
+        if pixel == 0 {
+            &1
+        } else {
+            &0
+        }
+    }
+}
+
+/// Location of the Data Matrix Code in the source image, in pixels
+///
+/// The corners are named as if the Data Matrix Code is upright, with the solid L-shaped finder pattern along the left and bottom edges.
+#[derive(Debug)]
+pub struct DataMatrixLocation {
+    /// Center of the top left module, in pixels, relative to the Data Matrix Code
+    pub top_left: Point,
+
+    /// Center of the bottom left module, where both legs of the finder pattern meet, in pixels, relative to the Data Matrix Code
+    pub bottom_left: Point,
+
+    /// Center of the bottom right module, in pixels, relative to the Data Matrix Code
+    pub bottom_right: Point,
+
+    /// Module size in pixels
+    pub module_size: f64,
+
+    /// Number of rows of modules
+    pub rows: u32,
+
+    /// Number of columns of modules
+    pub columns: u32,
+}
@@ -0,0 +1,91 @@
+//! Utility structs for decoding EAN and UPC barcodes
+
+use thiserror::Error;
+
+use crate::util::Point;
+
+/// Generic EAN Error message. Can be converted into `failure::Error`
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("Error decoding EAN/UPC barcode: {}", msg)]
+pub struct EANError {
+    /// Detail message
+    pub msg: String,
+}
+
+/// EAN Data extracted from the source image
+///
+/// A barcode is read as the widths of its alternating bars and spaces along the scan line, in pixels.
+/// The runs are in the order of the scan line, so they are reversed if the barcode is upside down.
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// use bardecoder::util::ean::EANData;
+///
+/// // Start guard, followed by the first digit
+/// let ean_data = EANData::new(vec![2, 2, 2, 6, 4, 2, 2], None);
+///
+/// assert_eq!(ean_data.runs.len(), 7);
+/// ```
+#[derive(Debug)]
+pub struct EANData {
+    /// Widths of the bars and spaces of the main symbol in pixels, starting and ending with a bar
+    pub runs: Vec<u32>,
+
+    /// Widths of the bars and spaces of the add-on in pixels, if present
+    pub addon: Option<Vec<u32>>,
+}
+
+impl EANData {
+    /// Create a new EANData object with the provided runs
+    pub fn new(runs: Vec<u32>, addon: Option<Vec<u32>>) -> EANData {
+        EANData { runs, addon }
+    }
+}
+
+/// Location of the EAN or UPC barcode in the source image, in pixels
+///
+/// The scan line runs from left to right through the image, regardless of the orientation of the barcode.
+#[derive(Debug)]
+pub struct EANLocation {
+    /// First pixel of the leftmost bar of the main symbol
+    pub start: Point,
+
+    /// Last pixel of the rightmost bar of the main symbol
+    pub end: Point,
+
+    /// First and last pixel of the add-on, if present
+    pub addon: Option<(Point, Point)>,
+
+    /// Module size in pixels
+    pub module_size: f64,
+}
+
+/// Symbology of the decoded barcode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EANSymbology {
+    /// EAN-13, 13 digits
+    EAN13,
+
+    /// EAN-8, 8 digits
+    EAN8,
+
+    /// UPC-A, 12 digits. Encoded as an EAN-13 starting with 0
+    UPCA,
+
+    /// UPC-E, 8 digits including the number system and check digit. Zeroes are suppressed from the equivalent UPC-A
+    UPCE,
+}
+
+/// Information about the decoded EAN or UPC barcode
+#[derive(Debug, PartialEq, Eq)]
+pub struct EANInfo {
+    /// Symbology of the barcode
+    pub symbology: EANSymbology,
+
+    /// Digits of the main symbol, including the check digit
+    pub digits: String,
+
+    /// Digits of the 2 or 5 digit add-on, if present
+    pub addon: Option<String>,
+}
@@ -0,0 +1,171 @@
+//! Utility structs and functions for GS1 data
+//!
+//! GS1 data is a sequence of application identifiers (AIs), each followed by its data.
+//! Data with a variable length is terminated by an FNC1, which is represented as the ASCII Group Separator (`0x1D`).
+
+use std::fmt;
+
+use thiserror::Error;
+
+/// ASCII Group Separator, used in place of FNC1 between variable length fields
+pub const GROUP_SEPARATOR: char = '\u{1D}';
+
+/// Generic GS1 Error message. Can be converted into `failure::Error`
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("Error parsing GS1 data: {}", msg)]
+pub struct GS1Error {
+    /// Detail message
+    pub msg: String,
+}
+
+/// An application identifier along with its data
+///
+/// It is displayed in the human readable form, with the identifier in parentheses
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// use bardecoder::util::gs1::ApplicationIdentifier;
+///
+/// let ai = ApplicationIdentifier {
+///     ai: String::from("01"),
+///     data: String::from("09501101530003"),
+/// };
+///
+/// assert_eq!(ai.to_string(), "(01)09501101530003");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApplicationIdentifier {
+    /// The application identifier, 2 to 4 digits
+    pub ai: String,
+
+    /// The data following the application identifier
+    pub data: String,
+}
+
+impl fmt::Display for ApplicationIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}){}", self.ai, self.data)
+    }
+}
+
+/// Parse GS1 data into its application identifiers
+///
+/// The length of an application identifier is determined by its first two digits.
+/// The data has a predefined length for some application identifiers, all others are terminated by a Group Separator or the end of the data.
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// use bardecoder::util::gs1::parse;
+///
+/// let ais = parse("01095011015300031720140710AB-123").unwrap();
+///
+/// assert_eq!(ais.len(), 3);
+/// assert_eq!(ais[2].ai, "10");
+/// assert_eq!(ais[2].data, "AB-123");
+/// ```
+pub fn parse(data: &str) -> Result<Vec<ApplicationIdentifier>, GS1Error> {
+    let chars: Vec<char> = data.chars().collect();
+    let mut ais = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] == GROUP_SEPARATOR {
+            i += 1;
+            continue;
+        }
+
+        let prefix: String = chars[i..].iter().take(2).collect();
+        let prefix: u8 = prefix.parse().map_err(|_| GS1Error {
+            msg: format!("Invalid application identifier at {}", i),
+        })?;
+
+        let ai_length = ai_length(prefix).ok_or(GS1Error {
+            msg: format!("Unknown application identifier {:02}", prefix),
+        })?;
+
+        if i + ai_length > chars.len() || !chars[i..i + ai_length].iter().all(char::is_ascii_digit)
+        {
+            return Err(GS1Error {
+                msg: format!("Invalid application identifier at {}", i),
+            });
+        }
+
+        let ai: String = chars[i..i + ai_length].iter().collect();
+        i += ai_length;
+
+        let end = match predefined_length(prefix) {
+            Some(length) => {
+                if i + length > chars.len() || chars[i..i + length].contains(&GROUP_SEPARATOR) {
+                    return Err(GS1Error {
+                        msg: format!("Data for application identifier {} is too short", ai),
+                    });
+                }
+
+                i + length
+            }
+            None => chars[i..]
+                .iter()
+                .position(|c| *c == GROUP_SEPARATOR)
+                .map_or(chars.len(), |p| i + p),
+        };
+
+        ais.push(ApplicationIdentifier {
+            ai,
+            data: chars[i..end].iter().collect(),
+        });
+
+        i = end;
+    }
+
+    Ok(ais)
+}
+
+// Number of digits in an application identifier, determined by its first two digits
+fn ai_length(prefix: u8) -> Option<usize> {
+    match prefix {
+        0..=22 | 30 | 37 | 90..=99 => Some(2),
+        23..=25 | 40..=42 | 71 => Some(3),
+        31..=36 | 39 | 43 | 70 | 72 | 80..=82 => Some(4),
+        _ => None,
+    }
+}
+
+// Length of the data for application identifiers that do not need a separator, determined by their first two digits
+fn predefined_length(prefix: u8) -> Option<usize> {
+    match prefix {
+        0 => Some(18),
+        1..=3 => Some(14),
+        4 => Some(16),
+        11..=19 => Some(6),
+        20 => Some(2),
+        31..=36 => Some(6),
+        41 => Some(13),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_parse() {
+        let ais = parse("0109501101530003\u{1D}10AB-123\u{1D}3103000189").unwrap();
+
+        assert_eq!(
+            vec!["(01)09501101530003", "(10)AB-123", "(3103)000189"],
+            ais.iter().map(|ai| ai.to_string()).collect::<Vec<_>>()
+        );
+
+        // Separator after a predefined length is allowed
+        assert_eq!(2, parse("1714070410AB").unwrap().len());
+
+        // Too short for AI 00
+        assert!(parse("0012345").is_err());
+
+        // Unknown AI
+        assert!(parse("2612").is_err());
+    }
+}
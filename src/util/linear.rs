@@ -0,0 +1,108 @@
+//! Utility structs for decoding linear barcodes
+
+use thiserror::Error;
+
+use crate::util::gs1::ApplicationIdentifier;
+use crate::util::Point;
+
+/// Generic Linear Barcode Error message. Can be converted into `failure::Error`
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("Error decoding linear barcode: {}", msg)]
+pub struct LinearError {
+    /// Detail message
+    pub msg: String,
+}
+
+/// Symbology of a linear barcode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinearSymbology {
+    /// Code 128, including GS1-128
+    Code128,
+
+    /// Code 39, optionally with a check character and Full ASCII
+    Code39,
+
+    /// Code 93, including Full ASCII
+    Code93,
+
+    /// Codabar, optionally with a check character
+    Codabar,
+
+    /// Interleaved 2 of 5, including ITF-14
+    ITF,
+
+    /// GS1 DataBar Omnidirectional, including GS1 DataBar Stacked and Stacked Omnidirectional
+    DataBar,
+
+    /// GS1 DataBar Expanded, including GS1 DataBar Expanded Stacked
+    DataBarExpanded,
+}
+
+/// Location of a linear barcode in the source image, in pixels
+///
+/// The scan line runs from left to right through the image, regardless of the orientation of the barcode.
+#[derive(Debug)]
+pub struct LinearLocation {
+    /// Symbology of the detected barcode
+    pub symbology: LinearSymbology,
+
+    /// First pixel of the leftmost bar
+    pub start: Point,
+
+    /// Last pixel of the rightmost bar
+    pub end: Point,
+
+    /// Width of the narrowest bar or space in pixels
+    pub module_size: f64,
+
+    /// Scan lines through the lower rows of a stacked barcode, from top to bottom
+    pub stacked: Vec<(Point, Point)>,
+}
+
+/// Linear Data extracted from the source image
+///
+/// A barcode is read as the widths of its alternating bars and spaces along the scan line, in pixels.
+/// The runs are in the order of the scan line, so they are reversed if the barcode is upside down.
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// use bardecoder::util::linear::{LinearData, LinearSymbology};
+///
+/// // Code 128 Start B symbol
+/// let linear_data = LinearData::new(LinearSymbology::Code128, vec![4, 2, 2, 4, 2, 8]);
+///
+/// assert_eq!(linear_data.runs.len(), 6);
+/// ```
+#[derive(Debug)]
+pub struct LinearData {
+    /// Symbology of the detected barcode
+    pub symbology: LinearSymbology,
+
+    /// Widths of the bars and spaces in pixels, starting and ending with a bar
+    pub runs: Vec<u32>,
+
+    /// Runs of the lower rows of a stacked barcode, from top to bottom
+    pub stacked: Vec<Vec<u32>>,
+}
+
+impl LinearData {
+    /// Create a new LinearData object with the provided runs
+    pub fn new(symbology: LinearSymbology, runs: Vec<u32>) -> LinearData {
+        LinearData {
+            symbology,
+            runs,
+            stacked: vec![],
+        }
+    }
+}
+
+/// Information about the decoded linear barcode
+#[derive(Debug, PartialEq, Eq)]
+pub struct LinearInfo {
+    /// Symbology of the barcode
+    pub symbology: LinearSymbology,
+
+    /// Application identifiers and their data, if the barcode contains GS1 data
+    pub gs1: Option<Vec<ApplicationIdentifier>>,
+}
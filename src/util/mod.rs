@@ -3,9 +3,19 @@
 #[allow(clippy::unreadable_literal)]
 mod chomp;
 
+mod perspective;
 mod point;
+mod reassemble;
 
+pub mod aztec;
+pub mod datamatrix;
+pub mod ean;
+pub mod gs1;
+pub mod linear;
+pub mod pdf417;
 pub mod qr;
 
 pub use self::chomp::Chomp;
+pub use self::perspective::Perspective;
 pub use self::point::{Delta, Point};
+pub use self::reassemble::{Reassembler, SeriesPart};
@@ -0,0 +1,234 @@
+//! Utility structs for decoding PDF417 Codes
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::util::Point;
+
+/// Generic PDF417 Error message. Can be converted into `failure::Error`
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("Error decoding PDF417 Code: {}", msg)]
+pub struct PDF417Error {
+    /// Detail message
+    pub msg: String,
+}
+
+/// PDF417 Data extracted from the source image
+///
+/// Unlike the matrix codes, the modules of a PDF417 Code vary in width, so the data consists of the codewords already read from the rows.
+/// The row indicators and the start and stop patterns are not included.
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// use bardecoder::util::pdf417::PDF417Data;
+///
+/// let mut codewords = vec![Some(900); 3 * 2];
+/// codewords[4] = None;
+/// let pdf_data = PDF417Data::new(codewords, 3, 2, 0);
+///
+/// assert_eq!(pdf_data.erasures(), vec![4]);
+/// ```
+#[derive(Debug)]
+pub struct PDF417Data {
+    /// Codewords in reading order, row by row. `None` marks a codeword that could not be read, which is treated as an erasure.
+    pub codewords: Vec<Option<u32>>,
+
+    /// Number of rows, 3 to 90
+    pub rows: u32,
+
+    /// Number of data columns, 1 to 30, not including the row indicators
+    pub columns: u32,
+
+    /// Error correction level, 0 to 8. A PDF417 Code has 2<sup>level + 1</sup> error correction codewords
+    pub ec_level: u32,
+}
+
+impl PDF417Data {
+    /// Create a new PDF417Data object with the provided codewords and size
+    pub fn new(codewords: Vec<Option<u32>>, rows: u32, columns: u32, ec_level: u32) -> PDF417Data {
+        PDF417Data {
+            codewords,
+            rows,
+            columns,
+            ec_level,
+        }
+    }
+
+    /// Positions of the codewords that could not be read
+    pub fn erasures(&self) -> Vec<usize> {
+        self.codewords
+            .iter()
+            .enumerate()
+            .filter(|(_, codeword)| codeword.is_none())
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Location of a PDF417 Code in the source image, in pixels
+///
+/// The scan lines run from left to right through the image, regardless of the orientation of the PDF417 Code.
+/// Every row of the PDF417 Code is crossed by a scan line interpolated between the top and bottom scan lines.
+#[derive(Debug)]
+pub struct PDF417Location {
+    /// First pixel of the leftmost bar and last pixel of the rightmost bar along the topmost scan line
+    pub top: (Point, Point),
+
+    /// First pixel of the leftmost bar and last pixel of the rightmost bar along the bottommost scan line
+    pub bottom: (Point, Point),
+
+    /// Width of the narrowest bar or space in pixels
+    pub module_size: f64,
+}
+
+/// The bar and space patterns of the 929 codeword values, in each of the three clusters
+///
+/// A symbol character of 17 modules is read as a pattern of 17 bits, the first module being the most significant bit and a bar being 1.
+/// The patterns are listed in ISO/IEC 15438 and are not included in this library, so they have to be provided to the [`PDF417Extractor`].
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// use bardecoder::util::pdf417::PDF417Symbols;
+///
+/// // Every pattern must start with a bar, end with a space and be of the right cluster
+/// assert!(PDF417Symbols::new(&[[0; 929]; 3]).is_err());
+/// ```
+///
+/// [`PDF417Extractor`]: ../../extract/struct.PDF417Extractor.html
+#[derive(Debug, Clone)]
+pub struct PDF417Symbols {
+    values: HashMap<u32, u32>,
+}
+
+impl PDF417Symbols {
+    /// Create a new PDF417Symbols object from the patterns of clusters 0, 3 and 6, indexed by codeword value
+    ///
+    /// Will return an error if any pattern does not consist of 4 bars and 4 spaces of at most 6 modules,
+    /// does not belong to its cluster, or occurs more than once.
+    pub fn new(clusters: &[[u32; 929]; 3]) -> Result<PDF417Symbols, PDF417Error> {
+        let mut values = HashMap::new();
+
+        for (i, patterns) in clusters.iter().enumerate() {
+            for (value, pattern) in patterns.iter().enumerate() {
+                let valid = match widths(*pattern) {
+                    Some(widths) => cluster(&widths) == 3 * i as u32,
+                    None => false,
+                };
+
+                if !valid || values.insert(*pattern, value as u32).is_some() {
+                    return Err(PDF417Error {
+                        msg: format!(
+                            "Invalid pattern {:017b} for codeword {} of cluster {}",
+                            pattern,
+                            value,
+                            3 * i
+                        ),
+                    });
+                }
+            }
+        }
+
+        Ok(PDF417Symbols { values })
+    }
+
+    /// Codeword value of the provided pattern, or None if it is not a valid symbol character
+    pub fn value(&self, pattern: u32) -> Option<u32> {
+        self.values.get(&pattern).copied()
+    }
+}
+
+// Cluster number of a symbol character, 0, 3 or 6 for a valid one, from the widths of its bars and spaces in modules
+pub(crate) fn cluster(widths: &[u32]) -> u32 {
+    (widths[0] + widths[4] + 18 - widths[2] - widths[6]) % 9
+}
+
+// Widths of the bars and spaces of a pattern of 17 bits, if it consists of 4 bars and 4 spaces of at most 6 modules
+fn widths(pattern: u32) -> Option<Vec<u32>> {
+    if pattern >> 16 != 1 || pattern & 1 != 0 {
+        return None;
+    }
+
+    let mut widths: Vec<u32> = vec![];
+    let mut previous = None;
+
+    for i in (0..17).rev() {
+        let bar = pattern & (1 << i) != 0;
+
+        if previous == Some(bar) {
+            *widths.last_mut().unwrap() += 1;
+        } else {
+            widths.push(1);
+            previous = Some(bar);
+        }
+    }
+
+    if widths.len() == 8 && widths.iter().all(|w| *w <= 6) {
+        Some(widths)
+    } else {
+        None
+    }
+}
+
+/// Information about the decoded PDF417 Code
+#[derive(Debug, PartialEq, Eq)]
+pub struct PDF417Info {
+    /// Number of rows of the PDF417 Code
+    pub rows: u32,
+
+    /// Number of data columns of the PDF417 Code
+    pub columns: u32,
+
+    /// Error correction level of the PDF417 Code
+    pub ec_level: u32,
+
+    /// Number of codewords that were read incorrectly and have been corrected, not including erasures
+    pub errors: u32,
+
+    /// Number of codewords that could not be read and have been restored
+    pub erasures: u32,
+
+    /// ECI assignment numbers encountered in the PDF417 Code, in order. Empty if the PDF417 Code does not contain any ECI designators.
+    pub eci: Vec<u32>,
+
+    /// Macro PDF417 control block if this PDF417 Code is one segment of a larger file
+    pub macro_pdf417: Option<MacroPDF417>,
+}
+
+/// Macro PDF417 control block, which allows a file to be split over multiple PDF417 Codes
+///
+/// All segments of the same file share the file ID. The optional fields are usually only present in one of the segments.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MacroPDF417 {
+    /// Index of this segment, starting at 0
+    pub segment_index: u32,
+
+    /// File ID, as groups of three digits per codeword
+    pub file_id: String,
+
+    /// Whether this is the last segment of the file
+    pub last_segment: bool,
+
+    /// File name
+    pub file_name: Option<String>,
+
+    /// Total number of segments
+    pub segment_count: Option<u32>,
+
+    /// Time stamp of the file, in seconds since the Unix epoch
+    pub time_stamp: Option<u64>,
+
+    /// Sender of the file
+    pub sender: Option<String>,
+
+    /// Addressee of the file
+    pub addressee: Option<String>,
+
+    /// Size of the file in bytes
+    pub file_size: Option<u64>,
+
+    /// CRC-16 checksum of the file
+    pub checksum: Option<u32>,
+}
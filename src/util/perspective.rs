@@ -0,0 +1,210 @@
+use super::Point;
+
+/// Projective transform from the module grid of a 2D code to the pixels of the source image
+///
+/// The transform is a 3x3 homography, mapping a point (x, y) to (x' / w, y' / w) where [x', y', w] = [x, y, 1] · `matrix`.
+/// Unlike an affine transform it keeps straight lines straight under any viewing angle, so modules that are further away
+/// from the camera are correctly sampled closer together.
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// use bardecoder::util::{Perspective, Point};
+///
+/// let grid = [
+///     Point { x: 0.0, y: 0.0 },
+///     Point { x: 10.0, y: 0.0 },
+///     Point { x: 10.0, y: 10.0 },
+///     Point { x: 0.0, y: 10.0 },
+/// ];
+/// let image = [
+///     Point { x: 100.0, y: 100.0 },
+///     Point { x: 200.0, y: 120.0 },
+///     Point { x: 180.0, y: 220.0 },
+///     Point { x: 90.0, y: 190.0 },
+/// ];
+///
+/// let perspective = Perspective::new(&grid, &image);
+/// let corner = perspective.map(Point { x: 10.0, y: 10.0 });
+///
+/// assert!((corner.x - 180.0).abs() < 1e-9);
+/// assert!((corner.y - 220.0).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Perspective {
+    /// Coefficients of the homography, see above for how a point is mapped
+    pub matrix: [[f64; 3]; 3],
+}
+
+impl Perspective {
+    /// Construct the transform that maps each of the four `from` points onto the `to` point at the same index
+    ///
+    /// Both sets of points should be the corners of a quadrilateral in the same order, with no three points on a single line.
+    pub fn new(from: &[Point; 4], to: &[Point; 4]) -> Perspective {
+        Perspective {
+            matrix: multiply(&adjoint(&square_to_quad(from)), &square_to_quad(to)),
+        }
+    }
+
+    /// Map a point through the transform
+    pub fn map(&self, p: Point) -> Point {
+        let m = &self.matrix;
+        let w = m[0][2] * p.x + m[1][2] * p.y + m[2][2];
+
+        Point {
+            x: (m[0][0] * p.x + m[1][0] * p.y + m[2][0]) / w,
+            y: (m[0][1] * p.x + m[1][1] * p.y + m[2][1]) / w,
+        }
+    }
+}
+
+// Transform from the unit square (0, 0), (1, 0), (1, 1), (0, 1) to the quadrilateral, as described by Heckbert
+fn square_to_quad(p: &[Point; 4]) -> [[f64; 3]; 3] {
+    let dx3 = p[0].x - p[1].x + p[2].x - p[3].x;
+    let dy3 = p[0].y - p[1].y + p[2].y - p[3].y;
+
+    if dx3 == 0.0 && dy3 == 0.0 {
+        // Parallelogram, the transform is affine
+        return [
+            [p[1].x - p[0].x, p[1].y - p[0].y, 0.0],
+            [p[2].x - p[1].x, p[2].y - p[1].y, 0.0],
+            [p[0].x, p[0].y, 1.0],
+        ];
+    }
+
+    let dx1 = p[1].x - p[2].x;
+    let dx2 = p[3].x - p[2].x;
+    let dy1 = p[1].y - p[2].y;
+    let dy2 = p[3].y - p[2].y;
+
+    let denominator = dx1 * dy2 - dx2 * dy1;
+    let a13 = (dx3 * dy2 - dx2 * dy3) / denominator;
+    let a23 = (dx1 * dy3 - dx3 * dy1) / denominator;
+
+    [
+        [
+            p[1].x - p[0].x + a13 * p[1].x,
+            p[1].y - p[0].y + a13 * p[1].y,
+            a13,
+        ],
+        [
+            p[3].x - p[0].x + a23 * p[3].x,
+            p[3].y - p[0].y + a23 * p[3].y,
+            a23,
+        ],
+        [p[0].x, p[0].y, 1.0],
+    ]
+}
+
+// Adjoint of the matrix, which is the inverse up to a scalar factor and therefore the inverse homography
+fn adjoint(m: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mut adjoint = [[0.0; 3]; 3];
+
+    for (i, row) in adjoint.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            let (r1, r2) = ((j + 1) % 3, (j + 2) % 3);
+            let (c1, c2) = ((i + 1) % 3, (i + 2) % 3);
+
+            *value = m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1];
+        }
+    }
+
+    adjoint
+}
+
+fn multiply(a: &[[f64; 3]; 3], b: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mut product = [[0.0; 3]; 3];
+
+    for (i, row) in product.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+
+    product
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(expected: Point, actual: Point) {
+        assert!(
+            (expected.x - actual.x).abs() < 1e-9 && (expected.y - actual.y).abs() < 1e-9,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    pub fn test_corners() {
+        let from = [
+            Point { x: 3.0, y: 3.0 },
+            Point { x: 21.0, y: 3.0 },
+            Point { x: 18.0, y: 18.0 },
+            Point { x: 3.0, y: 21.0 },
+        ];
+        let to = [
+            Point { x: 50.0, y: 40.0 },
+            Point { x: 250.0, y: 70.0 },
+            Point { x: 210.0, y: 230.0 },
+            Point { x: 30.0, y: 260.0 },
+        ];
+
+        let perspective = Perspective::new(&from, &to);
+
+        for (f, t) in from.iter().zip(to.iter()) {
+            assert_close(*t, perspective.map(*f));
+        }
+    }
+
+    #[test]
+    pub fn test_affine() {
+        let from = [
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 1.0, y: 0.0 },
+            Point { x: 1.0, y: 1.0 },
+            Point { x: 0.0, y: 1.0 },
+        ];
+        let to = [
+            Point { x: 10.0, y: 10.0 },
+            Point { x: 30.0, y: 15.0 },
+            Point { x: 25.0, y: 35.0 },
+            Point { x: 5.0, y: 30.0 },
+        ];
+
+        let perspective = Perspective::new(&from, &to);
+
+        assert_close(
+            Point { x: 17.5, y: 22.5 },
+            perspective.map(Point { x: 0.5, y: 0.5 }),
+        );
+    }
+
+    #[test]
+    pub fn test_straight_lines() {
+        // Points on a line in the grid stay on a line in the image
+        let from = [
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 10.0, y: 0.0 },
+            Point { x: 10.0, y: 10.0 },
+            Point { x: 0.0, y: 10.0 },
+        ];
+        let to = [
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 100.0, y: 20.0 },
+            Point { x: 90.0, y: 80.0 },
+            Point { x: 10.0, y: 100.0 },
+        ];
+
+        let perspective = Perspective::new(&from, &to);
+
+        let a = perspective.map(Point { x: 0.0, y: 5.0 });
+        let b = perspective.map(Point { x: 5.0, y: 5.0 });
+        let c = perspective.map(Point { x: 10.0, y: 5.0 });
+
+        let cross = (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+        assert!(cross.abs() < 1e-6);
+    }
+}
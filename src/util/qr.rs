@@ -53,15 +53,60 @@ pub struct QRData {
 
     /// Side in pixels of the QR square
     pub side: u32,
+
+    /// Version of the QR Code as estimated from the distance between the finder patterns.
+    /// For versions 7 and up this can differ from `version`, which is read from the version information in the QR Code itself.
+    pub estimated_version: u32,
+
+    /// Confidence in every module, in the same order as `data`. 255 means the module is certainly dark or light,
+    /// down to 0 for a module that could just as well be either, or could not be sampled at all. Codewords containing
+    /// modules of low confidence are treated as erasures during error correction.
+    pub confidence: Vec<u8>,
 }
 
 impl QRData {
     /// Create a new QRData object with the provided data and version. `side` will be calculated automatically.
+    ///
+    /// All modules are taken to be sampled with full confidence.
     pub fn new(data: Vec<u8>, version: u32) -> QRData {
         QRData {
+            confidence: vec![255; data.len()],
             data,
             version,
             side: 4 * version + 17,
+            estimated_version: version,
+        }
+    }
+
+    /// Create a new QRData object for a Micro QR Code with the provided data and version. `side` will be calculated automatically.
+    ///
+    /// Micro QR versions M1 to M4 are represented as versions 1 to 4
+    pub fn new_micro(data: Vec<u8>, version: u32) -> QRData {
+        QRData {
+            confidence: vec![255; data.len()],
+            data,
+            version,
+            side: 2 * version + 9,
+            estimated_version: version,
+        }
+    }
+
+    /// Swap the rows and columns of the QR Code, which turns a QR Code that was read from its mirror image
+    /// back into the original
+    pub fn transpose(&self) -> QRData {
+        let side = self.side as usize;
+        let transpose = |values: &[u8]| {
+            (0..side * side)
+                .map(|i| values[(i % side) * side + i / side])
+                .collect()
+        };
+
+        QRData {
+            data: transpose(&self.data),
+            confidence: transpose(&self.confidence),
+            version: self.version,
+            side: self.side,
+            estimated_version: self.estimated_version,
         }
     }
 }
@@ -79,6 +124,110 @@ impl Index<[u32; 2]> for QRData {
     }
 }
 
+/// Width and height in modules of every rMQR version, indexed by version
+///
+/// The versions are ordered by height first, then by width, so version 0 is R7x43 and version 31 is R17x139
+pub const RMQR_SIZES: [(u32, u32); 32] = [
+    (43, 7), // 0: R7x43
+    (59, 7), // 1: R7x59
+    (77, 7), // 2: R7x77
+    (99, 7), // 3: R7x99
+    (139, 7), // 4: R7x139
+    (43, 9), // 5: R9x43
+    (59, 9), // 6: R9x59
+    (77, 9), // 7: R9x77
+    (99, 9), // 8: R9x99
+    (139, 9), // 9: R9x139
+    (27, 11), // 10: R11x27
+    (43, 11), // 11: R11x43
+    (59, 11), // 12: R11x59
+    (77, 11), // 13: R11x77
+    (99, 11), // 14: R11x99
+    (139, 11), // 15: R11x139
+    (27, 13), // 16: R13x27
+    (43, 13), // 17: R13x43
+    (59, 13), // 18: R13x59
+    (77, 13), // 19: R13x77
+    (99, 13), // 20: R13x99
+    (139, 13), // 21: R13x139
+    (43, 15), // 22: R15x43
+    (59, 15), // 23: R15x59
+    (77, 15), // 24: R15x77
+    (99, 15), // 25: R15x99
+    (139, 15), // 26: R15x139
+    (43, 17), // 27: R17x43
+    (59, 17), // 28: R17x59
+    (77, 17), // 29: R17x77
+    (99, 17), // 30: R17x99
+    (139, 17), // 31: R17x139
+];
+
+/// rMQR Data extracted from the source image
+///
+/// Same as QRData, but for the rectangular rMQR symbols which have a separate width and height
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// use bardecoder::util::qr::RMQRData;
+///
+/// let mut data = vec![0; 43 * 7];
+/// data[43 * 7 - 1] = 255;
+/// let rmqr_data = RMQRData::new(data, 0);
+///
+/// assert_eq!(rmqr_data.width, 43);
+/// assert_eq!(rmqr_data.height, 7);
+/// assert_eq!(rmqr_data[[0, 0]], 1);
+/// assert_eq!(rmqr_data[[42, 6]], 0);
+/// ```
+#[derive(Debug)]
+pub struct RMQRData {
+    /// rMQR Pixel Data in width x height pixels, stored in row major order. Using the provided index will convert into 1's and 0's.
+    pub data: Vec<u8>,
+
+    /// Version of the rMQR Code, see [`RMQR_SIZES`]
+    ///
+    /// [`RMQR_SIZES`]: constant.RMQR_SIZES.html
+    pub version: u32,
+
+    /// Width in pixels of the rMQR rectangle
+    pub width: u32,
+
+    /// Height in pixels of the rMQR rectangle
+    pub height: u32,
+}
+
+impl RMQRData {
+    /// Create a new RMQRData object with the provided data and version. `width` and `height` will be looked up automatically.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the version is larger than 31
+    pub fn new(data: Vec<u8>, version: u32) -> RMQRData {
+        let (width, height) = RMQR_SIZES[version as usize];
+
+        RMQRData {
+            data,
+            version,
+            width,
+            height,
+        }
+    }
+}
+
+impl Index<[u32; 2]> for RMQRData {
+    type Output = u8;
+
+    fn index(&self, index: [u32; 2]) -> &u8 {
+        let pixel = self.data[index[1] as usize * self.width as usize + index[0] as usize];
+        if pixel == 0 {
+            &1
+        } else {
+            &0
+        }
+    }
+}
+
 /// Location of the QR Code in the source image, in pixels
 #[derive(Debug)]
 pub struct QRLocation {
@@ -98,12 +247,66 @@ pub struct QRLocation {
     pub version: u32,
 }
 
+/// Location of the Micro QR Code in the source image, in pixels
+///
+/// Micro QR Codes only have a single finder pattern, the other corners are located using the timing patterns.
+#[derive(Debug)]
+pub struct MicroQRLocation {
+    /// Center of the finder pattern, in pixels, relative to the Micro QR Code
+    pub top_left: Point,
+
+    /// Center of the last module of the horizontal timing pattern, in pixels, relative to the Micro QR Code
+    pub top_right: Point,
+
+    /// Center of the last module of the vertical timing pattern, in pixels, relative to the Micro QR Code
+    pub bottom_left: Point,
+
+    /// Module size in pixels
+    pub module_size: f64,
+
+    /// Version of the Micro QR Code, 1 to 4 for M1 to M4
+    pub version: u32,
+}
+
+/// Location of the rMQR Code in the source image, in pixels
+///
+/// rMQR Codes have a finder pattern in the top left corner, the other corners are located by following the edges of the symbol.
+#[derive(Debug)]
+pub struct RMQRLocation {
+    /// Center of the finder pattern, in pixels, relative to the rMQR Code
+    pub top_left: Point,
+
+    /// Center of the last module of the top edge, in pixels, relative to the rMQR Code
+    pub top_right: Point,
+
+    /// Center of the last module of the left edge, in pixels, relative to the rMQR Code
+    pub bottom_left: Point,
+
+    /// Module size in pixels
+    pub module_size: f64,
+
+    /// Width of the rMQR Code in modules
+    pub width: u32,
+
+    /// Height of the rMQR Code in modules
+    pub height: u32,
+
+    /// Version of the rMQR Code, see [`RMQR_SIZES`]
+    ///
+    /// [`RMQR_SIZES`]: constant.RMQR_SIZES.html
+    pub version: u32,
+}
+
 /// Information about the decoded QR Code
 #[derive(Debug, PartialEq, Eq)]
 pub struct QRInfo {
     /// Version of the QR Code, 1 being the smallest, 40 the largest
     pub version: u32,
 
+    /// Version of the QR Code as estimated from the distance between the finder patterns. If this differs from `version`,
+    /// the estimate was corrected by the version information in the QR Code.
+    pub estimated_version: u32,
+
     /// Error correction level of the QR Code
     pub ec_level: ECLevel,
 
@@ -112,10 +315,128 @@ pub struct QRInfo {
 
     /// Number of bits of information that were incorrect. This can be both in data codewords or error correction codewords since the algorithm doesn't distinguish between the two.
     pub errors: u32,
+
+    /// ECI assignment numbers encountered in the QR Code, in order. Each one switches the character set used to interpret
+    /// the byte segments following it. Empty if the QR Code does not contain any ECI designators.
+    pub eci: Vec<u32>,
+
+    /// Structured append header if this QR Code is one of a series of linked symbols, see [`Reassembler`]
+    ///
+    /// [`Reassembler`]: ../struct.Reassembler.html
+    pub structured_append: Option<StructuredAppend>,
+
+    /// FNC1 mode of the QR Code, if any. In FNC1 mode the data is formatted according to the GS1 or an industry specific standard.
+    pub fnc1: Option<FNC1>,
+
+    /// Whether the QR Code was mirrored, such as when it is printed on the back of a transparent label.
+    /// Mirrored QR Codes are read by swapping their rows and columns.
+    pub mirrored: bool,
+}
+
+impl QRInfo {
+    /// Symbology identifier as defined by ISO/IEC 15424, indicating the presence of ECI designators and FNC1 mode
+    ///
+    /// For FNC1 in second position the application indicator is appended as well, either as two digits or as a single letter,
+    /// which is how it is transmitted in front of the data
+    ///
+    /// # Example
+    /// ```
+    /// # extern crate bardecoder;
+    /// use bardecoder::util::qr::{ECLevel, QRInfo, FNC1};
+    ///
+    /// let info = QRInfo {
+    ///     version: 1,
+    ///     estimated_version: 1,
+    ///     ec_level: ECLevel::LOW,
+    ///     total_data: 152,
+    ///     errors: 0,
+    ///     eci: vec![],
+    ///     structured_append: None,
+    ///     fnc1: Some(FNC1::Second(37)),
+    ///     mirrored: false,
+    /// };
+    ///
+    /// assert_eq!(info.symbology_identifier(), "]Q537");
+    /// ```
+    pub fn symbology_identifier(&self) -> String {
+        let eci = if self.eci.is_empty() { 0 } else { 1 };
+
+        match self.fnc1 {
+            None => format!("]Q{}", 1 + eci),
+            Some(FNC1::First) => format!("]Q{}", 3 + eci),
+            Some(FNC1::Second(ai)) => match ai {
+                0..=99 => format!("]Q{}{:02}", 5 + eci, ai),
+                165..=190 | 197..=222 => format!("]Q{}{}", 5 + eci, (ai - 100) as char),
+                _ => format!("]Q{}", 5 + eci),
+            },
+        }
+    }
+}
+
+/// FNC1 mode of a QR Code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FNC1 {
+    /// FNC1 in first position, the data is formatted according to the GS1 General Specifications
+    First,
+
+    /// FNC1 in second position, the data is formatted according to an industry standard identified by the application indicator
+    Second(u8),
+}
+
+/// Structured append header, linking up to 16 QR Codes that together make up a single message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructuredAppend {
+    /// Position of this QR Code in the series, starting at 0
+    pub index: u8,
+
+    /// Total number of QR Codes in the series
+    pub total: u8,
+
+    /// Parity of the complete message, identical for every QR Code in the series
+    pub parity: u8,
+
+    /// Parity of the data in this QR Code alone, before any conversion to text.
+    /// The part parities of all QR Codes in the series combine into `parity`.
+    pub part_parity: u8,
+}
+
+/// A single segment of data in a QR Code
+///
+/// QR Codes split their data in segments, each encoded in the mode that is most efficient for the characters it contains
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QRSegment {
+    /// Mode the segment was encoded in
+    pub mode: QRMode,
+
+    /// Number of characters in the segment as read from the character count indicator. For byte segments this is the number of bytes.
+    pub count: u32,
+
+    /// Offset in bits of the start of the segment's mode indicator, relative to the start of the data codewords
+    pub offset: usize,
+
+    /// Raw bytes of the segment, the same as [`QRDecoderBytes`] returns for it
+    ///
+    /// [`QRDecoderBytes`]: ../../decode/struct.QRDecoderBytes.html
+    pub bytes: Vec<u8>,
+
+    /// Decoded contents of the segment, or the error if its bytes could not be converted to text
+    pub value: Result<String, QRError>,
+}
+
+/// Data encoding mode of a [`QRSegment`]
+///
+/// [`QRSegment`]: struct.QRSegment.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum QRMode {
+    NUMERIC,
+    ALPHANUMERIC,
+    BYTE,
+    KANJI,
 }
 
 /// Error Correction level of the QR Code
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(missing_docs)]
 pub enum ECLevel {
     LOW,
@@ -0,0 +1,325 @@
+use crate::util::qr::{QRError, QRInfo, StructuredAppend};
+
+/// Contents of a QR Code that can be joined with the contents of the other QR Codes of a structured append series
+///
+/// Implemented for the text returned by [`QRDecoderWithInfo`] and for the bytes returned by [`QRDecoderBytesWithInfo`]
+///
+/// [`QRDecoderWithInfo`]: ../decode/struct.QRDecoderWithInfo.html
+/// [`QRDecoderBytesWithInfo`]: ../decode/struct.QRDecoderBytesWithInfo.html
+pub trait SeriesPart: Clone + Default + PartialEq {
+    /// Append the contents of the next part
+    fn append(&mut self, next: &Self);
+}
+
+impl SeriesPart for String {
+    fn append(&mut self, next: &String) {
+        self.push_str(next);
+    }
+}
+
+impl SeriesPart for Vec<u8> {
+    fn append(&mut self, next: &Vec<u8>) {
+        self.extend_from_slice(next);
+    }
+}
+
+/// Join the contents of QR Codes linked together with a structured append header
+///
+/// The parts can be added in any order and may come from several calls to [`Decoder::decode`], possibly on different images.
+/// Adding the same part twice is allowed as long as its contents are identical.
+/// Once all parts are in, the parity of their data is checked against the parity in the structured append header.
+///
+/// The parts are Strings by default, but binary series can be joined as well by adding `Vec<u8>` parts, see [`SeriesPart`].
+///
+/// # Example
+/// ```
+/// # extern crate bardecoder;
+/// # use bardecoder::util::qr::{ECLevel, QRInfo, StructuredAppend};
+/// use bardecoder::util::Reassembler;
+///
+/// # fn info(index: u8, part_parity: u8) -> QRInfo {
+/// #     QRInfo {
+/// #         version: 1,
+/// #         estimated_version: 1,
+/// #         ec_level: ECLevel::LOW,
+/// #         total_data: 152,
+/// #         errors: 0,
+/// #         eci: vec![],
+/// #         structured_append: Some(StructuredAppend { index, total: 2, parity: 0x20, part_parity }),
+/// #         fnc1: None,
+/// #         mirrored: false,
+/// #     }
+/// # }
+/// let mut reassembler = Reassembler::new();
+///
+/// reassembler.add(String::from("World"), &info(1, 0x42)).unwrap();
+/// assert_eq!(vec![0], reassembler.missing());
+///
+/// reassembler.add(String::from("Hello "), &info(0, 0x62)).unwrap();
+/// assert_eq!(String::from("Hello World"), reassembler.result().unwrap());
+/// ```
+///
+/// [`Decoder::decode`]: ../struct.Decoder.html#method.decode
+/// [`SeriesPart`]: trait.SeriesPart.html
+pub struct Reassembler<T = String> {
+    header: Option<StructuredAppend>,
+    parts: Vec<Option<(T, u8)>>,
+}
+
+impl<T: SeriesPart> Reassembler<T> {
+    /// Construct a new, empty, Reassembler
+    pub fn new() -> Reassembler<T> {
+        Reassembler {
+            header: None,
+            parts: vec![],
+        }
+    }
+
+    /// Add the decoded contents of a single QR Code and its accompanying [`QRInfo`]
+    ///
+    /// Will return an error if the QR Code does not have a valid structured append header,
+    /// or if it does not belong to the same series as the previously added QR Codes
+    ///
+    /// [`QRInfo`]: qr/struct.QRInfo.html
+    pub fn add(&mut self, data: T, info: &QRInfo) -> Result<(), QRError> {
+        let sa = info.structured_append.ok_or(QRError {
+            msg: String::from("QR Code is not part of a structured append series"),
+        })?;
+
+        if sa.index >= sa.total {
+            return Err(QRError {
+                msg: format!(
+                    "Index {} is out of range for a series of {}",
+                    sa.index, sa.total
+                ),
+            });
+        }
+
+        if let Some(header) = self.header {
+            if header.parity != sa.parity {
+                return Err(QRError {
+                    msg: format!(
+                        "Parity {:08b} does not match parity {:08b} of the series",
+                        sa.parity, header.parity
+                    ),
+                });
+            }
+
+            if header.total != sa.total {
+                return Err(QRError {
+                    msg: format!(
+                        "Total of {} QR Codes does not match total {} of the series",
+                        sa.total, header.total
+                    ),
+                });
+            }
+        } else {
+            self.header = Some(sa);
+            self.parts = vec![None; sa.total as usize];
+        }
+
+        let part = &mut self.parts[sa.index as usize];
+
+        match part {
+            Some((existing, part_parity))
+                if *existing != data || *part_parity != sa.part_parity =>
+            {
+                Err(QRError {
+                    msg: format!("Conflicting contents for part {} of the series", sa.index),
+                })
+            }
+            _ => {
+                *part = Some((data, sa.part_parity));
+                Ok(())
+            }
+        }
+    }
+
+    /// Indices of the parts that have not been added yet. Empty if no part at all has been added, since the total is still unknown
+    pub fn missing(&self) -> Vec<u8> {
+        self.parts
+            .iter()
+            .enumerate()
+            .filter(|(_, part)| part.is_none())
+            .map(|(i, _)| i as u8)
+            .collect()
+    }
+
+    /// True if at least one part was added and none are missing
+    pub fn is_complete(&self) -> bool {
+        self.header.is_some() && self.missing().is_empty()
+    }
+
+    /// Join all parts in order. Will return an error listing the missing parts if the series is not complete yet,
+    /// or if the parity of the joined data does not match the parity of the series
+    pub fn result(&self) -> Result<T, QRError> {
+        let header = self.header.ok_or(QRError {
+            msg: String::from("No parts of the series have been added"),
+        })?;
+
+        let missing = self.missing();
+        if !missing.is_empty() {
+            return Err(QRError {
+                msg: format!(
+                    "Missing parts {:?} of a series of {}",
+                    missing,
+                    self.parts.len()
+                ),
+            });
+        }
+
+        let parity = self
+            .parts
+            .iter()
+            .flatten()
+            .fold(0, |parity, (_, part_parity)| parity ^ part_parity);
+
+        if parity != header.parity {
+            return Err(QRError {
+                msg: format!(
+                    "Parity {:08b} of the joined data does not match parity {:08b} of the series",
+                    parity, header.parity
+                ),
+            });
+        }
+
+        let mut result = T::default();
+
+        for (data, _) in self.parts.iter().flatten() {
+            result.append(data);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::util::qr::ECLevel;
+
+    fn info(data: &str, index: u8, total: u8, parity: u8) -> QRInfo {
+        info_bytes(data.as_bytes(), index, total, parity)
+    }
+
+    fn info_bytes(data: &[u8], index: u8, total: u8, parity: u8) -> QRInfo {
+        QRInfo {
+            version: 1,
+            estimated_version: 1,
+            ec_level: ECLevel::LOW,
+            total_data: 152,
+            errors: 0,
+            eci: vec![],
+            structured_append: Some(StructuredAppend {
+                index,
+                total,
+                parity,
+                part_parity: data.iter().fold(0, |parity, b| parity ^ b),
+            }),
+            fnc1: None,
+            mirrored: false,
+        }
+    }
+
+    #[test]
+    pub fn test_out_of_order() {
+        let mut reassembler = Reassembler::new();
+
+        reassembler
+            .add(String::from("C"), &info("C", 2, 3, 0x40))
+            .unwrap();
+        reassembler
+            .add(String::from("A"), &info("A", 0, 3, 0x40))
+            .unwrap();
+
+        assert!(!reassembler.is_complete());
+        assert_eq!(vec![1], reassembler.missing());
+        assert!(reassembler.result().is_err());
+
+        reassembler
+            .add(String::from("B"), &info("B", 1, 3, 0x40))
+            .unwrap();
+
+        assert!(reassembler.is_complete());
+        assert_eq!(Ok(String::from("ABC")), reassembler.result());
+    }
+
+    #[test]
+    pub fn test_mismatch() {
+        let mut reassembler = Reassembler::new();
+
+        reassembler
+            .add(String::from("A"), &info("A", 0, 3, 0x40))
+            .unwrap();
+
+        assert!(reassembler
+            .add(String::from("B"), &info("B", 1, 3, 0x41))
+            .is_err());
+        assert!(reassembler
+            .add(String::from("B"), &info("B", 1, 4, 0x40))
+            .is_err());
+
+        // duplicates are fine, as long as the contents are the same
+        assert!(reassembler
+            .add(String::from("A"), &info("A", 0, 3, 0x40))
+            .is_ok());
+        assert!(reassembler
+            .add(String::from("X"), &info("X", 0, 3, 0x40))
+            .is_err());
+
+        let mut no_header = info("B", 1, 3, 0x40);
+        no_header.structured_append = None;
+        assert!(reassembler.add(String::from("B"), &no_header).is_err());
+
+        assert_eq!(vec![1, 2], reassembler.missing());
+    }
+
+    #[test]
+    pub fn test_index_out_of_range() {
+        let mut reassembler = Reassembler::new();
+
+        assert!(reassembler
+            .add(String::from("D"), &info("D", 3, 3, 0x40))
+            .is_err());
+
+        // The invalid part does not start a series
+        reassembler
+            .add(String::from("A"), &info("A", 0, 2, 0x40))
+            .unwrap();
+        assert_eq!(vec![1], reassembler.missing());
+    }
+
+    #[test]
+    pub fn test_bytes() {
+        let mut reassembler = Reassembler::new();
+
+        reassembler
+            .add(vec![0x80, 0x01], &info_bytes(&[0x80, 0x01], 1, 2, 0x7E))
+            .unwrap();
+        reassembler
+            .add(vec![0x00, 0xFF], &info_bytes(&[0x00, 0xFF], 0, 2, 0x7E))
+            .unwrap();
+
+        assert_eq!(Ok(vec![0x00, 0xFF, 0x80, 0x01]), reassembler.result());
+    }
+
+    #[test]
+    pub fn test_parity() {
+        let mut reassembler = Reassembler::new();
+
+        // A part from another series that happens to share the same header
+        reassembler
+            .add(String::from("A"), &info("A", 0, 3, 0x40))
+            .unwrap();
+        reassembler
+            .add(String::from("B"), &info("B", 1, 3, 0x40))
+            .unwrap();
+        reassembler
+            .add(String::from("D"), &info("D", 2, 3, 0x40))
+            .unwrap();
+
+        assert!(reassembler.is_complete());
+        assert!(reassembler.result().is_err());
+    }
+}